@@ -1,388 +0,0 @@
---ݿ
-CREATE DATABASE jz_db;
-
--- ûMySQL 5.7ϰ汾
-
-CREATE USER 'jz_db' @'localhost' IDENTIFIED BY 'cXMYpa2fwkFx3447';
-
--- ûȨޣȨޣ
-GRANT ALL PRIVILEGES ON jz_db.* TO 'jz_db' @'localhost';
-
--- ֻضȨ
--- GRANT SELECT, INSERT, UPDATE, DELETE ON database_name.* TO 'new_user'@'localhost';
-
--- ˢȨ
-FLUSH PRIVILEGES;
--- jz_db:cXMYpa2fwkFx3447@127.0.0.1:3306/jz_db
-
-use jz_db;
--- ============================================================
--- 1. ûر
--- ============================================================
-
-CREATE TABLE users (
-    user_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ûID',
-    username VARCHAR(50) UNIQUE NOT NULL COMMENT 'û',
-    password_hash VARCHAR(255) NOT NULL COMMENT 'ϣ',
-    email VARCHAR(100) UNIQUE NOT NULL COMMENT '',
-    phone VARCHAR(20) UNIQUE NOT NULL COMMENT 'ֻ',
-    user_type ENUM('customer', 'worker', 'admin') DEFAULT 'customer' COMMENT 'û',
-    avatar_url VARCHAR(500) COMMENT 'ͷURL',
-    real_name VARCHAR(50) COMMENT 'ʵ',
-    is_verified BOOLEAN DEFAULT FALSE COMMENT 'Ƿʵ֤',
-    balance DECIMAL(10, 2) DEFAULT 0.00 COMMENT '˻',
-    status ENUM(
-        'active',
-        'inactive',
-        'banned'
-    ) DEFAULT 'active' COMMENT '˻״̬',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-    INDEX idx_phone_email (phone, email),
-    INDEX idx_status (status)
-) COMMENT = 'û';
-
-CREATE TABLE user_addresses (
-    address_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ַID',
-    user_id INT NOT NULL COMMENT 'ûID',
-    contact_name VARCHAR(50) NOT NULL COMMENT 'ϵ',
-    contact_phone VARCHAR(20) NOT NULL COMMENT 'ϵ绰',
-    province VARCHAR(50) NOT NULL COMMENT 'ʡ',
-    city VARCHAR(50) NOT NULL COMMENT '',
-    district VARCHAR(50) NOT NULL COMMENT '',
-    street_address VARCHAR(200) NOT NULL COMMENT 'ϸַ',
-    is_default BOOLEAN DEFAULT FALSE COMMENT 'ǷĬ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
-    INDEX idx_user (user_id),
-    INDEX idx_user_default (user_id, is_default)
-) COMMENT = 'ûַ';
-
--- ============================================================
--- 2. 
--- ============================================================
-
-CREATE TABLE service_categories (
-    category_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ID',
-    category_name VARCHAR(50) NOT NULL COMMENT '',
-    parent_id INT NULL COMMENT 'ID',
-    icon_url VARCHAR(500) COMMENT 'ͼURL',
-    is_active BOOLEAN DEFAULT TRUE COMMENT 'Ƿ',
-    sort_order INT DEFAULT 0 COMMENT '',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (parent_id) REFERENCES service_categories (category_id),
-    INDEX idx_parent (parent_id)
-) COMMENT = '';
-
-CREATE TABLE services (
-    service_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ID',
-    category_id INT NOT NULL COMMENT 'ID',
-    service_name VARCHAR(100) NOT NULL COMMENT '',
-    description TEXT COMMENT '',
-    base_price DECIMAL(8, 2) NOT NULL COMMENT '۸',
-    unit ENUM(
-        'hour',
-        'square_meter',
-        'item',
-        'fixed'
-    ) NOT NULL COMMENT 'Ƽ۵λ',
-    min_duration INT DEFAULT 1 COMMENT 'Сʱ/',
-    max_duration INT DEFAULT 8 COMMENT 'ʱ/',
-    is_active BOOLEAN DEFAULT TRUE COMMENT 'Ƿ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (category_id) REFERENCES service_categories (category_id),
-    INDEX idx_category (category_id),
-    INDEX idx_active (is_active)
-) COMMENT = 'Ŀ';
-
-CREATE TABLE service_addons (
-    addon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ID',
-    service_id INT NOT NULL COMMENT 'ID',
-    addon_name VARCHAR(100) NOT NULL COMMENT '',
-    addon_price DECIMAL(8, 2) NOT NULL COMMENT '۸',
-    is_active BOOLEAN DEFAULT TRUE COMMENT 'Ƿ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (service_id) REFERENCES services (service_id) ON DELETE CASCADE,
-    INDEX idx_service (service_id)
-) COMMENT = '񸽼';
-
--- ============================================================
--- 3. ر
--- ============================================================
-
-CREATE TABLE worker_profiles (
-    worker_id INT PRIMARY KEY COMMENT 'ԱID',
-    service_category_id INT NOT NULL COMMENT 'ID',
-    hourly_rate DECIMAL(8, 2) NOT NULL COMMENT 'ʱн',
-    bio TEXT COMMENT '˼',
-    skills JSON COMMENT 'ܱǩ',
-    service_area JSON COMMENT '',
-    total_orders INT DEFAULT 0 COMMENT 'ܶ',
-    completed_orders INT DEFAULT 0 COMMENT 'ɶ',
-    avg_rating DECIMAL(3, 2) DEFAULT 0.00 COMMENT 'ƽ',
-    is_available BOOLEAN DEFAULT TRUE COMMENT 'Ƿɽӵ',
-    max_daily_orders INT DEFAULT 3 COMMENT 'ÿӵ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-    FOREIGN KEY (worker_id) REFERENCES users (user_id) ON DELETE CASCADE,
-    FOREIGN KEY (service_category_id) REFERENCES service_categories (category_id),
-    INDEX idx_category_rating (
-        service_category_id,
-        avg_rating DESC
-    ),
-    INDEX idx_available (is_available)
-) COMMENT = 'Ա';
-
--- ============================================================
--- 4. Żȯ
--- ============================================================
-
-CREATE TABLE coupons (
-    coupon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ŻȯID',
-    coupon_code VARCHAR(20) UNIQUE NOT NULL COMMENT 'Żȯ',
-    coupon_name VARCHAR(100) NOT NULL COMMENT 'Żȯ',
-    discount_type ENUM(
-        'percentage',
-        'fixed',
-        'service'
-    ) NOT NULL COMMENT 'ۿ',
-    discount_value DECIMAL(10, 2) NOT NULL COMMENT 'ۿֵ',
-    min_order_amount DECIMAL(10, 2) DEFAULT 0.00 COMMENT 'Ͷ',
-    applicable_services JSON COMMENT '÷',
-    valid_from DATE NOT NULL COMMENT 'Чڿʼ',
-    valid_until DATE NOT NULL COMMENT 'Чڽֹ',
-    usage_limit INT DEFAULT 1 COMMENT 'ʹô',
-    used_count INT DEFAULT 0 COMMENT 'ʹô',
-    is_active BOOLEAN DEFAULT TRUE COMMENT 'Ƿ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    INDEX idx_code_validity (
-        coupon_code,
-        valid_until,
-        is_active
-    )
-) COMMENT = 'Żȯ';
-
--- ============================================================
--- 5. 
--- ============================================================
-
-CREATE TABLE orders (
-    order_id VARCHAR(20) PRIMARY KEY COMMENT '',
-    customer_id INT NOT NULL COMMENT 'ͻID',
-    worker_id INT NULL COMMENT 'ԱID',
-    address_id INT NOT NULL COMMENT 'ַID',
-    service_id INT NOT NULL COMMENT 'ID',
-    coupon_id INT NULL COMMENT 'ŻȯID',
-    service_date DATE NOT NULL COMMENT '',
-    time_slot ENUM(
-        'morning',
-        'afternoon',
-        'evening',
-        'full_day'
-    ) NOT NULL COMMENT 'ʱ',
-    duration DECIMAL(5, 1) NOT NULL COMMENT 'ʱ',
-    unit_price DECIMAL(8, 2) NOT NULL COMMENT '',
-    subtotal DECIMAL(10, 2) NOT NULL COMMENT 'Сƽ',
-    discount_amount DECIMAL(8, 2) DEFAULT 0.00 COMMENT 'Żݽ',
-    total_amount DECIMAL(10, 2) NOT NULL COMMENT 'ܽ',
-    payment_status ENUM('pending', 'paid', 'refunded') DEFAULT 'pending' COMMENT '֧״̬',
-    order_status ENUM(
-        'pending',
-        'confirmed',
-        'assigned',
-        'ongoing',
-        'completed',
-        'cancelled'
-    ) DEFAULT 'pending' COMMENT '״̬',
-    special_instructions TEXT COMMENT 'Ҫ',
-    cancellation_reason TEXT COMMENT 'ȡԭ',
-    scheduled_start_time DATETIME NULL COMMENT 'ƻʼʱ',
-    actual_start_time DATETIME NULL COMMENT 'ʵʿʼʱ',
-    actual_end_time DATETIME NULL COMMENT 'ʵʽʱ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-    FOREIGN KEY (customer_id) REFERENCES users (user_id),
-    FOREIGN KEY (worker_id) REFERENCES users (user_id),
-    FOREIGN KEY (address_id) REFERENCES user_addresses (address_id),
-    FOREIGN KEY (service_id) REFERENCES services (service_id),
-    FOREIGN KEY (coupon_id) REFERENCES coupons (coupon_id),
-    INDEX idx_customer_status (customer_id, order_status),
-    INDEX idx_worker_status (worker_id, order_status),
-    INDEX idx_date_status (service_date, order_status)
-) COMMENT = '';
-
--- ============================================================
--- 6. 
--- ============================================================
-
-CREATE TABLE order_addons (
-    order_addon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ID',
-    order_id VARCHAR(20) NOT NULL COMMENT 'ID',
-    addon_id INT NOT NULL COMMENT 'ID',
-    quantity INT DEFAULT 1 COMMENT '',
-    unit_price DECIMAL(8, 2) NOT NULL COMMENT '',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (order_id) REFERENCES orders (order_id) ON DELETE CASCADE,
-    FOREIGN KEY (addon_id) REFERENCES service_addons (addon_id),
-    INDEX idx_order (order_id)
-) COMMENT = '';
-
--- ============================================================
--- 7. ճ̣Ҫ orders
--- ============================================================
-
-CREATE TABLE worker_schedules (
-    schedule_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ճID',
-    worker_id INT NOT NULL COMMENT 'ԱID',
-    schedule_date DATE NOT NULL COMMENT 'Ű',
-    time_slot ENUM(
-        'morning',
-        'afternoon',
-        'evening',
-        'full_day'
-    ) NOT NULL COMMENT 'ʱ',
-    status ENUM(
-        'available',
-        'booked',
-        'unavailable'
-    ) DEFAULT 'available' COMMENT '״̬',
-    order_id VARCHAR(20) NULL COMMENT 'ID',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (worker_id) REFERENCES users (user_id) ON DELETE CASCADE,
-    FOREIGN KEY (order_id) REFERENCES orders (order_id),
-    UNIQUE KEY uk_worker_time (
-        worker_id,
-        schedule_date,
-        time_slot
-    ),
-    INDEX idx_worker_date (worker_id, schedule_date)
-) COMMENT = 'Աճ̱';
-
--- ============================================================
--- 8. ûŻȯ
--- ============================================================
-
-CREATE TABLE user_coupons (
-    user_coupon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ûŻȯID',
-    user_id INT NOT NULL COMMENT 'ûID',
-    coupon_id INT NOT NULL COMMENT 'ŻȯID',
-    order_id VARCHAR(20) NULL COMMENT 'ID',
-    is_used BOOLEAN DEFAULT FALSE COMMENT 'Ƿʹ',
-    used_at DATETIME NULL COMMENT 'ʹʱ',
-    expires_at DATE NOT NULL COMMENT 'ʱ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (user_id) REFERENCES users (user_id),
-    FOREIGN KEY (coupon_id) REFERENCES coupons (coupon_id),
-    FOREIGN KEY (order_id) REFERENCES orders (order_id),
-    UNIQUE KEY uk_user_coupon_order (user_id, coupon_id, order_id),
-    INDEX idx_user_unused (user_id, is_used, expires_at)
-) COMMENT = 'ûŻȯ';
-
--- ============================================================
--- 9. Ͷ
--- ============================================================
-
-CREATE TABLE reviews (
-    review_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ID',
-    order_id VARCHAR(20) UNIQUE NOT NULL COMMENT 'ID',
-    customer_id INT NOT NULL COMMENT 'ͻID',
-    worker_id INT NOT NULL COMMENT 'ԱID',
-    rating TINYINT NOT NULL COMMENT '(1-5)',
-    service_rating TINYINT COMMENT '',
-    punctuality_rating TINYINT COMMENT 'ʱ',
-    review_text TEXT COMMENT '',
-    is_anonymous BOOLEAN DEFAULT FALSE COMMENT 'Ƿ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (order_id) REFERENCES orders (order_id),
-    FOREIGN KEY (customer_id) REFERENCES users (user_id),
-    FOREIGN KEY (worker_id) REFERENCES users (user_id),
-    INDEX idx_worker_rating (worker_id, rating DESC),
-    INDEX idx_order (order_id)
-) COMMENT = '۱';
-
-CREATE TABLE complaints (
-    complaint_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ͶID',
-    order_id VARCHAR(20) NOT NULL COMMENT 'ID',
-    complainant_id INT NOT NULL COMMENT 'ͶID',
-    target_id INT NOT NULL COMMENT 'ͶID',
-    complaint_type ENUM(
-        'service',
-        'punctuality',
-        'attitude',
-        'other'
-    ) NOT NULL COMMENT 'Ͷ',
-    complaint_text TEXT NOT NULL COMMENT 'Ͷ',
-    status ENUM(
-        'pending',
-        'investigating',
-        'resolved',
-        'rejected'
-    ) DEFAULT 'pending' COMMENT '״̬',
-    resolved_at DATETIME NULL COMMENT 'ʱ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (order_id) REFERENCES orders (order_id),
-    FOREIGN KEY (complainant_id) REFERENCES users (user_id),
-    FOREIGN KEY (target_id) REFERENCES users (user_id),
-    INDEX idx_status (status),
-    INDEX idx_complainant (complainant_id)
-) COMMENT = 'Ͷ߱';
-
--- ============================================================
--- 10. ֧
--- ============================================================
-
-CREATE TABLE payments (
-    payment_id VARCHAR(30) PRIMARY KEY COMMENT '֧ID',
-    order_id VARCHAR(20) NOT NULL COMMENT 'ID',
-    user_id INT NOT NULL COMMENT 'ûID',
-    payment_method ENUM(
-        'wechat',
-        'alipay',
-        'balance',
-        'card'
-    ) NOT NULL COMMENT '֧ʽ',
-    payment_amount DECIMAL(10, 2) NOT NULL COMMENT '֧',
-    payment_status ENUM(
-        'pending',
-        'processing',
-        'success',
-        'failed'
-    ) DEFAULT 'pending' COMMENT '֧״̬',
-    thirdparty_trade_no VARCHAR(100) COMMENT '׺',
-    payment_time DATETIME NULL COMMENT '֧ʱ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (order_id) REFERENCES orders (order_id),
-    FOREIGN KEY (user_id) REFERENCES users (user_id),
-    INDEX idx_order_status (order_id, payment_status),
-    INDEX idx_user_time (user_id, payment_time DESC)
-) COMMENT = '֧¼';
-
--- ============================================================
--- 11. ϵͳ֪ͨ
--- ============================================================
-
-CREATE TABLE system_settings (
-    setting_key VARCHAR(50) PRIMARY KEY COMMENT 'ü',
-    setting_value TEXT COMMENT 'ֵ',
-    category VARCHAR(50) COMMENT '',
-    description TEXT COMMENT '',
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
-) COMMENT = 'ϵͳñ';
-
-CREATE TABLE notifications (
-    notification_id INT PRIMARY KEY AUTO_INCREMENT COMMENT 'ϢID',
-    user_id INT NOT NULL COMMENT 'ûID',
-    notification_type ENUM(
-        'order',
-        'system',
-        'promotion'
-    ) NOT NULL COMMENT 'Ϣ',
-    title VARCHAR(200) NOT NULL COMMENT '',
-    content TEXT NOT NULL COMMENT '',
-    related_id VARCHAR(50) COMMENT 'ҵID',
-    is_read BOOLEAN DEFAULT FALSE COMMENT 'ǷѶ',
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    FOREIGN KEY (user_id) REFERENCES users (user_id),
-    INDEX idx_user_unread (user_id, is_read),
-    INDEX idx_created (created_at DESC)
-) COMMENT = 'Ϣ֪ͨ';
\ No newline at end of file
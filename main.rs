@@ -4,18 +4,17 @@ use std::result::Result;
 
 use axum::{
     Router,
-    extract::Request,
+    extract::{DefaultBodyLimit, Request},
+    http::HeaderValue,
     middleware::{self, Next},
     response::Response,
     routing::get,
 };
-use jz::{log_init, init_db_pool, user_routes, service_routes, order_routes, init_app_state, AppState};
+use jz::config::{CorsConfig, DatabaseConfig};
+use jz::database::{migration, MigrateCommand};
+use jz::{log_init, connect_db, init_db_pool, AppState, Config, user_routes, service_routes, category_routes, order_routes, worker_routes, coupon_routes, complaint_routes, notification_routes, event_routes, auth_interceptor, request_id_interceptor};
 use std::time::Instant;
 use tower_http::services::ServeDir;
-// 添加Arc用于共享状态
-use std::sync::Arc;
-// 添加sqlx导入
-use sqlx::mysql::MySqlPool;
 // 添加cors支持
 use tower_http::cors::{CorsLayer, Any};
 
@@ -24,26 +23,42 @@ use tower_http::cors::{CorsLayer, Any};
 /// 使用 tokio 多线程运行时
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    log_init();
-
-
-    // 初始化应用状态
-    let app_state = init_app_state();
+    // 加载并校验配置，配置有误时直接退出
+    let config = Config::load()?;
+    log_init(&config.log);
+    tracing::info!("运行环境: {:?}", config.environment);
+
+    // jz migrate up/down/status 只执行迁移，不启动服务
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(command) = MigrateCommand::from_args(&args) {
+        let command = command.map_err(anyhow::Error::msg)?;
+        return run_migrate_command(&config.database, command).await;
+    }
 
-    // 初始化数据库连接池
-    let pool = init_db_pool(&app_state.database_url).await
+    // 初始化数据库连接池，数据库结构版本落后时拒绝启动
+    let pool = init_db_pool(&config.database).await
         .expect("Failed to initialize database pool");
 
-    // let _database_url = env::var("DATABASE_URL")?;
-    
-    
-    // 创建嵌套路由，并将数据库连接池作为状态传递给这些路由
+    // 初始化应用状态，业务服务在此构造一次，由各处理器共享
+    let app_state = AppState::new(pool, config);
+    let config = app_state.config.clone();
+
+    // 创建嵌套路由，并将应用状态传递给这些路由
     let api_routes = Router::new()
         .nest("/users", user_routes())
         .nest("/services", service_routes())
+        .nest("/categories", category_routes())
         .nest("/orders", order_routes())
-        .with_state(pool) // 为API路由提供数据库连接池
-        .route_layer(middleware::from_fn(auth_interceptor));
+        .nest("/workers", worker_routes())
+        .nest("/coupons", coupon_routes())
+        .nest("/complaints", complaint_routes())
+        .nest("/notifications", notification_routes())
+        .nest("/events", event_routes())
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth_interceptor))
+        .with_state(app_state) // 为API路由提供应用状态
+        .layer(DefaultBodyLimit::max(config.upload.max_body_bytes))
+        .layer(middleware::from_fn(logging_interceptor))
+        .layer(middleware::from_fn(request_id_interceptor)); // 最外层，日志与错误响应都能读到请求ID
 
     // 静态文件服务
     let serve_dir = ServeDir::new("assets").append_index_html_on_directories(true);
@@ -52,14 +67,10 @@ async fn main() -> anyhow::Result<()> {
         .route("/", get(|| async { "欢迎使用家政服务API" }))
         .nest("/api", api_routes) // 将所有 API 路由嵌套在 /api 路径下
         // 添加CORS支持
-        .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
-        .fallback_service(serve_dir)
-        .with_state(Arc::new(app_state));
+        .layer(cors_layer(&config.cors))
+        .fallback_service(serve_dir);
 
-    // 从环境变量读取主机和端口，默认为 0.0.0.0:8000
-    let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    let port = env::var("PORT").unwrap_or_else(|_| "8000".to_string());
-    let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
+    let addr: SocketAddr = format!("{}:{}", config.server.host, config.server.port).parse()?;
 
     println!("🚀 正在家政服务API服务器 {}", addr);
     // println!("📖 文档地址: http://{}:{}/docs", host, port);
@@ -72,6 +83,48 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// 按配置构造跨域中间件，未配置来源时允许任意来源
+fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let layer = CorsLayer::new().allow_methods(Any).allow_headers(Any);
+    if config.allows_any_origin() {
+        return layer.allow_origin(Any);
+    }
+    let origins: Vec<HeaderValue> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| match origin.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                tracing::warn!("忽略无效的跨域来源: {}", origin);
+                None
+            }
+        })
+        .collect();
+    layer.allow_origin(origins)
+}
+
+/// 执行迁移子命令
+async fn run_migrate_command(config: &DatabaseConfig, command: MigrateCommand) -> anyhow::Result<()> {
+    let pool = connect_db(config).await?;
+    match command {
+        MigrateCommand::Up => {
+            migration::run_migrations(&pool).await?;
+            println!("数据库迁移已全部执行");
+        }
+        MigrateCommand::Down => match migration::revert_last_migration(&pool).await? {
+            Some(version) => println!("已回滚迁移 {}", version),
+            None => println!("没有可回滚的迁移"),
+        },
+        MigrateCommand::Status => {
+            for status in migration::migration_status(&pool).await? {
+                let state = if status.applied { "已执行" } else { "未执行" };
+                println!("{:04} {:<32} {}", status.version, status.description, state);
+            }
+        }
+    }
+    Ok(())
+}
+
 // 不再需要独立的 handle_404 函数，因为 ServeDir 已经内置了 not_found_service 处理
 // 且我们通过 handle_error 处理了文件读取错误
 
@@ -81,10 +134,12 @@ async fn logging_interceptor(
 ) -> Result<Response, axum::http::StatusCode> {
     let start_time = Instant::now();
     let method = request.method().clone();
-    let uri = request.uri().clone();
+    // 只记录路径，查询参数中可能带有事件流令牌
+    let uri = request.uri().path().to_string();
 
     // 前置处理 - preHandle
-    tracing::info!("开始处理请求:logging {} {}", method, uri);
+    let request_id = jz::middleware::current_request_id().unwrap_or_default();
+    tracing::info!("开始处理请求:logging [{}] {} {}", request_id, method, uri);
 
     // 执行实际请求处理
     let response = next.run(request).await;
@@ -92,7 +147,8 @@ async fn logging_interceptor(
     // 后置处理 - postHandle
     let duration = start_time.elapsed();
     tracing::info!(
-        "请求完成:logging {} {} - 状态码: {} - 耗时: {:?}",
+        "请求完成:logging [{}] {} {} - 状态码: {} - 耗时: {:?}",
+        request_id,
         method,
         uri,
         response.status(),
@@ -101,26 +157,3 @@ async fn logging_interceptor(
 
     Ok(response)
 }
-
-// 404处理器
-
-// 只对特定路径应用拦截器
-async fn auth_interceptor(
-    request: Request,
-    next: Next,
-) -> Result<Response, axum::http::StatusCode> {
-    let path = request.uri().path();
-
-    // 对特定路径进行权限检查
-    if path.starts_with("/api") || path.starts_with("/orders") {
-        // 模拟权限验证
-        let auth_header = request.headers().get("authorization");
-        if auth_header.is_none() {
-            return Err(axum::http::StatusCode::UNAUTHORIZED);
-        }
-        tracing::info!("权限验证通过: {}", path);
-    }
-
-    let response = next.run(request).await;
-    Ok(response)
-}
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- 用户相关表
+
+DROP TABLE IF EXISTS user_addresses;
+DROP TABLE IF EXISTS users;
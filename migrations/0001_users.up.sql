@@ -0,0 +1,40 @@
+-- 用户相关表
+
+CREATE TABLE users (
+    user_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '用户ID',
+    username VARCHAR(50) UNIQUE NOT NULL COMMENT '用户名',
+    password_hash VARCHAR(255) NOT NULL COMMENT '密码哈希',
+    email VARCHAR(100) UNIQUE NOT NULL COMMENT '邮箱',
+    phone VARCHAR(20) UNIQUE NOT NULL COMMENT '手机号',
+    user_type ENUM('customer', 'worker', 'admin') DEFAULT 'customer' COMMENT '用户类型',
+    avatar_url VARCHAR(500) COMMENT '头像URL',
+    real_name VARCHAR(50) COMMENT '真实姓名',
+    is_verified BOOLEAN DEFAULT FALSE COMMENT '是否实名认证',
+    balance DECIMAL(10, 2) DEFAULT 0.00 COMMENT '账户余额',
+    status ENUM(
+        'active',
+        'inactive',
+        'banned'
+    ) DEFAULT 'active' COMMENT '账户状态',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_phone_email (phone, email),
+    INDEX idx_status (status)
+) COMMENT = '用户主表';
+
+CREATE TABLE user_addresses (
+    address_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '地址ID',
+    user_id INT NOT NULL COMMENT '用户ID',
+    contact_name VARCHAR(50) NOT NULL COMMENT '联系人',
+    contact_phone VARCHAR(20) NOT NULL COMMENT '联系电话',
+    province VARCHAR(50) NOT NULL COMMENT '省份',
+    city VARCHAR(50) NOT NULL COMMENT '城市',
+    district VARCHAR(50) NOT NULL COMMENT '区县',
+    street_address VARCHAR(200) NOT NULL COMMENT '详细地址',
+    is_default BOOLEAN DEFAULT FALSE COMMENT '是否默认',
+    is_deleted BOOLEAN DEFAULT FALSE COMMENT '是否已删除 (软删除，保留历史订单引用)',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+    INDEX idx_user (user_id),
+    INDEX idx_user_default (user_id, is_default)
+) COMMENT = '用户地址表';
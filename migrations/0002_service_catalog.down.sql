@@ -0,0 +1,5 @@
+-- 服务分类与服务
+
+DROP TABLE IF EXISTS service_addons;
+DROP TABLE IF EXISTS services;
+DROP TABLE IF EXISTS service_categories;
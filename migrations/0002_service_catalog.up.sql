@@ -0,0 +1,45 @@
+-- 服务分类与服务
+
+CREATE TABLE service_categories (
+    category_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '分类ID',
+    category_name VARCHAR(50) NOT NULL COMMENT '分类名称',
+    parent_id INT NULL COMMENT '父分类ID',
+    icon_url VARCHAR(500) COMMENT '图标URL',
+    is_active BOOLEAN DEFAULT TRUE COMMENT '是否启用',
+    sort_order INT DEFAULT 0 COMMENT '排序',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (parent_id) REFERENCES service_categories (category_id),
+    INDEX idx_parent (parent_id)
+) COMMENT = '服务分类表';
+
+CREATE TABLE services (
+    service_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '服务ID',
+    category_id INT NOT NULL COMMENT '分类ID',
+    service_name VARCHAR(100) NOT NULL COMMENT '服务名称',
+    description TEXT COMMENT '服务描述',
+    base_price DECIMAL(8, 2) NOT NULL COMMENT '基础价格',
+    unit ENUM(
+        'hour',
+        'square_meter',
+        'item',
+        'fixed'
+    ) NOT NULL COMMENT '计价单位',
+    min_duration INT DEFAULT 1 COMMENT '最小时长/数量',
+    max_duration INT DEFAULT 8 COMMENT '最大时长/数量',
+    is_active BOOLEAN DEFAULT TRUE COMMENT '是否启用',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (category_id) REFERENCES service_categories (category_id),
+    INDEX idx_category (category_id),
+    INDEX idx_active (is_active)
+) COMMENT = '服务项目表';
+
+CREATE TABLE service_addons (
+    addon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '附加项ID',
+    service_id INT NOT NULL COMMENT '服务ID',
+    addon_name VARCHAR(100) NOT NULL COMMENT '附加项名称',
+    addon_price DECIMAL(8, 2) NOT NULL COMMENT '附加项价格',
+    is_active BOOLEAN DEFAULT TRUE COMMENT '是否启用',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (service_id) REFERENCES services (service_id) ON DELETE CASCADE,
+    INDEX idx_service (service_id)
+) COMMENT = '服务附加项表';
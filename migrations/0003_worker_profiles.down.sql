@@ -0,0 +1,3 @@
+-- 工作者相关表
+
+DROP TABLE IF EXISTS worker_profiles;
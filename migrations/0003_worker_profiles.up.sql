@@ -0,0 +1,24 @@
+-- 工作者相关表
+
+CREATE TABLE worker_profiles (
+    worker_id INT PRIMARY KEY COMMENT '服务人员ID',
+    service_category_id INT NOT NULL COMMENT '服务分类ID',
+    hourly_rate DECIMAL(8, 2) NOT NULL COMMENT '时薪',
+    bio TEXT COMMENT '个人简介',
+    skills JSON COMMENT '技能标签',
+    service_area JSON COMMENT '服务区域',
+    total_orders INT DEFAULT 0 COMMENT '总订单数',
+    completed_orders INT DEFAULT 0 COMMENT '完成订单数',
+    avg_rating DECIMAL(3, 2) DEFAULT 0.00 COMMENT '平均评分',
+    is_available BOOLEAN DEFAULT TRUE COMMENT '是否可接单',
+    max_daily_orders INT DEFAULT 3 COMMENT '每日最大接单数',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (worker_id) REFERENCES users (user_id) ON DELETE CASCADE,
+    FOREIGN KEY (service_category_id) REFERENCES service_categories (category_id),
+    INDEX idx_category_rating (
+        service_category_id,
+        avg_rating DESC
+    ),
+    INDEX idx_available (is_available)
+) COMMENT = '服务人员详情表';
@@ -0,0 +1,3 @@
+-- 优惠券基础表
+
+DROP TABLE IF EXISTS coupons;
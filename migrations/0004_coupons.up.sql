@@ -0,0 +1,26 @@
+-- 优惠券基础表
+
+CREATE TABLE coupons (
+    coupon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '优惠券ID',
+    coupon_code VARCHAR(20) UNIQUE NOT NULL COMMENT '优惠券码',
+    coupon_name VARCHAR(100) NOT NULL COMMENT '优惠券名称',
+    discount_type ENUM(
+        'percentage',
+        'fixed',
+        'service'
+    ) NOT NULL COMMENT '折扣类型',
+    discount_value DECIMAL(10, 2) NOT NULL COMMENT '折扣值',
+    min_order_amount DECIMAL(10, 2) DEFAULT 0.00 COMMENT '最低订单金额',
+    applicable_services JSON COMMENT '适用服务',
+    valid_from DATE NOT NULL COMMENT '有效期开始',
+    valid_until DATE NOT NULL COMMENT '有效期截止',
+    usage_limit INT DEFAULT 1 COMMENT '使用次数限制',
+    used_count INT DEFAULT 0 COMMENT '已使用次数',
+    is_active BOOLEAN DEFAULT TRUE COMMENT '是否启用',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_code_validity (
+        coupon_code,
+        valid_until,
+        is_active
+    )
+) COMMENT = '优惠券表';
@@ -0,0 +1,3 @@
+-- 订单主表（依赖多表）
+
+DROP TABLE IF EXISTS orders;
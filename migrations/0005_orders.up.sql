@@ -0,0 +1,46 @@
+-- 订单主表（依赖多表）
+
+CREATE TABLE orders (
+    order_id VARCHAR(20) PRIMARY KEY COMMENT '订单号',
+    customer_id INT NOT NULL COMMENT '客户ID',
+    worker_id INT NULL COMMENT '服务人员ID',
+    address_id INT NOT NULL COMMENT '地址ID',
+    service_id INT NOT NULL COMMENT '服务ID',
+    coupon_id INT NULL COMMENT '优惠券ID',
+    service_date DATE NOT NULL COMMENT '服务日期',
+    time_slot ENUM(
+        'morning',
+        'afternoon',
+        'evening',
+        'full_day'
+    ) NOT NULL COMMENT '服务时段',
+    duration DECIMAL(5, 1) NOT NULL COMMENT '服务时长',
+    unit_price DECIMAL(8, 2) NOT NULL COMMENT '单价',
+    subtotal DECIMAL(10, 2) NOT NULL COMMENT '小计金额',
+    discount_amount DECIMAL(8, 2) DEFAULT 0.00 COMMENT '优惠金额',
+    total_amount DECIMAL(10, 2) NOT NULL COMMENT '总金额',
+    payment_status ENUM('pending', 'paid', 'refunded') DEFAULT 'pending' COMMENT '支付状态',
+    order_status ENUM(
+        'pending',
+        'confirmed',
+        'assigned',
+        'ongoing',
+        'completed',
+        'cancelled'
+    ) DEFAULT 'pending' COMMENT '订单状态',
+    special_instructions TEXT COMMENT '特殊要求',
+    cancellation_reason TEXT COMMENT '取消原因',
+    scheduled_start_time DATETIME NULL COMMENT '计划开始时间',
+    actual_start_time DATETIME NULL COMMENT '实际开始时间',
+    actual_end_time DATETIME NULL COMMENT '实际结束时间',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (customer_id) REFERENCES users (user_id),
+    FOREIGN KEY (worker_id) REFERENCES users (user_id),
+    FOREIGN KEY (address_id) REFERENCES user_addresses (address_id),
+    FOREIGN KEY (service_id) REFERENCES services (service_id),
+    FOREIGN KEY (coupon_id) REFERENCES coupons (coupon_id),
+    INDEX idx_customer_status (customer_id, order_status),
+    INDEX idx_worker_status (worker_id, order_status),
+    INDEX idx_date_status (service_date, order_status)
+) COMMENT = '订单主表';
@@ -0,0 +1,3 @@
+-- 订单附加项
+
+DROP TABLE IF EXISTS order_addons;
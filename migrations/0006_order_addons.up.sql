@@ -0,0 +1,13 @@
+-- 订单附加项
+
+CREATE TABLE order_addons (
+    order_addon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '订单附加项ID',
+    order_id VARCHAR(20) NOT NULL COMMENT '订单ID',
+    addon_id INT NOT NULL COMMENT '附加项ID',
+    quantity INT DEFAULT 1 COMMENT '数量',
+    unit_price DECIMAL(8, 2) NOT NULL COMMENT '单价',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders (order_id) ON DELETE CASCADE,
+    FOREIGN KEY (addon_id) REFERENCES service_addons (addon_id),
+    INDEX idx_order (order_id)
+) COMMENT = '订单附加项表';
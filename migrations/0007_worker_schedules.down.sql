@@ -0,0 +1,3 @@
+-- 工作者日程（需要 orders）
+
+DROP TABLE IF EXISTS worker_schedules;
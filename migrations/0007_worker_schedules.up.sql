@@ -0,0 +1,28 @@
+-- 工作者日程（需要 orders）
+
+CREATE TABLE worker_schedules (
+    schedule_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '日程ID',
+    worker_id INT NOT NULL COMMENT '服务人员ID',
+    schedule_date DATE NOT NULL COMMENT '排班日期',
+    time_slot ENUM(
+        'morning',
+        'afternoon',
+        'evening',
+        'full_day'
+    ) NOT NULL COMMENT '时间段',
+    status ENUM(
+        'available',
+        'booked',
+        'unavailable'
+    ) DEFAULT 'available' COMMENT '状态',
+    order_id VARCHAR(20) NULL COMMENT '关联订单ID',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (worker_id) REFERENCES users (user_id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders (order_id),
+    UNIQUE KEY uk_worker_time (
+        worker_id,
+        schedule_date,
+        time_slot
+    ),
+    INDEX idx_worker_date (worker_id, schedule_date)
+) COMMENT = '服务人员日程表';
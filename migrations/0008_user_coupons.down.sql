@@ -0,0 +1,3 @@
+-- 用户优惠券
+
+DROP TABLE IF EXISTS user_coupons;
@@ -0,0 +1,17 @@
+-- 用户优惠券
+
+CREATE TABLE user_coupons (
+    user_coupon_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '用户优惠券ID',
+    user_id INT NOT NULL COMMENT '用户ID',
+    coupon_id INT NOT NULL COMMENT '优惠券ID',
+    order_id VARCHAR(20) NULL COMMENT '订单ID',
+    is_used BOOLEAN DEFAULT FALSE COMMENT '是否已使用',
+    used_at DATETIME NULL COMMENT '使用时间',
+    expires_at DATE NOT NULL COMMENT '过期时间',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id),
+    FOREIGN KEY (coupon_id) REFERENCES coupons (coupon_id),
+    FOREIGN KEY (order_id) REFERENCES orders (order_id),
+    UNIQUE KEY uk_user_coupon_order (user_id, coupon_id, order_id),
+    INDEX idx_user_unused (user_id, is_used, expires_at)
+) COMMENT = '用户优惠券表';
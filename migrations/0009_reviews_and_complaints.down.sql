@@ -0,0 +1,4 @@
+-- 评价与投诉
+
+DROP TABLE IF EXISTS complaints;
+DROP TABLE IF EXISTS reviews;
@@ -0,0 +1,53 @@
+-- 评价与投诉
+
+CREATE TABLE reviews (
+    review_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '评价ID',
+    order_id VARCHAR(20) UNIQUE NOT NULL COMMENT '订单ID',
+    customer_id INT NOT NULL COMMENT '客户ID',
+    worker_id INT NOT NULL COMMENT '服务人员ID',
+    rating TINYINT NOT NULL COMMENT '评分(1-5)',
+    service_rating TINYINT COMMENT '服务评分',
+    punctuality_rating TINYINT COMMENT '守时评分',
+    review_text TEXT COMMENT '评价内容',
+    is_anonymous BOOLEAN DEFAULT FALSE COMMENT '是否匿名',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders (order_id),
+    FOREIGN KEY (customer_id) REFERENCES users (user_id),
+    FOREIGN KEY (worker_id) REFERENCES users (user_id),
+    INDEX idx_worker_rating (worker_id, rating DESC),
+    INDEX idx_order (order_id)
+) COMMENT = '评价表';
+
+CREATE TABLE complaints (
+    complaint_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '投诉ID',
+    order_id VARCHAR(20) NOT NULL COMMENT '订单ID',
+    complainant_id INT NOT NULL COMMENT '投诉人ID',
+    target_id INT NOT NULL COMMENT '被投诉人ID',
+    complaint_type ENUM(
+        'service',
+        'punctuality',
+        'attitude',
+        'other'
+    ) NOT NULL COMMENT '投诉类型',
+    complaint_text TEXT NOT NULL COMMENT '投诉内容',
+    status ENUM(
+        'pending',
+        'investigating',
+        'resolved',
+        'rejected'
+    ) DEFAULT 'pending' COMMENT '处理状态',
+    evidence_text TEXT NULL COMMENT '证据说明',
+    handler_id INT NULL COMMENT '处理人ID',
+    resolution TEXT NULL COMMENT '处理结果',
+    compensation_type ENUM('none', 'refund', 'coupon') DEFAULT 'none' COMMENT '补偿方式',
+    compensation_coupon_id INT NULL COMMENT '补偿优惠券ID',
+    resolved_at DATETIME NULL COMMENT '处理时间',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders (order_id),
+    FOREIGN KEY (complainant_id) REFERENCES users (user_id),
+    FOREIGN KEY (target_id) REFERENCES users (user_id),
+    FOREIGN KEY (handler_id) REFERENCES users (user_id),
+    FOREIGN KEY (compensation_coupon_id) REFERENCES coupons (coupon_id),
+    INDEX idx_status (status),
+    INDEX idx_complainant (complainant_id)
+) COMMENT = '投诉表';
@@ -0,0 +1,3 @@
+-- 支付表
+
+DROP TABLE IF EXISTS payments;
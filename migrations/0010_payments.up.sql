@@ -0,0 +1,27 @@
+-- 支付表
+
+CREATE TABLE payments (
+    payment_id VARCHAR(30) PRIMARY KEY COMMENT '支付ID',
+    order_id VARCHAR(20) NOT NULL COMMENT '订单ID',
+    user_id INT NOT NULL COMMENT '用户ID',
+    payment_method ENUM(
+        'wechat',
+        'alipay',
+        'balance',
+        'card'
+    ) NOT NULL COMMENT '支付方式',
+    payment_amount DECIMAL(10, 2) NOT NULL COMMENT '支付金额',
+    payment_status ENUM(
+        'pending',
+        'processing',
+        'success',
+        'failed'
+    ) DEFAULT 'pending' COMMENT '支付状态',
+    thirdparty_trade_no VARCHAR(100) COMMENT '第三方交易号',
+    payment_time DATETIME NULL COMMENT '支付时间',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders (order_id),
+    FOREIGN KEY (user_id) REFERENCES users (user_id),
+    INDEX idx_order_status (order_id, payment_status),
+    INDEX idx_user_time (user_id, payment_time DESC)
+) COMMENT = '支付记录表';
@@ -0,0 +1,4 @@
+-- 系统配置与通知
+
+DROP TABLE IF EXISTS notifications;
+DROP TABLE IF EXISTS system_settings;
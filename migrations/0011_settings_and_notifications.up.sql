@@ -0,0 +1,28 @@
+-- 系统配置与通知
+
+CREATE TABLE system_settings (
+    setting_key VARCHAR(50) PRIMARY KEY COMMENT '配置键',
+    setting_value TEXT COMMENT '配置值',
+    category VARCHAR(50) COMMENT '分类',
+    description TEXT COMMENT '描述',
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) COMMENT = '系统配置表';
+
+CREATE TABLE notifications (
+    notification_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '消息ID',
+    user_id INT NOT NULL COMMENT '用户ID',
+    notification_type ENUM(
+        'order',
+        'system',
+        'promotion'
+    ) NOT NULL COMMENT '消息类型',
+    title VARCHAR(200) NOT NULL COMMENT '标题',
+    content TEXT NOT NULL COMMENT '内容',
+    related_id VARCHAR(50) COMMENT '关联业务ID',
+    is_read BOOLEAN DEFAULT FALSE COMMENT '是否已读',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id),
+    INDEX idx_user_unread (user_id, is_read),
+    INDEX idx_user_latest (user_id, notification_id DESC),
+    INDEX idx_created (created_at DESC)
+) COMMENT = '消息通知表';
@@ -0,0 +1,3 @@
+-- 刷新令牌
+
+DROP TABLE IF EXISTS refresh_tokens;
@@ -0,0 +1,17 @@
+-- 刷新令牌
+
+CREATE TABLE refresh_tokens (
+    token_id BIGINT PRIMARY KEY AUTO_INCREMENT COMMENT '令牌ID',
+    token_hash CHAR(64) UNIQUE NOT NULL COMMENT '令牌SHA-256哈希',
+    user_id INT NOT NULL COMMENT '用户ID',
+    family_id VARCHAR(36) NOT NULL COMMENT '令牌族ID(同一次登录轮换出的令牌共享)',
+    parent_id BIGINT NULL COMMENT '轮换前的令牌ID',
+    device_info VARCHAR(255) COMMENT '设备信息',
+    expires_at DATETIME NOT NULL COMMENT '过期时间',
+    revoked BOOLEAN DEFAULT FALSE COMMENT '是否已撤销',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_id) REFERENCES refresh_tokens (token_id),
+    INDEX idx_family (family_id),
+    INDEX idx_user (user_id)
+) COMMENT = '刷新令牌表';
@@ -0,0 +1,3 @@
+-- 订单状态变更记录
+
+DROP TABLE IF EXISTS order_status_history;
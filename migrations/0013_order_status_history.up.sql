@@ -0,0 +1,28 @@
+-- 订单状态变更记录
+
+CREATE TABLE order_status_history (
+    history_id BIGINT PRIMARY KEY AUTO_INCREMENT COMMENT '记录ID',
+    order_id VARCHAR(20) NOT NULL COMMENT '订单ID',
+    from_status ENUM(
+        'pending',
+        'confirmed',
+        'assigned',
+        'ongoing',
+        'completed',
+        'cancelled'
+    ) NULL COMMENT '变更前状态',
+    to_status ENUM(
+        'pending',
+        'confirmed',
+        'assigned',
+        'ongoing',
+        'completed',
+        'cancelled'
+    ) NOT NULL COMMENT '变更后状态',
+    changed_by INT NOT NULL COMMENT '操作人ID',
+    remark TEXT COMMENT '备注',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders (order_id) ON DELETE CASCADE,
+    FOREIGN KEY (changed_by) REFERENCES users (user_id),
+    INDEX idx_order_time (order_id, created_at)
+) COMMENT = '订单状态变更记录表';
@@ -0,0 +1,3 @@
+-- 余额流水
+
+DROP TABLE IF EXISTS balance_transactions;
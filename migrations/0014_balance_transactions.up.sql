@@ -0,0 +1,24 @@
+-- 余额流水
+
+CREATE TABLE balance_transactions (
+    transaction_id BIGINT PRIMARY KEY AUTO_INCREMENT COMMENT '流水ID',
+    user_id INT NOT NULL COMMENT '用户ID',
+    transaction_type ENUM(
+        'topup',
+        'payment',
+        'refund',
+        'adjustment'
+    ) NOT NULL COMMENT '交易类型',
+    amount DECIMAL(10, 2) NOT NULL COMMENT '变动金额，入账为正，出账为负',
+    balance_before DECIMAL(10, 2) NOT NULL COMMENT '变动前余额',
+    balance_after DECIMAL(10, 2) NOT NULL COMMENT '变动后余额',
+    reference_type ENUM('order', 'payment', 'topup', 'manual') NOT NULL COMMENT '关联对象类型',
+    reference_id VARCHAR(100) NULL COMMENT '关联订单ID、支付ID或充值交易号',
+    operator_id INT NULL COMMENT '操作人ID (管理员调整时记录)',
+    remark VARCHAR(255) COMMENT '备注',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id),
+    FOREIGN KEY (operator_id) REFERENCES users (user_id),
+    INDEX idx_user_txn (user_id, transaction_id DESC),
+    INDEX idx_reference (reference_type, reference_id)
+) COMMENT = '余额流水表';
@@ -0,0 +1,3 @@
+-- 投诉处理备注
+
+DROP TABLE IF EXISTS complaint_notes;
@@ -0,0 +1,12 @@
+-- 投诉处理备注
+
+CREATE TABLE complaint_notes (
+    note_id BIGINT PRIMARY KEY AUTO_INCREMENT COMMENT '备注ID',
+    complaint_id INT NOT NULL COMMENT '投诉ID',
+    author_id INT NOT NULL COMMENT '填写人ID',
+    note TEXT NOT NULL COMMENT '内部备注',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (complaint_id) REFERENCES complaints (complaint_id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users (user_id),
+    INDEX idx_complaint (complaint_id)
+) COMMENT = '投诉内部备注表';
@@ -0,0 +1,3 @@
+-- 审计日志
+
+DROP TABLE IF EXISTS audit_logs;
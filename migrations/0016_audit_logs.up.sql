@@ -0,0 +1,14 @@
+-- 审计日志
+
+CREATE TABLE audit_logs (
+    log_id BIGINT PRIMARY KEY AUTO_INCREMENT COMMENT '日志ID',
+    operator_id INT NOT NULL COMMENT '操作人ID',
+    entity_type ENUM('category', 'service', 'addon') NOT NULL COMMENT '操作对象类型',
+    entity_id INT NOT NULL COMMENT '操作对象ID',
+    action ENUM('create', 'update', 'delete') NOT NULL COMMENT '操作类型 (删除为软删除)',
+    snapshot JSON NULL COMMENT '操作后的数据快照',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (operator_id) REFERENCES users (user_id),
+    INDEX idx_entity (entity_type, entity_id),
+    INDEX idx_operator_time (operator_id, created_at)
+) COMMENT = '管理操作审计日志表';
@@ -0,0 +1,3 @@
+-- 服务人员入驻申请
+
+DROP TABLE IF EXISTS worker_applications;
@@ -0,0 +1,26 @@
+-- 服务人员入驻申请
+
+CREATE TABLE worker_applications (
+    application_id INT PRIMARY KEY AUTO_INCREMENT COMMENT '申请ID',
+    user_id INT NOT NULL COMMENT '申请人ID',
+    service_category_id INT NOT NULL COMMENT '申请的服务分类ID',
+    real_name VARCHAR(50) NOT NULL COMMENT '真实姓名',
+    hourly_rate DECIMAL(8, 2) NOT NULL COMMENT '期望时薪',
+    bio TEXT COMMENT '个人简介',
+    skills JSON COMMENT '技能标签',
+    service_area JSON NOT NULL COMMENT '服务区域',
+    status ENUM(
+        'pending',
+        'approved',
+        'rejected'
+    ) DEFAULT 'pending' COMMENT '审核状态',
+    reviewer_id INT NULL COMMENT '审核人ID',
+    review_remark VARCHAR(255) NULL COMMENT '审核意见',
+    reviewed_at DATETIME NULL COMMENT '审核时间',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+    FOREIGN KEY (service_category_id) REFERENCES service_categories (category_id),
+    FOREIGN KEY (reviewer_id) REFERENCES users (user_id),
+    INDEX idx_user (user_id),
+    INDEX idx_status (status, application_id)
+) COMMENT = '服务人员入驻申请表';
@@ -0,0 +1,3 @@
+-- 订单编号当日序号
+
+DROP TABLE IF EXISTS order_sequences;
@@ -0,0 +1,12 @@
+-- 订单编号当日序号
+
+CREATE TABLE order_sequences (
+    seq_date DATE PRIMARY KEY COMMENT '日期',
+    seq INT UNSIGNED NOT NULL COMMENT '当日已分配的最大序号'
+) COMMENT = '订单编号序号表';
+
+-- 从已有订单初始化各日期的序号
+INSERT INTO order_sequences (seq_date, seq)
+SELECT STR_TO_DATE(LEFT(order_id, 8), '%Y%m%d'), MAX(CAST(SUBSTRING(order_id, 9) AS UNSIGNED))
+FROM orders
+GROUP BY LEFT(order_id, 8);
@@ -0,0 +1,10 @@
+-- 支付记录增加已退款状态
+
+UPDATE payments SET payment_status = 'success' WHERE payment_status = 'refunded';
+
+ALTER TABLE payments MODIFY payment_status ENUM(
+    'pending',
+    'processing',
+    'success',
+    'failed'
+) DEFAULT 'pending' COMMENT '支付状态';
@@ -0,0 +1,9 @@
+-- 支付记录增加已退款状态
+
+ALTER TABLE payments MODIFY payment_status ENUM(
+    'pending',
+    'processing',
+    'success',
+    'failed',
+    'refunded'
+) DEFAULT 'pending' COMMENT '支付状态';
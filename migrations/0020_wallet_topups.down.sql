@@ -0,0 +1,3 @@
+-- 余额充值记录
+
+DROP TABLE IF EXISTS wallet_topups;
@@ -0,0 +1,21 @@
+-- 余额充值记录
+
+CREATE TABLE wallet_topups (
+    topup_id VARCHAR(30) PRIMARY KEY COMMENT '充值ID',
+    user_id INT NOT NULL COMMENT '用户ID',
+    payment_method ENUM('wechat', 'alipay', 'card') NOT NULL COMMENT '充值渠道',
+    amount DECIMAL(10, 2) NOT NULL COMMENT '充值金额',
+    topup_status ENUM(
+        'pending',
+        'processing',
+        'success',
+        'failed'
+    ) NOT NULL DEFAULT 'pending' COMMENT '充值状态',
+    thirdparty_trade_no VARCHAR(100) NULL COMMENT '第三方交易号',
+    transaction_id BIGINT NULL COMMENT '入账后对应的余额流水',
+    settled_at DATETIME NULL COMMENT '入账时间',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (user_id),
+    FOREIGN KEY (transaction_id) REFERENCES balance_transactions (transaction_id),
+    INDEX idx_user_status (user_id, topup_status)
+) COMMENT = '余额充值记录表';
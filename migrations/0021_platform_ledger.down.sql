@@ -0,0 +1,3 @@
+-- 平台科目分录
+
+DROP TABLE IF EXISTS platform_ledger;
@@ -0,0 +1,31 @@
+-- 平台科目分录
+-- 用户余额的每一笔流水在平台科目上记一笔金额相反的对应分录，
+-- 全部用户流水与平台分录的金额之和恒为 0
+
+CREATE TABLE platform_ledger (
+    entry_id BIGINT PRIMARY KEY AUTO_INCREMENT COMMENT '分录ID',
+    account ENUM(
+        'channel_clearing',
+        'order_settlement',
+        'manual_adjustment'
+    ) NOT NULL COMMENT '平台科目',
+    transaction_id BIGINT NOT NULL COMMENT '对应的用户余额流水',
+    amount DECIMAL(10, 2) NOT NULL COMMENT '变动金额，与用户流水金额相反',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (transaction_id) REFERENCES balance_transactions (transaction_id),
+    UNIQUE KEY uk_transaction (transaction_id),
+    INDEX idx_account (account, entry_id)
+) COMMENT = '平台科目分录表';
+
+-- 为已有流水补记对应分录
+INSERT INTO platform_ledger (account, transaction_id, amount, created_at)
+SELECT
+    CASE transaction_type
+        WHEN 'topup' THEN 'channel_clearing'
+        WHEN 'adjustment' THEN 'manual_adjustment'
+        ELSE 'order_settlement'
+    END,
+    transaction_id,
+    -amount,
+    created_at
+FROM balance_transactions;
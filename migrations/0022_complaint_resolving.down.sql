@@ -0,0 +1,10 @@
+-- 投诉增加结案中状态
+
+UPDATE complaints SET status = 'investigating' WHERE status = 'resolving';
+
+ALTER TABLE complaints MODIFY status ENUM(
+    'pending',
+    'investigating',
+    'resolved',
+    'rejected'
+) DEFAULT 'pending' COMMENT '处理状态';
@@ -0,0 +1,10 @@
+-- 投诉增加结案中状态
+-- 退款补偿前先把投诉置为 resolving 占位，防止并发结案重复退款
+
+ALTER TABLE complaints MODIFY status ENUM(
+    'pending',
+    'investigating',
+    'resolving',
+    'resolved',
+    'rejected'
+) DEFAULT 'pending' COMMENT '处理状态';
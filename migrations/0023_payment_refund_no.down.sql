@@ -0,0 +1,3 @@
+-- 支付记录增加渠道退款单号
+
+ALTER TABLE payments DROP COLUMN refund_no;
@@ -0,0 +1,5 @@
+-- 支付记录增加渠道退款单号
+-- 渠道退款成功后记录退款单号，用于与渠道对账
+
+ALTER TABLE payments
+    ADD COLUMN refund_no VARCHAR(100) NULL COMMENT '渠道退款单号' AFTER thirdparty_trade_no;
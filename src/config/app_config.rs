@@ -0,0 +1,402 @@
+//! 应用配置
+//!
+//! 配置按 默认值 → TOML 配置文件 → 环境变量 的顺序叠加，后者覆盖前者。
+//! 配置文件路径由 `JZ_CONFIG` 指定，未指定时尝试读取当前目录下的 `jz.toml`。
+//! 加载完成后统一校验，生产环境拒绝使用默认 JWT 密钥等不安全配置
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::services::payment_provider::MockOutcome;
+
+/// 开发环境默认的 JWT 密钥，生产环境禁止使用
+pub const DEFAULT_JWT_SECRET: &str = "secret-development-key";
+
+/// 未指定 `JZ_CONFIG` 时读取的配置文件
+const DEFAULT_CONFIG_FILE: &str = "jz.toml";
+
+/// 生产环境 JWT 密钥最小长度
+const MIN_PRODUCTION_SECRET_LEN: usize = 32;
+
+/// 运行环境
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    #[default]
+    Development,
+    Production,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub environment: Environment,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub jwt: JwtConfig,
+    pub log: LogConfig,
+    pub cors: CorsConfig,
+    pub upload: UploadConfig,
+    pub payment: PaymentConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { host: "0.0.0.0".to_string(), port: 8000 }
+    }
+}
+
+/// 数据库连接与连接池
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// 获取连接的超时时间 (秒)
+    pub acquire_timeout_secs: u64,
+    /// 启动时是否自动执行数据库迁移
+    pub auto_migrate: bool,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            auto_migrate: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JwtConfig {
+    pub secret: String,
+    /// 访问令牌有效期 (秒)
+    pub access_token_ttl_secs: usize,
+    /// 刷新令牌有效期 (天)
+    pub refresh_token_ttl_days: i64,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            secret: DEFAULT_JWT_SECRET.to_string(),
+            access_token_ttl_secs: 3600,
+            refresh_token_ttl_days: 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// 日志文件目录，按天滚动
+    pub dir: String,
+    /// 日志级别：trace/debug/info/warn/error
+    pub level: String,
+    /// 是否同时输出到控制台
+    pub stdout: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self { dir: "logs".to_string(), level: "info".to_string(), stdout: true }
+    }
+}
+
+/// 跨域配置，allowed_origins 为空或包含 "*" 时允许任意来源
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+}
+
+impl CorsConfig {
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.is_empty() || self.allowed_origins.iter().any(|o| o == "*")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UploadConfig {
+    /// 请求体最大字节数
+    pub max_body_bytes: usize,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self { max_body_bytes: 2 * 1024 * 1024 }
+    }
+}
+
+/// 支付渠道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentProviderKind {
+    /// 进程内模拟网关
+    #[default]
+    Mock,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PaymentConfig {
+    pub provider: PaymentProviderKind,
+    /// 模拟网关的支付结果
+    pub mock_outcome: MockOutcome,
+    /// 生产环境默认拒绝模拟网关，接入真实渠道前可显式开启以部署预发或演示环境
+    pub allow_mock: bool,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    ReadError(PathBuf, std::io::Error),
+    ParseError(PathBuf, toml::de::Error),
+    /// 环境变量的值无法解析 (变量名, 值)
+    InvalidEnv(String, String),
+    /// 校验未通过的配置项
+    ValidationError(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::ReadError(path, e) => write!(f, "读取配置文件 {} 失败: {}", path.display(), e),
+            ConfigError::ParseError(path, e) => write!(f, "解析配置文件 {} 失败: {}", path.display(), e),
+            ConfigError::InvalidEnv(key, value) => write!(f, "环境变量 {} 的值无效: {}", key, value),
+            ConfigError::ValidationError(errors) => write!(f, "配置校验失败: {}", errors.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn parse_env<T: std::str::FromStr>(key: &str, value: String) -> Result<T, ConfigError> {
+    value.trim().parse().map_err(|_| ConfigError::InvalidEnv(key.to_string(), value))
+}
+
+fn parse_bool_env(key: &str, value: String) -> Result<bool, ConfigError> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" => Ok(true),
+        "0" | "false" | "no" => Ok(false),
+        _ => Err(ConfigError::InvalidEnv(key.to_string(), value)),
+    }
+}
+
+impl Config {
+    /// 加载配置：读取 .env，叠加配置文件和环境变量，并校验
+    pub fn load() -> Result<Self, ConfigError> {
+        dotenvy::dotenv().ok();
+
+        let path = std::env::var("JZ_CONFIG").ok().map(PathBuf::from);
+        let mut config = match &path {
+            Some(path) => Self::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?,
+            None => Self::default(),
+        };
+        config.apply_env(|key| std::env::var(key).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadError(path.to_path_buf(), e))?;
+        toml::from_str(&content).map_err(|e| ConfigError::ParseError(path.to_path_buf(), e))
+    }
+
+    /// 用环境变量覆盖配置项
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        if let Some(v) = lookup("JZ_ENV") {
+            self.environment = match v.trim().to_ascii_lowercase().as_str() {
+                "development" => Environment::Development,
+                "production" => Environment::Production,
+                _ => return Err(ConfigError::InvalidEnv("JZ_ENV".to_string(), v)),
+            };
+        }
+        if let Some(v) = lookup("HOST") {
+            self.server.host = v;
+        }
+        if let Some(v) = lookup("PORT") {
+            self.server.port = parse_env("PORT", v)?;
+        }
+        if let Some(v) = lookup("DATABASE_URL") {
+            self.database.url = v;
+        }
+        if let Some(v) = lookup("DB_MAX_CONNECTIONS") {
+            self.database.max_connections = parse_env("DB_MAX_CONNECTIONS", v)?;
+        }
+        if let Some(v) = lookup("DB_AUTO_MIGRATE") {
+            self.database.auto_migrate = parse_bool_env("DB_AUTO_MIGRATE", v)?;
+        }
+        if let Some(v) = lookup("JWT_SECRET") {
+            self.jwt.secret = v;
+        }
+        if let Some(v) = lookup("LOG_LEVEL") {
+            self.log.level = v;
+        }
+        if let Some(v) = lookup("PAYMENT_ALLOW_MOCK") {
+            self.payment.allow_mock = parse_bool_env("PAYMENT_ALLOW_MOCK", v)?;
+        }
+        if let Some(v) = lookup("CORS_ALLOWED_ORIGINS") {
+            self.cors.allowed_origins = v
+                .split(',')
+                .map(str::trim)
+                .filter(|o| !o.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// 校验配置，返回全部不合法的配置项
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+        let production = self.environment == Environment::Production;
+
+        if self.database.url.trim().is_empty() {
+            errors.push("database.url 不能为空 (或设置 DATABASE_URL)".to_string());
+        }
+        if self.database.max_connections == 0 {
+            errors.push("database.max_connections 必须大于0".to_string());
+        }
+        if self.database.min_connections > self.database.max_connections {
+            errors.push("database.min_connections 不能大于 max_connections".to_string());
+        }
+        if self.jwt.secret.is_empty() {
+            errors.push("jwt.secret 不能为空".to_string());
+        }
+        if production && self.jwt.secret == DEFAULT_JWT_SECRET {
+            errors.push("生产环境不能使用默认的 jwt.secret".to_string());
+        }
+        if production && self.jwt.secret.len() < MIN_PRODUCTION_SECRET_LEN {
+            errors.push(format!("生产环境 jwt.secret 至少 {} 个字符", MIN_PRODUCTION_SECRET_LEN));
+        }
+        if self.jwt.access_token_ttl_secs == 0 || self.jwt.refresh_token_ttl_days <= 0 {
+            errors.push("jwt 令牌有效期必须大于0".to_string());
+        }
+        if self.log.level.parse::<tracing_subscriber::filter::LevelFilter>().is_err() {
+            errors.push(format!("log.level 无效: {}", self.log.level));
+        }
+        if production && self.cors.allows_any_origin() {
+            errors.push("生产环境必须配置 cors.allowed_origins，不能允许任意来源".to_string());
+        }
+        if self.upload.max_body_bytes == 0 {
+            errors.push("upload.max_body_bytes 必须大于0".to_string());
+        }
+        if production && self.payment.provider == PaymentProviderKind::Mock && !self.payment.allow_mock {
+            errors.push("生产环境使用模拟支付网关需显式设置 payment.allow_mock = true".to_string());
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(ConfigError::ValidationError(errors)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn valid_config() -> Config {
+        let mut config = Config::default();
+        config.database.url = "mysql://localhost/jz_db".to_string();
+        config
+    }
+
+    #[test]
+    fn test_parse_toml_with_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            environment = "production"
+
+            [server]
+            port = 9000
+
+            [database]
+            url = "mysql://localhost/jz_db"
+            max_connections = 20
+
+            [cors]
+            allowed_origins = ["https://jz.example.com"]
+
+            [payment]
+            mock_outcome = "deferred"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.environment, Environment::Production);
+        assert_eq!((config.server.host.as_str(), config.server.port), ("0.0.0.0", 9000));
+        assert_eq!(config.database.max_connections, 20);
+        assert_eq!(config.jwt.access_token_ttl_secs, 3600);
+        assert_eq!(config.payment.mock_outcome, MockOutcome::Deferred);
+        assert!(!config.cors.allows_any_origin());
+    }
+
+    #[test]
+    fn test_example_config_is_valid() {
+        let config: Config = toml::from_str(include_str!("../../jz.toml.example")).unwrap();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let env: HashMap<&str, &str> = [
+            ("PORT", "8081"),
+            ("JWT_SECRET", "from-env"),
+            ("DB_AUTO_MIGRATE", "true"),
+            ("CORS_ALLOWED_ORIGINS", "https://a.com, https://b.com"),
+        ]
+        .into_iter()
+        .collect();
+        let mut config = valid_config();
+        config.apply_env(|key| env.get(key).map(|v| v.to_string())).unwrap();
+        assert_eq!(config.server.port, 8081);
+        assert_eq!(config.jwt.secret, "from-env");
+        assert!(config.database.auto_migrate);
+        assert_eq!(config.cors.allowed_origins, vec!["https://a.com", "https://b.com"]);
+
+        let result = config.apply_env(|key| (key == "PORT").then(|| "abc".to_string()));
+        assert!(matches!(result, Err(ConfigError::InvalidEnv(..))));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(valid_config().validate().is_ok());
+        assert!(Config::default().validate().is_err());
+
+        let mut config = valid_config();
+        config.environment = Environment::Production;
+        config.cors.allowed_origins = vec!["https://jz.example.com".to_string()];
+        let Err(ConfigError::ValidationError(errors)) = config.validate() else {
+            panic!("生产环境使用默认密钥应校验失败");
+        };
+        assert!(errors.iter().any(|e| e.contains("默认的 jwt.secret")));
+
+        config.jwt.secret = "x".repeat(MIN_PRODUCTION_SECRET_LEN);
+        let Err(ConfigError::ValidationError(errors)) = config.validate() else {
+            panic!("生产环境使用模拟支付网关应校验失败");
+        };
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("payment.allow_mock"));
+
+        config.payment.allow_mock = true;
+        assert!(config.validate().is_ok());
+
+        let mut config = valid_config();
+        config.log.level = "verbose".to_string();
+        assert!(config.validate().is_err());
+    }
+}
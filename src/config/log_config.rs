@@ -1,14 +1,20 @@
 use std::fs;
 
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::fmt;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::registry::Registry;
-pub fn log_init() -> WorkerGuard{
-    let logs_dir = "logs";
+
+use super::LogConfig;
+
+pub fn log_init(config: &LogConfig) -> WorkerGuard {
+    let logs_dir = config.dir.as_str();
     if let Err(e) = fs::create_dir_all(logs_dir) {
         eprintln!("无法创建日志目录 {}: {}", logs_dir, e);
     }
+    // 配置加载时已校验日志级别
+    let level: LevelFilter = config.level.parse().unwrap_or(LevelFilter::INFO);
 
     let file_appender = tracing_appender::rolling::daily(logs_dir, "jz.log");
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
@@ -17,13 +23,15 @@ pub fn log_init() -> WorkerGuard{
         .with_writer(non_blocking)
         .with_ansi(false)
         .with_target(false)
-        .with_filter(tracing_subscriber::filter::LevelFilter::INFO);
+        .with_filter(level);
 
-    let stdout_layer = fmt::layer()
-        .with_writer(std::io::stdout)
-        .with_ansi(true)
-        .with_target(false)
-        .with_filter(tracing_subscriber::filter::LevelFilter::INFO);
+    let stdout_layer = config.stdout.then(|| {
+        fmt::layer()
+            .with_writer(std::io::stdout)
+            .with_ansi(true)
+            .with_target(false)
+            .with_filter(level)
+    });
 
     Registry::default()
         .with(file_layer)
@@ -31,9 +39,11 @@ pub fn log_init() -> WorkerGuard{
         .init();
 
     tracing::info!(
-        "日志系统已初始化（文件: {}/jz.log，控制台输出已启用）",
-        logs_dir
+        "日志系统已初始化（文件: {}/jz.log，级别: {}，控制台输出: {}）",
+        logs_dir,
+        level,
+        config.stdout
     );
     _guard
 
-}
\ No newline at end of file
+}
@@ -2,13 +2,33 @@
 //!
 //! 负责初始化和管理数据库连接池
 
-use sqlx::mysql::MySqlPool;
-use std::env;
+use std::time::Duration;
+
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
+
+use super::migration::{ensure_schema_up_to_date, run_migrations, DatabaseInitError};
+use crate::config::DatabaseConfig;
+
+/// 按配置建立数据库连接池，不检查结构版本，供迁移命令使用
+pub async fn connect_db(config: &DatabaseConfig) -> Result<MySqlPool, sqlx::Error> {
+    MySqlPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .connect(&config.url)
+        .await
+}
 
 /// 初始化数据库连接池
-pub async fn init_db_pool(database_url : &str) -> Result<MySqlPool, sqlx::Error> {
-    
-    let pool = MySqlPool::connect(&database_url).await?;
-    
-    Ok(pool)    
-}
\ No newline at end of file
+/// 配置开启 auto_migrate 时先执行未执行的迁移；数据库结构版本落后时拒绝启动
+pub async fn init_db_pool(config: &DatabaseConfig) -> Result<MySqlPool, DatabaseInitError> {
+    let pool = connect_db(config).await?;
+
+    if config.auto_migrate {
+        tracing::info!("启动时自动执行数据库迁移");
+        run_migrations(&pool).await?;
+    }
+    ensure_schema_up_to_date(&pool).await?;
+
+    Ok(pool)
+}
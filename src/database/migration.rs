@@ -0,0 +1,184 @@
+//! 数据库结构迁移
+//!
+//! migrations 目录下的可回滚迁移在编译时嵌入二进制，
+//! 通过 `jz migrate up/down/status` 手动执行，或在配置开启时于启动阶段自动执行
+
+use std::collections::HashSet;
+use std::fmt;
+
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::mysql::MySqlPool;
+
+/// 嵌入的迁移脚本
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Debug)]
+pub enum DatabaseInitError {
+    ConnectionError(sqlx::Error),
+    MigrateError(MigrateError),
+    /// 数据库结构版本落后于程序，列出未执行的迁移版本
+    SchemaBehind(Vec<i64>),
+}
+
+impl fmt::Display for DatabaseInitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DatabaseInitError::ConnectionError(e) => write!(f, "数据库连接错误: {}", e),
+            DatabaseInitError::MigrateError(e) => write!(f, "数据库迁移错误: {}", e),
+            DatabaseInitError::SchemaBehind(pending) => write!(
+                f,
+                "数据库结构版本落后，未执行的迁移: {:?}，请先运行 `jz migrate up`",
+                pending
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseInitError {}
+
+impl From<sqlx::Error> for DatabaseInitError {
+    fn from(error: sqlx::Error) -> Self {
+        DatabaseInitError::ConnectionError(error)
+    }
+}
+
+impl From<MigrateError> for DatabaseInitError {
+    fn from(error: MigrateError) -> Self {
+        DatabaseInitError::MigrateError(error)
+    }
+}
+
+/// 迁移子命令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrateCommand {
+    /// 执行全部未执行的迁移
+    Up,
+    /// 回滚最近一次迁移
+    Down,
+    /// 列出各迁移的执行状态
+    Status,
+}
+
+impl MigrateCommand {
+    /// 解析命令行参数 (不含程序名)，`migrate` 之后必须跟 up/down/status
+    pub fn from_args(args: &[String]) -> Option<Result<Self, String>> {
+        let (first, rest) = args.split_first()?;
+        if first != "migrate" {
+            return None;
+        }
+        let command = match rest.first().map(String::as_str) {
+            Some("up") => Ok(MigrateCommand::Up),
+            Some("down") => Ok(MigrateCommand::Down),
+            Some("status") => Ok(MigrateCommand::Status),
+            _ => Err("用法: jz migrate <up|down|status>".to_string()),
+        };
+        Some(command)
+    }
+}
+
+/// 单个迁移的执行状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+/// 对照已执行的版本列出全部迁移的状态
+fn migration_statuses(migrator: &Migrator, applied: &HashSet<i64>) -> Vec<MigrationStatus> {
+    migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            applied: applied.contains(&m.version),
+        })
+        .collect()
+}
+
+/// 已执行的迁移版本，迁移中断未完成时报错
+async fn applied_versions(pool: &MySqlPool) -> Result<HashSet<i64>, MigrateError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    if let Some(version) = conn.dirty_version().await? {
+        return Err(MigrateError::Dirty(version));
+    }
+    let applied = conn.list_applied_migrations().await?;
+    Ok(applied.into_iter().map(|m| m.version).collect())
+}
+
+pub async fn migration_status(pool: &MySqlPool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let applied = applied_versions(pool).await?;
+    Ok(migration_statuses(&MIGRATOR, &applied))
+}
+
+/// 执行全部未执行的迁移
+pub async fn run_migrations(pool: &MySqlPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+/// 回滚最近一次迁移，返回被回滚的版本，没有已执行的迁移时返回 None
+pub async fn revert_last_migration(pool: &MySqlPool) -> Result<Option<i64>, MigrateError> {
+    let mut applied: Vec<i64> = applied_versions(pool).await?.into_iter().collect();
+    applied.sort_unstable();
+    let Some(latest) = applied.pop() else {
+        return Ok(None);
+    };
+    MIGRATOR.undo(pool, applied.last().copied().unwrap_or(0)).await?;
+    Ok(Some(latest))
+}
+
+/// 检查数据库结构是否已执行全部迁移
+pub async fn ensure_schema_up_to_date(pool: &MySqlPool) -> Result<(), DatabaseInitError> {
+    let pending: Vec<i64> = migration_status(pool)
+        .await?
+        .into_iter()
+        .filter(|m| !m.applied)
+        .map(|m| m.version)
+        .collect();
+    if pending.is_empty() {
+        Ok(())
+    } else {
+        Err(DatabaseInitError::SchemaBehind(pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_embedded_migrations_are_reversible() {
+        let ups: Vec<i64> = MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()).map(|m| m.version).collect();
+        let downs: HashSet<i64> = MIGRATOR.iter().filter(|m| m.migration_type.is_down_migration()).map(|m| m.version).collect();
+        assert!(!ups.is_empty());
+        assert!(ups.windows(2).all(|w| w[0] < w[1]));
+        assert!(ups.iter().all(|v| downs.contains(v)));
+        assert!(MIGRATOR.iter().all(|m| !m.sql.contains("CREATE USER")));
+    }
+
+    #[test]
+    fn test_migration_statuses() {
+        let applied: HashSet<i64> = [1, 2].into_iter().collect();
+        let statuses = migration_statuses(&MIGRATOR, &applied);
+        assert_eq!(statuses.len(), MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()).count());
+        assert!(statuses[0].applied && statuses[1].applied);
+        assert!(statuses[2..].iter().all(|m| !m.applied));
+        assert_eq!(statuses[0].description, "users");
+    }
+
+    #[test]
+    fn test_migrate_command_from_args() {
+        assert_eq!(MigrateCommand::from_args(&args(&[])), None);
+        assert_eq!(MigrateCommand::from_args(&args(&["serve"])), None);
+        assert_eq!(MigrateCommand::from_args(&args(&["migrate", "up"])), Some(Ok(MigrateCommand::Up)));
+        assert_eq!(MigrateCommand::from_args(&args(&["migrate", "down"])), Some(Ok(MigrateCommand::Down)));
+        assert_eq!(MigrateCommand::from_args(&args(&["migrate", "status"])), Some(Ok(MigrateCommand::Status)));
+        assert!(matches!(MigrateCommand::from_args(&args(&["migrate"])), Some(Err(_))));
+    }
+}
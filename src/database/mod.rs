@@ -1,3 +1,5 @@
 pub mod connection;
+pub mod migration;
 
-pub use connection::init_db_pool;
\ No newline at end of file
+pub use connection::{connect_db, init_db_pool};
+pub use migration::{DatabaseInitError, MigrateCommand, MIGRATOR};
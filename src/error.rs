@@ -0,0 +1,383 @@
+//! 统一的 API 错误类型
+//!
+//! 处理器返回 `Result<T, AppError>`，各层错误通过 `From` 转换后直接用 `?` 传播。
+//! 错误响应统一为 JSON：`{code, message, details, request_id}`，
+//! 其中 code 为稳定的机器可读错误码，客户端应根据 code 而不是 message 分支处理
+
+use std::fmt;
+
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::middleware::request_id::current_request_id;
+use crate::services::{
+    address_service::AddressServiceError,
+    complaint_service::ComplaintServiceError,
+    coupon_service::CouponServiceError,
+    notification_service::NotificationServiceError,
+    order_service::OrderServiceError,
+    payment_provider::PaymentProviderError,
+    payment_service::PaymentServiceError,
+    review_service::ReviewServiceError,
+    schedule_service::ScheduleServiceError,
+    service_service::ServiceServiceError,
+    token_service::RefreshTokenError,
+    user_service::UserServiceError,
+    wallet_service::WalletServiceError,
+    worker_service::WorkerServiceError,
+};
+
+/// 错误码，序列化为大写下划线形式，例如 `NOT_FOUND`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// 请求参数格式错误或未通过业务校验
+    ValidationFailed,
+    /// 未登录或访问令牌无效
+    Unauthorized,
+    /// 用户名或密码错误
+    InvalidCredentials,
+    /// 刷新令牌无效、过期或已被使用
+    TokenInvalid,
+    /// 无权访问
+    Forbidden,
+    NotFound,
+    /// 资源当前状态不允许该操作
+    Conflict,
+    /// 违反唯一约束，资源已存在
+    AlreadyExists,
+    /// 第三方支付渠道返回错误
+    PaymentProviderError,
+    InternalError,
+}
+
+impl ErrorCode {
+    /// 错误码对应的默认 HTTP 状态码
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::ValidationFailed => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized | ErrorCode::InvalidCredentials | ErrorCode::TokenInvalid => {
+                StatusCode::UNAUTHORIZED
+            }
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict | ErrorCode::AlreadyExists => StatusCode::CONFLICT,
+            ErrorCode::PaymentProviderError => StatusCode::BAD_GATEWAY,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// 错误响应体
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct AppError {
+    status: StatusCode,
+    code: ErrorCode,
+    message: String,
+    details: Option<serde_json::Value>,
+    /// 仅写入日志的内部原因，不返回给客户端
+    source: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            status: code.status(),
+            code,
+            message: message.into(),
+            details: None,
+            source: None,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ValidationFailed, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unauthorized, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Forbidden, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Conflict, message)
+    }
+
+    /// 服务器内部错误，原因只写入日志
+    pub fn internal(source: impl fmt::Debug) -> Self {
+        Self {
+            source: Some(format!("{:?}", source)),
+            ..Self::new(ErrorCode::InternalError, "服务器内部错误")
+        }
+    }
+
+    /// 附加结构化的错误详情
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// 覆盖默认的 HTTP 状态码
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let request_id = current_request_id();
+        if self.status.is_server_error() {
+            tracing::error!(
+                "请求 {} 处理失败: {} {}",
+                request_id.as_deref().unwrap_or("-"),
+                self,
+                self.source.as_deref().unwrap_or_default()
+            );
+        }
+        let body = ErrorBody {
+            code: self.code,
+            message: self.message,
+            details: self.details,
+            request_id,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => AppError::not_found("资源不存在"),
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                AppError::new(ErrorCode::AlreadyExists, "数据已存在")
+            }
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                AppError::validation("关联的数据不存在")
+            }
+            _ => AppError::internal(error),
+        }
+    }
+}
+
+/// 请求体、查询参数和路径参数解析失败
+fn rejection(status: StatusCode, reason: String) -> AppError {
+    let status = if status.is_client_error() { status } else { StatusCode::BAD_REQUEST };
+    AppError::validation("请求参数格式错误")
+        .with_status(status)
+        .with_details(serde_json::json!({ "reason": reason }))
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(error: JsonRejection) -> Self {
+        rejection(error.status(), error.body_text())
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(error: QueryRejection) -> Self {
+        rejection(error.status(), error.body_text())
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(error: PathRejection) -> Self {
+        rejection(error.status(), error.body_text())
+    }
+}
+
+impl From<PaymentProviderError> for AppError {
+    fn from(error: PaymentProviderError) -> Self {
+        tracing::warn!("支付渠道错误: {}", error);
+        AppError::new(ErrorCode::PaymentProviderError, error.to_string())
+    }
+}
+
+impl From<RefreshTokenError> for AppError {
+    fn from(error: RefreshTokenError) -> Self {
+        match error {
+            RefreshTokenError::DatabaseError(e) => e.into(),
+            e => AppError::new(ErrorCode::TokenInvalid, e.to_string()),
+        }
+    }
+}
+
+impl From<UserServiceError> for AppError {
+    fn from(error: UserServiceError) -> Self {
+        match error {
+            UserServiceError::DatabaseError(sqlx::Error::RowNotFound) => AppError::not_found("用户不存在"),
+            UserServiceError::DatabaseError(e) => e.into(),
+            UserServiceError::AuthenticationError(msg) => AppError::new(ErrorCode::InvalidCredentials, msg),
+            UserServiceError::RegistrationError(msg) => AppError::new(ErrorCode::AlreadyExists, msg),
+            UserServiceError::PasswordHashError(e) => AppError::internal(e),
+            UserServiceError::TokenError(msg) => AppError::new(ErrorCode::TokenInvalid, msg),
+        }
+    }
+}
+
+/// 业务错误枚举大多由同一组变体构成，按变体名统一转换
+macro_rules! impl_from_service_error {
+    ($error:ident { $($variant:ident => $constructor:expr),* $(,)? }) => {
+        impl From<$error> for AppError {
+            fn from(error: $error) -> Self {
+                match error {
+                    $error::DatabaseError(e) => e.into(),
+                    $($error::$variant(msg) => $constructor(msg),)*
+                }
+            }
+        }
+    };
+}
+
+impl_from_service_error!(OrderServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Forbidden => AppError::forbidden,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(ReviewServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Forbidden => AppError::forbidden,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(ComplaintServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Forbidden => AppError::forbidden,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(PaymentServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Forbidden => AppError::forbidden,
+    Conflict => AppError::conflict,
+    ProviderError => AppError::from,
+});
+
+impl_from_service_error!(WalletServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Conflict => AppError::conflict,
+    ProviderError => AppError::from,
+});
+
+impl_from_service_error!(ServiceServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(AddressServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(WorkerServiceError {
+    NotFound => AppError::not_found,
+    ValidationError => AppError::validation,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(CouponServiceError {
+    NotFound => AppError::not_found,
+    Conflict => AppError::conflict,
+});
+
+impl_from_service_error!(NotificationServiceError {
+    NotFound => AppError::not_found,
+});
+
+impl_from_service_error!(ScheduleServiceError {
+    ValidationError => AppError::validation,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body_json(error: AppError) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_error_body_shape() {
+        let (status, body) = body_json(AppError::not_found("订单不存在")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "NOT_FOUND");
+        assert_eq!(body["message"], "订单不存在");
+        assert!(body["details"].is_null());
+        assert!(body["request_id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_internal_error_hides_source() {
+        let (status, body) = body_json(AppError::from(sqlx::Error::PoolTimedOut)).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["code"], "INTERNAL_ERROR");
+        assert_eq!(body["message"], "服务器内部错误");
+    }
+
+    #[test]
+    fn test_service_error_conversion() {
+        let error = AppError::from(sqlx::Error::RowNotFound);
+        assert_eq!((error.status(), error.code()), (StatusCode::NOT_FOUND, ErrorCode::NotFound));
+
+        let error = AppError::from(OrderServiceError::Conflict("订单状态已变更".to_string()));
+        assert_eq!((error.status(), error.message()), (StatusCode::CONFLICT, "订单状态已变更"));
+
+        let error = AppError::from(PaymentServiceError::ProviderError(PaymentProviderError::Rejected("余额不足".to_string())));
+        assert_eq!((error.status(), error.code()), (StatusCode::BAD_GATEWAY, ErrorCode::PaymentProviderError));
+
+        let error = AppError::from(UserServiceError::AuthenticationError("用户名或密码错误".to_string()));
+        assert_eq!((error.status(), error.code()), (StatusCode::UNAUTHORIZED, ErrorCode::InvalidCredentials));
+
+        let error = AppError::from(RefreshTokenError::Reused);
+        assert_eq!(error.code(), ErrorCode::TokenInvalid);
+    }
+}
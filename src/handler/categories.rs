@@ -0,0 +1,60 @@
+use axum::{
+    routing::{get, put},
+    Router,
+    extract::State,
+    http::StatusCode,
+};
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    error::AppError,
+    handler::extract::{Json, Path, Query},
+    services::service_service::ServiceService,
+    models::service::{CategoryNode, CategoryQuery, CategoryRequest, ServiceCategory},
+};
+use crate::middleware::{Admin, AuthUser, RequireRole};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(category_tree).post(create_category))
+        .route("/{id}", put(update_category).delete(delete_category))
+}
+
+/// 服务分类树，同级分类按 sort_order 排列
+pub async fn category_tree(
+    auth_user: AuthUser,
+    State(service_service): State<Arc<ServiceService>>,
+    Query(query): Query<CategoryQuery>,
+) -> Result<Json<Vec<CategoryNode>>, AppError> {
+    Ok(Json(service_service.category_tree(query.active_only.unwrap_or(true), auth_user.is_admin()).await?))
+}
+
+/// 新增分类 (管理员)
+pub async fn create_category(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Json(payload): Json<CategoryRequest>,
+) -> Result<(StatusCode, Json<ServiceCategory>), AppError> {
+    let category = service_service.create_category(&payload, admin.user_id).await?;
+    Ok((StatusCode::CREATED, Json(category)))
+}
+
+/// 修改分类 (管理员)
+pub async fn update_category(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CategoryRequest>,
+) -> Result<Json<ServiceCategory>, AppError> {
+    Ok(Json(service_service.update_category(id, &payload, admin.user_id).await?))
+}
+
+/// 停用分类 (管理员)
+pub async fn delete_category(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    service_service.delete_category(id, admin.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,106 @@
+use axum::{
+    routing::{get, post},
+    Router,
+    extract::State,
+    http::StatusCode,
+};
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    error::AppError,
+    handler::extract::{Json, Path, Query},
+    services::complaint_service::ComplaintService,
+    models::pagination::{Page, PageQuery},
+    models::review::{
+        AssignComplaintRequest, Complaint, ComplaintFilter, ComplaintNote, ComplaintNoteRequest,
+        CreateComplaintRequest, ResolveComplaintRequest,
+    },
+};
+use crate::middleware::{Admin, AuthUser, Customer, RequireRole};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_complaints).post(create_complaint))
+        .route("/mine", get(list_my_complaints))
+        .route("/{id}", get(get_complaint))
+        .route("/{id}/assign", post(assign_complaint))
+        .route("/{id}/notes", get(list_notes).post(add_note))
+        .route("/{id}/resolve", post(resolve_complaint))
+}
+
+/// 提交投诉 (客户)
+pub async fn create_complaint(
+    customer: RequireRole<Customer>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Json(payload): Json<CreateComplaintRequest>,
+) -> Result<(StatusCode, Json<Complaint>), AppError> {
+    let complaint = complaint_service.create_complaint(customer.user_id, &payload).await?;
+    Ok((StatusCode::CREATED, Json(complaint)))
+}
+
+/// 我提交的投诉 (客户)
+pub async fn list_my_complaints(
+    customer: RequireRole<Customer>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+) -> Result<Json<Vec<Complaint>>, AppError> {
+    Ok(Json(complaint_service.list_my_complaints(customer.user_id).await?))
+}
+
+/// 投诉队列 (管理员)，可按状态筛选
+pub async fn list_complaints(
+    _admin: RequireRole<Admin>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Query(filter): Query<ComplaintFilter>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<Complaint>>, AppError> {
+    Ok(Json(complaint_service.list_complaints(filter.status, &page).await?))
+}
+
+/// 投诉详情，投诉人、被投诉人和管理员可查看
+pub async fn get_complaint(
+    auth_user: AuthUser,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Complaint>, AppError> {
+    Ok(Json(complaint_service.get_complaint(id, auth_user.user_id, auth_user.is_admin()).await?))
+}
+
+/// 分配处理人 (管理员)
+pub async fn assign_complaint(
+    admin: RequireRole<Admin>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<AssignComplaintRequest>,
+) -> Result<Json<Complaint>, AppError> {
+    Ok(Json(complaint_service.assign(id, admin.user_id, &payload).await?))
+}
+
+/// 投诉内部备注 (管理员)
+pub async fn list_notes(
+    _admin: RequireRole<Admin>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<ComplaintNote>>, AppError> {
+    Ok(Json(complaint_service.list_notes(id).await?))
+}
+
+/// 添加内部备注 (管理员)
+pub async fn add_note(
+    admin: RequireRole<Admin>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ComplaintNoteRequest>,
+) -> Result<(StatusCode, Json<ComplaintNote>), AppError> {
+    let note = complaint_service.add_note(id, admin.user_id, &payload.note).await?;
+    Ok((StatusCode::CREATED, Json(note)))
+}
+
+/// 处理投诉 (管理员)，可附带退款或优惠券补偿
+pub async fn resolve_complaint(
+    admin: RequireRole<Admin>,
+    State(complaint_service): State<Arc<ComplaintService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ResolveComplaintRequest>,
+) -> Result<Json<Complaint>, AppError> {
+    Ok(Json(complaint_service.resolve(id, admin.user_id, &payload).await?))
+}
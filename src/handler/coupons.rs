@@ -0,0 +1,62 @@
+use axum::{
+    routing::{get, post},
+    Router,
+    extract::State,
+    http::StatusCode,
+};
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    error::AppError,
+    handler::extract::{Json, Path},
+    services::{
+        coupon_service::CouponService,
+        order_service::OrderService,
+        pricing::PriceBreakdown,
+    },
+    models::coupon::{Coupon, CouponPreviewRequest, UserCoupon, UserCouponDetail},
+};
+use crate::middleware::{AuthUser, Customer, RequireRole};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_available_coupons))
+        .route("/mine", get(list_my_coupons))
+        .route("/preview", post(preview_discount))
+        .route("/{id}/claim", post(claim_coupon))
+}
+
+/// 可领取的优惠券列表
+pub async fn list_available_coupons(
+    _auth_user: AuthUser,
+    State(coupon_service): State<Arc<CouponService>>,
+) -> Result<Json<Vec<Coupon>>, AppError> {
+    Ok(Json(coupon_service.list_available().await?))
+}
+
+/// 我领取的优惠券
+pub async fn list_my_coupons(
+    auth_user: AuthUser,
+    State(coupon_service): State<Arc<CouponService>>,
+) -> Result<Json<Vec<UserCouponDetail>>, AppError> {
+    Ok(Json(coupon_service.list_mine(auth_user.user_id).await?))
+}
+
+/// 领取优惠券 (客户)
+pub async fn claim_coupon(
+    customer: RequireRole<Customer>,
+    State(coupon_service): State<Arc<CouponService>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<UserCoupon>), AppError> {
+    let user_coupon = coupon_service.claim(customer.user_id, id).await?;
+    Ok((StatusCode::CREATED, Json(user_coupon)))
+}
+
+/// 预览使用优惠券后的订单金额 (客户)
+pub async fn preview_discount(
+    customer: RequireRole<Customer>,
+    State(order_service): State<Arc<OrderService>>,
+    Json(payload): Json<CouponPreviewRequest>,
+) -> Result<Json<PriceBreakdown>, AppError> {
+    Ok(Json(order_service.preview_price(customer.user_id, &payload).await?))
+}
@@ -0,0 +1,86 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Extension, Router,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use tokio_stream::{Stream, StreamExt};
+use crate::{
+    config::Config,
+    error::AppError,
+    handler::extract::{Json, Query},
+    services::event_hub::EventHub,
+    state::AppState,
+    models::event::{EventStreamQuery, RealtimeEvent, StreamTokenResponse},
+    utils::jwt::Claims,
+};
+use crate::middleware::{AuthUser, STREAM_TOKEN_TTL_SECS};
+
+/// 心跳间隔，避免代理因连接空闲而断开
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 客户端断线后的重连等待时间
+const RETRY_INTERVAL: Duration = Duration::from_secs(3);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(stream_events))
+        .route("/token", post(issue_stream_token))
+}
+
+/// 读取断线重连时携带的 Last-Event-ID，请求头优先于查询参数
+fn last_event_id(headers: &HeaderMap, query: &EventStreamQuery) -> Option<u64> {
+    headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .or(query.last_event_id)
+}
+
+fn to_sse_event(event: &RealtimeEvent) -> Event {
+    let data = serde_json::to_string(&event.payload).unwrap_or_else(|e| {
+        tracing::error!("序列化推送事件 {} 失败: {}", event.id, e);
+        "null".to_string()
+    });
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.payload.event_name())
+        .data(data)
+}
+
+/// 换取订阅事件流的短期令牌，供无法设置 Authorization 头的 EventSource 客户端使用
+pub async fn issue_stream_token(
+    Extension(claims): Extension<Claims>,
+    State(config): State<Arc<Config>>,
+) -> Result<Json<StreamTokenResponse>, AppError> {
+    let token = claims
+        .reissue("stream", STREAM_TOKEN_TTL_SECS)
+        .generate_token(&config.jwt.secret)
+        .map_err(AppError::internal)?;
+    Ok(Json(StreamTokenResponse { token, expires_in: STREAM_TOKEN_TTL_SECS }))
+}
+
+/// 当前用户的实时事件流 (SSE)
+/// 推送新通知和订单状态变更，定时发送心跳，重连时补发错过的事件
+pub async fn stream_events(
+    auth_user: AuthUser,
+    State(hub): State<Arc<dyn EventHub>>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = last_event_id(&headers, &query);
+    tracing::info!("用户 {} 订阅实时事件, Last-Event-ID: {:?}", auth_user.user_id, last_event_id);
+
+    let retry = tokio_stream::once(Ok(Event::default().retry(RETRY_INTERVAL)));
+    let events = hub
+        .subscribe(auth_user.user_id, last_event_id)
+        .await
+        .map(|event| Ok(to_sse_event(&event)));
+
+    Sse::new(retry.chain(events)).keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL).text("heartbeat"))
+}
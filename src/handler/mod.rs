@@ -2,19 +2,50 @@
 // 负责处理HTTP请求和响应，调用相应的服务层处理业务逻辑
 pub mod users;
 pub mod services;
+pub mod categories;
 pub mod orders;
+pub mod workers;
+pub mod coupons;
+pub mod complaints;
+pub mod notifications;
+pub mod events;
+pub mod extract;
 
 use axum::Router;
-use sqlx::mysql::MySqlPool;
+use crate::state::AppState;
 
-pub fn user_routes() -> Router<MySqlPool> {
+pub fn user_routes() -> Router<AppState> {
     users::routes()
 }
 
-pub fn service_routes() -> Router<MySqlPool> {
+pub fn service_routes() -> Router<AppState> {
     services::routes()
 }
 
-pub fn order_routes() -> Router<MySqlPool> {
+pub fn category_routes() -> Router<AppState> {
+    categories::routes()
+}
+
+pub fn order_routes() -> Router<AppState> {
     orders::routes()
-}
\ No newline at end of file
+}
+
+pub fn worker_routes() -> Router<AppState> {
+    workers::routes()
+}
+
+pub fn coupon_routes() -> Router<AppState> {
+    coupons::routes()
+}
+
+pub fn complaint_routes() -> Router<AppState> {
+    complaints::routes()
+}
+
+pub fn notification_routes() -> Router<AppState> {
+    notifications::routes()
+}
+
+pub fn event_routes() -> Router<AppState> {
+    events::routes()
+}
@@ -0,0 +1,63 @@
+use axum::{
+    routing::{get, post},
+    Router,
+    extract::State,
+    http::StatusCode,
+};
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    error::AppError,
+    handler::extract::{Json, Path, Query},
+    services::notification_service::NotificationService,
+    models::notification::{MarkAllReadResult, Notification, NotificationFilter, UnreadCount},
+    models::pagination::{CursorPage, CursorQuery},
+};
+use crate::middleware::AuthUser;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_notifications))
+        .route("/unread-count", get(unread_count))
+        .route("/read-all", post(mark_all_read))
+        .route("/{id}/read", post(mark_read))
+}
+
+/// 我的通知，游标分页，可按类型筛选
+pub async fn list_notifications(
+    auth_user: AuthUser,
+    State(notification_service): State<Arc<NotificationService>>,
+    Query(filter): Query<NotificationFilter>,
+    Query(cursor): Query<CursorQuery>,
+) -> Result<Json<CursorPage<Notification>>, AppError> {
+    let notifications = notification_service
+        .list_notifications(auth_user.user_id, filter.notification_type, &cursor)
+        .await?;
+    Ok(Json(notifications))
+}
+
+/// 未读通知数
+pub async fn unread_count(
+    auth_user: AuthUser,
+    State(notification_service): State<Arc<NotificationService>>,
+) -> Result<Json<UnreadCount>, AppError> {
+    Ok(Json(notification_service.unread_count(auth_user.user_id).await?))
+}
+
+/// 标记单条通知为已读
+pub async fn mark_read(
+    auth_user: AuthUser,
+    State(notification_service): State<Arc<NotificationService>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    notification_service.mark_read(auth_user.user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 标记全部通知为已读
+pub async fn mark_all_read(
+    auth_user: AuthUser,
+    State(notification_service): State<Arc<NotificationService>>,
+) -> Result<Json<MarkAllReadResult>, AppError> {
+    Ok(Json(notification_service.mark_all_read(auth_user.user_id).await?))
+}
@@ -1,42 +1,231 @@
 use axum::{
     routing::{get, post},
     Router,
-    extract::{State, Path},
-    Json, http::StatusCode,
+    extract::State,
+    http::StatusCode,
 };
-use sqlx::mysql::MySqlPool;
-use crate::{services::order_service::OrderService, models::order::Order};
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    services::{
+        dispatch_service::DispatchService,
+        order_service::OrderService,
+        payment_service::PaymentService,
+        review_service::ReviewService,
+    },
+    models::order::{CancelOrderRequest, CreateOrderRequest, Order, OrderPaymentStatus, OrderStatusHistory},
+    models::payment::{PayOrderRequest, Payment},
+    models::review::{CreateReviewRequest, Review},
+    models::user::UserType,
+    models::worker::{AssignWorkerRequest, WorkerCandidate},
+};
+use crate::error::AppError;
+use crate::handler::extract::{Json, Path};
+use crate::middleware::{forbidden, Admin, AuthUser, Customer, RequireRole, Worker};
 
-pub fn routes() -> Router<MySqlPool> {
+pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/", get(list_orders))
+        .route("/", get(list_orders).post(create_order))
         .route("/{id}", get(get_order))
+        .route("/{id}/history", get(get_order_history))
+        .route("/{id}/confirm", post(confirm_order))
+        .route("/{id}/candidates", get(list_candidates))
+        .route("/{id}/assign", post(assign_worker))
+        .route("/{id}/auto-assign", post(auto_assign_worker))
+        .route("/{id}/start", post(start_order))
+        .route("/{id}/complete", post(complete_order))
+        .route("/{id}/cancel", post(cancel_order))
+        .route("/{id}/pay", post(pay_order))
+        .route("/{id}/payments", get(list_payments))
+        .route("/{id}/refund", post(refund_order))
+        .route("/{id}/review", post(create_review))
 }
 
-pub async fn list_orders(State(pool): State<MySqlPool>) -> Result<Json<Vec<Order>>, (StatusCode, String)> {
-    let order_service = OrderService::new(pool);
-    match order_service.list_orders().await {
-        Ok(orders) => Ok(Json(orders)),
-        Err(e) => {
-            tracing::error!("获取订单列表错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
+/// 查询订单，仅下单客户、被指派的服务人员和管理员可见
+async fn find_visible_order(
+    order_service: &OrderService,
+    auth_user: &AuthUser,
+    id: String,
+) -> Result<Order, AppError> {
+    let order = order_service.get_order_by_id(id).await.map_err(|e| match e {
+        sqlx::Error::RowNotFound => AppError::not_found("订单不存在"),
+        e => e.into(),
+    })?;
+    let is_party = order.customer_id == auth_user.user_id || order.worker_id == Some(auth_user.user_id);
+    if !is_party && !auth_user.is_admin() {
+        return Err(forbidden());
     }
+    Ok(order)
+}
+
+/// 订单列表
+/// 管理员查看全部订单，服务人员查看指派给自己的订单，客户查看自己下的订单
+pub async fn list_orders(
+    auth_user: AuthUser,
+    State(order_service): State<Arc<OrderService>>,
+) -> Result<Json<Vec<Order>>, AppError> {
+    let result = match auth_user.user_type {
+        UserType::Admin => order_service.list_orders().await,
+        UserType::Worker => order_service.list_worker_orders(auth_user.user_id).await,
+        UserType::Customer => order_service.list_customer_orders(auth_user.user_id).await,
+    };
+    Ok(Json(result?))
 }
 
+/// 订单详情，仅下单客户、被指派的服务人员和管理员可查看
 pub async fn get_order(
-    State(pool): State<MySqlPool>,
+    auth_user: AuthUser,
+    State(order_service): State<Arc<OrderService>>,
     Path(id): Path<String>
-) -> Result<Json<Order>, (StatusCode, String)> {
-    let order_service = OrderService::new(pool);
-    match order_service.get_order_by_id(id).await {
-        Ok(order) => Ok(Json(order)),
-        Err(sqlx::Error::RowNotFound) => {
-            Err((StatusCode::NOT_FOUND, "订单不存在".to_string()))
-        }
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(find_visible_order(&order_service, &auth_user, id).await?))
+}
+
+/// 创建订单
+/// 金额由服务端计算，成功返回 201 和订单详情
+pub async fn create_order(
+    customer: RequireRole<Customer>,
+    State(order_service): State<Arc<OrderService>>,
+    Json(payload): Json<CreateOrderRequest>,
+) -> Result<(StatusCode, Json<Order>), AppError> {
+    let order = order_service.create_order(customer.user_id, &payload).await?;
+    Ok((StatusCode::CREATED, Json(order)))
+}
+
+/// 订单状态变更历史，仅订单相关方和管理员可查看
+pub async fn get_order_history(
+    auth_user: AuthUser,
+    State(order_service): State<Arc<OrderService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<OrderStatusHistory>>, AppError> {
+    find_visible_order(&order_service, &auth_user, id.clone()).await?;
+
+    Ok(Json(order_service.list_status_history(&id).await?))
+}
+
+/// 确认订单 (管理员)
+pub async fn confirm_order(
+    admin: RequireRole<Admin>,
+    State(order_service): State<Arc<OrderService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(order_service.confirm_order(&id, admin.user_id).await?))
+}
+
+/// 开始服务，记录实际开始时间
+pub async fn start_order(
+    worker: RequireRole<Worker>,
+    State(order_service): State<Arc<OrderService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(order_service.start_order(&id, worker.user_id, worker.is_admin()).await?))
+}
+
+/// 完成服务，记录实际结束时间
+pub async fn complete_order(
+    worker: RequireRole<Worker>,
+    State(order_service): State<Arc<OrderService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(order_service.complete_order(&id, worker.user_id, worker.is_admin()).await?))
+}
+
+/// 取消订单，必须填写取消原因
+/// 已支付的订单取消后自动原路退款，退款失败时订单保持已取消、已支付，由管理员重试退款
+pub async fn cancel_order(
+    auth_user: AuthUser,
+    State(order_service): State<Arc<OrderService>>,
+    State(payment_service): State<Arc<PaymentService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<CancelOrderRequest>,
+) -> Result<Json<Order>, AppError> {
+    let order = order_service
+        .cancel_order(&id, auth_user.user_id, auth_user.is_admin(), &payload.cancellation_reason)
+        .await?;
+
+    if order.payment_status != OrderPaymentStatus::Paid {
+        return Ok(Json(order));
+    }
+    match payment_service.refund_order(&id, auth_user.user_id).await {
+        Ok(refunded) => Ok(Json(refunded)),
         Err(e) => {
-            tracing::error!("获取订单错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
+            tracing::error!("订单 {} 取消后自动退款失败: {}", id, e);
+            Ok(Json(order))
         }
     }
-}
\ No newline at end of file
+}
+
+/// 支付订单 (下单客户)
+pub async fn pay_order(
+    customer: RequireRole<Customer>,
+    State(payment_service): State<Arc<PaymentService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<PayOrderRequest>,
+) -> Result<(StatusCode, Json<Payment>), AppError> {
+    let payment = payment_service
+        .pay_order(&id, customer.user_id, payload.payment_method)
+        .await?;
+    Ok((StatusCode::CREATED, Json(payment)))
+}
+
+/// 订单的支付记录，仅下单客户和管理员可查看
+pub async fn list_payments(
+    auth_user: AuthUser,
+    State(payment_service): State<Arc<PaymentService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Payment>>, AppError> {
+    let payments = payment_service
+        .list_payments(&id, auth_user.user_id, auth_user.is_admin())
+        .await?;
+    Ok(Json(payments))
+}
+
+/// 已取消订单退款 (管理员)，用于自动退款失败后的重试
+pub async fn refund_order(
+    admin: RequireRole<Admin>,
+    State(payment_service): State<Arc<PaymentService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(payment_service.refund_order(&id, admin.user_id).await?))
+}
+
+/// 订单的候选服务人员 (管理员)
+pub async fn list_candidates(
+    _admin: RequireRole<Admin>,
+    State(dispatch_service): State<Arc<DispatchService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<WorkerCandidate>>, AppError> {
+    Ok(Json(dispatch_service.list_candidates(&id).await?))
+}
+
+/// 手动派单 (管理员)
+pub async fn assign_worker(
+    admin: RequireRole<Admin>,
+    State(dispatch_service): State<Arc<DispatchService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssignWorkerRequest>,
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(dispatch_service.assign(&id, payload.worker_id, admin.user_id).await?))
+}
+
+/// 自动派单 (管理员)
+pub async fn auto_assign_worker(
+    admin: RequireRole<Admin>,
+    State(dispatch_service): State<Arc<DispatchService>>,
+    Path(id): Path<String>,
+) -> Result<Json<Order>, AppError> {
+    Ok(Json(dispatch_service.auto_assign(&id, admin.user_id).await?))
+}
+
+/// 评价订单，仅下单客户可在订单完成后评价一次
+pub async fn create_review(
+    customer: RequireRole<Customer>,
+    State(review_service): State<Arc<ReviewService>>,
+    Path(id): Path<String>,
+    Json(payload): Json<CreateReviewRequest>,
+) -> Result<(StatusCode, Json<Review>), AppError> {
+    let review = review_service
+        .create_review(customer.user_id, &id, &payload)
+        .await?;
+    Ok((StatusCode::CREATED, Json(review)))
+}
@@ -1,42 +1,128 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
-    extract::{State, Path},
-    Json, http::StatusCode,
+    extract::State,
+    http::StatusCode,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    error::AppError,
+    handler::extract::{Json, Path, Query},
+    middleware::{Admin, AuthUser, RequireRole},
+    services::{
+        service_service::ServiceService,
+        schedule_service::ScheduleService,
+    },
+    models::{
+        pagination::{Page, PageQuery},
+        service::{AddonRequest, Service, ServiceAddon, ServiceDetail, ServiceQuery, ServiceRequest},
+        worker::SlotAvailability,
+    },
 };
-use sqlx::mysql::MySqlPool;
-use crate::{services::service_service::ServiceService, models::service::Service};
 
-pub fn routes() -> Router<MySqlPool> {
+pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/", get(list_services))
-        .route("/{id}", get(get_service))
+        .route("/", get(list_services).post(create_service))
+        .route("/{id}", get(get_service).put(update_service).delete(delete_service))
+        .route("/{id}/slots", get(get_bookable_slots))
+        .route("/{id}/addons", post(create_addon))
+        .route("/{id}/addons/{addon_id}", put(update_addon).delete(delete_addon))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlotQuery {
+    pub date: chrono::NaiveDate,
+    /// 服务地址所在区县，提供时只统计服务区域包含该区县的服务人员
+    pub district: Option<String>,
 }
 
-pub async fn list_services(State(pool): State<MySqlPool>) -> Result<Json<Vec<Service>>, (StatusCode, String)> {
-    let service_service = ServiceService::new(pool);
-    match service_service.list_services().await {
-        Ok(services) => Ok(Json(services)),
-        Err(e) => {
-            tracing::error!("获取服务列表错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
+/// 按分类、关键词、价格区间、计价单位筛选服务，支持排序和分页
+pub async fn list_services(
+    auth_user: AuthUser,
+    State(service_service): State<Arc<ServiceService>>,
+    Query(query): Query<ServiceQuery>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<Service>>, AppError> {
+    Ok(Json(service_service.list_services(&query, &page, auth_user.is_admin()).await?))
 }
 
+/// 服务详情，包含启用的附加项
 pub async fn get_service(
-    State(pool): State<MySqlPool>,
+    State(service_service): State<Arc<ServiceService>>,
     Path(id): Path<i32>
-) -> Result<Json<Service>, (StatusCode, String)> {
-    let service_service = ServiceService::new(pool);
-    match service_service.get_service_by_id(id).await {
-        Ok(service) => Ok(Json(service)),
-        Err(sqlx::Error::RowNotFound) => {
-            Err((StatusCode::NOT_FOUND, "服务不存在".to_string()))
-        }
-        Err(e) => {
-            tracing::error!("获取服务错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
-}
\ No newline at end of file
+) -> Result<Json<ServiceDetail>, AppError> {
+    Ok(Json(service_service.get_service_detail(id).await?))
+}
+
+/// 新增服务 (管理员)
+pub async fn create_service(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Json(payload): Json<ServiceRequest>,
+) -> Result<(StatusCode, Json<Service>), AppError> {
+    let service = service_service.create_service(&payload, admin.user_id).await?;
+    Ok((StatusCode::CREATED, Json(service)))
+}
+
+/// 修改服务 (管理员)
+pub async fn update_service(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ServiceRequest>,
+) -> Result<Json<Service>, AppError> {
+    Ok(Json(service_service.update_service(id, &payload, admin.user_id).await?))
+}
+
+/// 下架服务 (管理员)
+pub async fn delete_service(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    service_service.delete_service(id, admin.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 新增附加项 (管理员)
+pub async fn create_addon(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<AddonRequest>,
+) -> Result<(StatusCode, Json<ServiceAddon>), AppError> {
+    let addon = service_service.create_addon(id, &payload, admin.user_id).await?;
+    Ok((StatusCode::CREATED, Json(addon)))
+}
+
+/// 修改附加项 (管理员)
+pub async fn update_addon(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path((id, addon_id)): Path<(i32, i32)>,
+    Json(payload): Json<AddonRequest>,
+) -> Result<Json<ServiceAddon>, AppError> {
+    Ok(Json(service_service.update_addon(id, addon_id, &payload, admin.user_id).await?))
+}
+
+/// 停用附加项 (管理员)
+pub async fn delete_addon(
+    admin: RequireRole<Admin>,
+    State(service_service): State<Arc<ServiceService>>,
+    Path((id, addon_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    service_service.delete_addon(id, addon_id, admin.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 查询服务在某天各时间段是否可预约，无需登录
+/// 统计条件与派单一致，下单前应带上服务地址所在区县
+pub async fn get_bookable_slots(
+    State(schedule_service): State<Arc<ScheduleService>>,
+    Path(id): Path<i32>,
+    Query(query): Query<SlotQuery>,
+) -> Result<Json<Vec<SlotAvailability>>, AppError> {
+    Ok(Json(schedule_service.bookable_slots(id, query.date, query.district.as_deref()).await?))
+}
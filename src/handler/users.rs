@@ -1,16 +1,24 @@
 use axum::{
-    routing::{get, post},
-    Router, Json, http::StatusCode,
-    extract::{State, Path},
+    routing::{get, post, put},
+    Router, http::{header, HeaderMap, StatusCode},
+    extract::State,
 };
-use std::env;
+use std::sync::Arc;
+
+use crate::state::AppState;
 
 use crate::models::auth::{LoginRequest, LoginResponse, RegisterRequest, RegisterResponse, RefreshTokenRequest, RefreshTokenResponse, LogoutRequest};
-use crate::utils::jwt::Claims;
+use crate::error::AppError;
+use crate::handler::extract::{Json, Path, Query};
 use crate::services::user_service::UserService;
-use crate::services::user_service::UserServiceError;
+use crate::services::wallet_service::WalletService;
+use crate::services::address_service::AddressService;
+use crate::models::pagination::PageQuery;
+use crate::models::user::{AddressRequest, UserAddress};
+use crate::models::wallet::{AdjustBalanceRequest, BalanceTransaction, TopUpRequest, Wallet, WalletTopup};
+use crate::middleware::{Admin, AuthUser, RequireRole};
 
-pub fn routes() -> Router<sqlx::mysql::MySqlPool> {
+pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_users))
         .route("/{id}", get(get_user))  // 修正：使用正确的花括号路径参数格式
@@ -18,132 +26,163 @@ pub fn routes() -> Router<sqlx::mysql::MySqlPool> {
         .route("/register", post(register))
         .route("/refresh", post(refresh_token))
         .route("/logout", post(logout))
+        .route("/me/wallet", get(get_my_wallet))
+        .route("/me/wallet/topup", post(top_up))
+        .route("/me/wallet/topups/{id}", get(get_topup))
+        .route("/{id}/wallet/adjustments", post(adjust_balance))
+        .route("/me/addresses", get(list_my_addresses).post(create_address))
+        .route("/me/addresses/{id}", put(update_address).delete(delete_address))
+}
+
+/// 从 User-Agent 头中读取设备信息
+fn device_info(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::USER_AGENT).and_then(|v| v.to_str().ok())
 }
 
-pub async fn list_users(State(pool): State<sqlx::mysql::MySqlPool>) -> Result<Json<Vec<crate::models::user::User>>, (StatusCode, String)> {
-    let user_service = UserService::new(pool);
-    match user_service.list_users().await {
-        Ok(users) => Ok(Json(users)),
-        Err(e) => {
-            tracing::error!("获取用户列表错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
+/// 用户列表，仅管理员可访问
+pub async fn list_users(
+    _admin: RequireRole<Admin>,
+    State(user_service): State<Arc<UserService>>,
+) -> Result<Json<Vec<crate::models::user::User>>, AppError> {
+    Ok(Json(user_service.list_users().await?))
 }
 
+/// 用户详情，客户只能查看本人信息
 pub async fn get_user(
-    State(pool): State<sqlx::mysql::MySqlPool>,
+    auth_user: AuthUser,
+    State(user_service): State<Arc<UserService>>,
     Path(id): Path<i32>
-) -> Result<Json<crate::models::user::User>, (StatusCode, String)> {
-    let user_service = UserService::new(pool);
-    match user_service.get_user_by_id(id).await {
-        Ok(user) => Ok(Json(user)),
-        Err(UserServiceError::DatabaseError(sqlx::Error::RowNotFound)) => {
-            Err((StatusCode::NOT_FOUND, "用户不存在".to_string()))
-        }
-        Err(e) => {
-            tracing::error!("获取用户错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
+) -> Result<Json<crate::models::user::User>, AppError> {
+    auth_user.ensure_self_or_admin(id)?;
+
+    Ok(Json(user_service.get_user_by_id(id).await?))
 }
 
 /// 登录接口
 /// 接受用户名/邮箱和密码，验证成功后返回JWT token和刷新令牌
 pub async fn login(
-    State(pool): State<sqlx::mysql::MySqlPool>,
+    State(user_service): State<Arc<UserService>>,
+    headers: HeaderMap,
     Json(payload): Json<LoginRequest>
-) -> Result<Json<LoginResponse>, (StatusCode, String)> {
-    let user_service = UserService::new(pool);
-    
-    match user_service.login(&payload).await {
-        Ok((token, refresh_token, user)) => {
-            let response = LoginResponse {
-                token,
-                refresh_token,
-                user,
-            };
-            
-            Ok(Json(response))
-        }
-        Err(UserServiceError::AuthenticationError(msg)) => {
-            Err((StatusCode::UNAUTHORIZED, msg))
-        }
-        Err(e) => {
-            tracing::error!("登录错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
+) -> Result<Json<LoginResponse>, AppError> {
+    let (token, refresh_token, user) = user_service.login(&payload, device_info(&headers)).await?;
+
+    Ok(Json(LoginResponse {
+        token,
+        refresh_token,
+        user,
+    }))
 }
 
 /// 注册接口
 /// 接受用户名、邮箱、手机号和密码，创建新用户
 pub async fn register(
-    State(pool): State<sqlx::mysql::MySqlPool>,
+    State(user_service): State<Arc<UserService>>,
     Json(payload): Json<RegisterRequest>
-) -> Result<(StatusCode, Json<RegisterResponse>), (StatusCode, String)> {
-    let user_service = UserService::new(pool);
-    
-    match user_service.register(&payload).await {
-        Ok(user) => {
-            let response = RegisterResponse {
-                user,
-            };
-            
-            Ok((StatusCode::CREATED, Json(response)))
-        }
-        Err(UserServiceError::RegistrationError(msg)) => {
-            Err((StatusCode::BAD_REQUEST, msg))
-        }
-        Err(e) => {
-            tracing::error!("注册错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
+) -> Result<(StatusCode, Json<RegisterResponse>), AppError> {
+    let user = user_service.register(&payload).await?;
+
+    Ok((StatusCode::CREATED, Json(RegisterResponse { user })))
 }
 
 /// 刷新令牌接口
 /// 使用刷新令牌获取新的访问令牌
 pub async fn refresh_token(
-    State(pool): State<sqlx::mysql::MySqlPool>,
+    State(user_service): State<Arc<UserService>>,
+    headers: HeaderMap,
     Json(payload): Json<RefreshTokenRequest>
-) -> Result<Json<RefreshTokenResponse>, (StatusCode, String)> {
-    let user_service = UserService::new(pool);
-    
-    match user_service.refresh_token(&payload).await {
-        Ok((token, refresh_token)) => {
-            let response = RefreshTokenResponse {
-                token,
-                refresh_token,
-            };
-            
-            Ok(Json(response))
-        }
-        Err(UserServiceError::TokenError(msg)) => {
-            Err((StatusCode::UNAUTHORIZED, msg))
-        }
-        Err(e) => {
-            tracing::error!("刷新令牌错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
+) -> Result<Json<RefreshTokenResponse>, AppError> {
+    let (token, refresh_token) = user_service.refresh_token(&payload, device_info(&headers)).await?;
+
+    Ok(Json(RefreshTokenResponse {
+        token,
+        refresh_token,
+    }))
 }
 
 /// 登出接口
 /// 使刷新令牌失效
 pub async fn logout(
-    State(pool): State<sqlx::mysql::MySqlPool>,
+    State(user_service): State<Arc<UserService>>,
     Json(payload): Json<LogoutRequest>
-) -> Result<Json<()>, (StatusCode, String)> {
-    let user_service = UserService::new(pool);
-    
-    match user_service.logout(&payload).await {
-        Ok(_) => {
-            Ok(Json(()))
-        }
-        Err(e) => {
-            tracing::error!("登出错误: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string()))
-        }
-    }
-}
\ No newline at end of file
+) -> Result<Json<()>, AppError> {
+    user_service.logout(&payload).await?;
+    Ok(Json(()))
+}
+
+/// 我的钱包：当前余额和分页的余额流水
+pub async fn get_my_wallet(
+    auth_user: AuthUser,
+    State(wallet_service): State<Arc<WalletService>>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Wallet>, AppError> {
+    Ok(Json(wallet_service.get_wallet(auth_user.user_id, &page).await?))
+}
+
+/// 余额充值，返回充值记录，渠道处理中时状态为 processing
+pub async fn top_up(
+    auth_user: AuthUser,
+    State(wallet_service): State<Arc<WalletService>>,
+    Json(payload): Json<TopUpRequest>,
+) -> Result<(StatusCode, Json<WalletTopup>), AppError> {
+    let topup = wallet_service.top_up(auth_user.user_id, &payload).await?;
+    Ok((StatusCode::CREATED, Json(topup)))
+}
+
+/// 查询充值记录，处理中的充值会向渠道同步结果
+pub async fn get_topup(
+    auth_user: AuthUser,
+    State(wallet_service): State<Arc<WalletService>>,
+    Path(id): Path<String>,
+) -> Result<Json<WalletTopup>, AppError> {
+    Ok(Json(wallet_service.get_topup(auth_user.user_id, &id).await?))
+}
+
+/// 管理员调整用户余额，流水中记录操作人
+pub async fn adjust_balance(
+    admin: RequireRole<Admin>,
+    State(wallet_service): State<Arc<WalletService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<AdjustBalanceRequest>,
+) -> Result<(StatusCode, Json<BalanceTransaction>), AppError> {
+    let transaction = wallet_service.adjust(id, admin.user_id, &payload).await?;
+    Ok((StatusCode::CREATED, Json(transaction)))
+}
+
+/// 我的地址列表，默认地址在前
+pub async fn list_my_addresses(
+    auth_user: AuthUser,
+    State(address_service): State<Arc<AddressService>>,
+) -> Result<Json<Vec<UserAddress>>, AppError> {
+    Ok(Json(address_service.list_addresses(auth_user.user_id).await?))
+}
+
+/// 新增地址
+pub async fn create_address(
+    auth_user: AuthUser,
+    State(address_service): State<Arc<AddressService>>,
+    Json(payload): Json<AddressRequest>,
+) -> Result<(StatusCode, Json<UserAddress>), AppError> {
+    let address = address_service.create_address(auth_user.user_id, &payload).await?;
+    Ok((StatusCode::CREATED, Json(address)))
+}
+
+/// 修改地址
+pub async fn update_address(
+    auth_user: AuthUser,
+    State(address_service): State<Arc<AddressService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<AddressRequest>,
+) -> Result<Json<UserAddress>, AppError> {
+    Ok(Json(address_service.update_address(auth_user.user_id, id, &payload).await?))
+}
+
+/// 删除地址，有进行中订单的地址不能删除
+pub async fn delete_address(
+    auth_user: AuthUser,
+    State(address_service): State<Arc<AddressService>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    address_service.delete_address(auth_user.user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,172 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+    extract::State,
+    http::StatusCode,
+};
+use std::sync::Arc;
+use crate::state::AppState;
+use crate::{
+    error::AppError,
+    handler::extract::{Json, Path, Query},
+    services::schedule_service::ScheduleService,
+    services::review_service::ReviewService,
+    services::worker_service::WorkerService,
+    models::pagination::{Page, PageQuery},
+    models::review::WorkerReview,
+    models::worker::{
+        ApplicationDecisionRequest, ApplicationFilter, ApplicationStatus, MarkUnavailableRequest, ScheduleQuery,
+        ScheduleUpdateResult, SetAvailabilityRequest, UpdateWorkerProfileRequest, WorkerApplication,
+        WorkerApplicationRequest, WorkerAvailabilityRequest, WorkerProfile, WorkerPublicProfile, WorkerSchedule,
+        WorkerSearchQuery,
+    },
+};
+use crate::middleware::{Admin, AuthUser, Customer, RequireRole, Worker};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(search_workers))
+        .route("/{id}", get(get_worker))
+        .route("/applications", get(list_applications).post(apply))
+        .route("/applications/mine", get(list_my_applications))
+        .route("/applications/{id}/approve", post(approve_application))
+        .route("/applications/{id}/reject", post(reject_application))
+        .route("/me/profile", get(get_my_profile).put(update_my_profile))
+        .route("/me/availability", put(set_my_accepting_orders))
+        .route("/me/schedules", get(list_my_schedules).put(set_my_availability))
+        .route("/me/schedules/unavailable", post(mark_my_unavailable))
+        .route("/{id}/reviews", get(list_worker_reviews))
+}
+
+/// 按分类、服务区域和最低评分搜索服务人员，无需登录
+pub async fn search_workers(
+    State(worker_service): State<Arc<WorkerService>>,
+    Query(query): Query<WorkerSearchQuery>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<WorkerPublicProfile>>, AppError> {
+    Ok(Json(worker_service.search_workers(&query, &page).await?))
+}
+
+/// 服务人员公开资料和接单统计，无需登录
+pub async fn get_worker(
+    State(worker_service): State<Arc<WorkerService>>,
+    Path(id): Path<i32>,
+) -> Result<Json<WorkerPublicProfile>, AppError> {
+    Ok(Json(worker_service.get_worker(id).await?))
+}
+
+/// 申请成为服务人员
+pub async fn apply(
+    customer: RequireRole<Customer>,
+    State(worker_service): State<Arc<WorkerService>>,
+    Json(payload): Json<WorkerApplicationRequest>,
+) -> Result<(StatusCode, Json<WorkerApplication>), AppError> {
+    let application = worker_service.apply(customer.user_id, &payload).await?;
+    Ok((StatusCode::CREATED, Json(application)))
+}
+
+/// 查看自己的入驻申请
+pub async fn list_my_applications(
+    auth_user: AuthUser,
+    State(worker_service): State<Arc<WorkerService>>,
+) -> Result<Json<Vec<WorkerApplication>>, AppError> {
+    Ok(Json(worker_service.list_my_applications(auth_user.user_id).await?))
+}
+
+/// 入驻申请列表 (管理员)，可按状态筛选
+pub async fn list_applications(
+    _admin: RequireRole<Admin>,
+    State(worker_service): State<Arc<WorkerService>>,
+    Query(filter): Query<ApplicationFilter>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<WorkerApplication>>, AppError> {
+    Ok(Json(worker_service.list_applications(filter.status, &page).await?))
+}
+
+/// 通过入驻申请 (管理员)
+pub async fn approve_application(
+    admin: RequireRole<Admin>,
+    State(worker_service): State<Arc<WorkerService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ApplicationDecisionRequest>,
+) -> Result<Json<WorkerApplication>, AppError> {
+    let application = worker_service
+        .review_application(id, admin.user_id, ApplicationStatus::Approved, &payload)
+        .await?;
+    Ok(Json(application))
+}
+
+/// 驳回入驻申请 (管理员)
+pub async fn reject_application(
+    admin: RequireRole<Admin>,
+    State(worker_service): State<Arc<WorkerService>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ApplicationDecisionRequest>,
+) -> Result<Json<WorkerApplication>, AppError> {
+    let application = worker_service
+        .review_application(id, admin.user_id, ApplicationStatus::Rejected, &payload)
+        .await?;
+    Ok(Json(application))
+}
+
+/// 查看自己的服务人员资料
+pub async fn get_my_profile(
+    worker: RequireRole<Worker>,
+    State(worker_service): State<Arc<WorkerService>>,
+) -> Result<Json<WorkerProfile>, AppError> {
+    Ok(Json(worker_service.get_my_profile(worker.user_id).await?))
+}
+
+/// 修改自己的时薪、简介、技能、服务区域和每日接单上限
+pub async fn update_my_profile(
+    worker: RequireRole<Worker>,
+    State(worker_service): State<Arc<WorkerService>>,
+    Json(payload): Json<UpdateWorkerProfileRequest>,
+) -> Result<Json<WorkerProfile>, AppError> {
+    Ok(Json(worker_service.update_my_profile(worker.user_id, &payload).await?))
+}
+
+/// 切换是否接单
+pub async fn set_my_accepting_orders(
+    worker: RequireRole<Worker>,
+    State(worker_service): State<Arc<WorkerService>>,
+    Json(payload): Json<WorkerAvailabilityRequest>,
+) -> Result<Json<WorkerProfile>, AppError> {
+    Ok(Json(worker_service.set_available(worker.user_id, payload.is_available).await?))
+}
+
+/// 查看自己在日期范围内的日程
+pub async fn list_my_schedules(
+    worker: RequireRole<Worker>,
+    State(schedule_service): State<Arc<ScheduleService>>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<Json<Vec<WorkerSchedule>>, AppError> {
+    Ok(Json(schedule_service.list_schedules(worker.user_id, query.start_date, query.end_date).await?))
+}
+
+/// 批量设置可预约时间，已被预订的时间段不会被覆盖，在结果中以冲突返回
+pub async fn set_my_availability(
+    worker: RequireRole<Worker>,
+    State(schedule_service): State<Arc<ScheduleService>>,
+    Json(payload): Json<SetAvailabilityRequest>,
+) -> Result<Json<ScheduleUpdateResult>, AppError> {
+    Ok(Json(schedule_service.set_availability(worker.user_id, &payload).await?))
+}
+
+/// 将若干日期整天标记为不可用 (请假)
+pub async fn mark_my_unavailable(
+    worker: RequireRole<Worker>,
+    State(schedule_service): State<Arc<ScheduleService>>,
+    Json(payload): Json<MarkUnavailableRequest>,
+) -> Result<Json<ScheduleUpdateResult>, AppError> {
+    Ok(Json(schedule_service.mark_unavailable(worker.user_id, &payload).await?))
+}
+
+/// 服务人员收到的评价，分页返回，匿名评价隐藏评价人
+pub async fn list_worker_reviews(
+    State(review_service): State<Arc<ReviewService>>,
+    Path(id): Path<i32>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<WorkerReview>>, AppError> {
+    Ok(Json(review_service.list_worker_reviews(id, &page).await?))
+}
@@ -5,8 +5,14 @@ pub mod handler;
 pub mod database;
 pub mod services;
 pub mod repositories;
+pub mod middleware;
+pub mod error;
+pub mod state;
 
 // 重新导出主要模块，方便在main.rs和其他crate中使用
-pub use config::{log_init, AppState, init_app_state};
-pub use database::init_db_pool;
-pub use handler::{user_routes, service_routes, order_routes};
\ No newline at end of file
+pub use config::{log_init, Config};
+pub use state::{AppState, Components};
+pub use database::{connect_db, init_db_pool};
+pub use handler::{user_routes, service_routes, category_routes, order_routes, worker_routes, coupon_routes, complaint_routes, notification_routes, event_routes};
+pub use middleware::{auth_interceptor, request_id_interceptor, AuthUser};
+pub use error::{AppError, ErrorCode};
@@ -0,0 +1,211 @@
+//! 认证中间件
+//!
+//! 校验请求中的 Bearer 访问令牌，并将解析出的 Claims 放入请求扩展，
+//! 供处理器通过 `AuthUser` 提取器读取当前用户信息。
+//! 浏览器 `EventSource` 无法设置请求头，事件流接口额外接受查询参数中的短期 stream 令牌
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, Query, Request, State},
+    http::{header, request::Parts, Method, Uri},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::user::UserType;
+use crate::utils::jwt::Claims;
+
+/// 事件流令牌的有效期 (秒)，只用于建立连接，连接建立后不再校验
+pub const STREAM_TOKEN_TTL_SECS: usize = 60;
+
+/// 可以通过查询参数 token 传入 stream 令牌的接口 (方法, 路径)
+const STREAM_ROUTE: (Method, &str) = (Method::GET, "/events");
+
+/// 无需登录即可访问的接口 (方法, 路径)
+/// 路径为 /api 之后的部分，`{name}` 形式的路径段匹配任意非空段
+const PUBLIC_ROUTES: &[(Method, &str)] = &[
+    (Method::POST, "/users/login"),
+    (Method::POST, "/users/register"),
+    (Method::POST, "/users/refresh"),
+    (Method::POST, "/users/logout"),
+    (Method::GET, "/services/{id}/slots"),
+    (Method::GET, "/workers/{id}/reviews"),
+    (Method::GET, "/workers"),
+    (Method::GET, "/workers/{id}"),
+];
+
+/// 路径参数只匹配数字ID，避免 /workers/{id} 误放行 /workers/applications 之类的静态路由
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.split('/');
+    let mut path_segments = path.split('/');
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (None, None) => return true,
+            (Some(p), Some(s))
+                if p.starts_with('{') && p.ends_with('}') && !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => {}
+            (Some(p), Some(s)) if p == s => {}
+            _ => return false,
+        }
+    }
+}
+
+/// 去掉 /api 前缀和末尾的斜杠
+fn api_path(path: &str) -> &str {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    path.trim_end_matches('/')
+}
+
+fn is_public(method: &Method, path: &str) -> bool {
+    let path = api_path(path);
+    PUBLIC_ROUTES
+        .iter()
+        .any(|(m, p)| m == method && matches_pattern(p, path))
+}
+
+fn is_stream_route(method: &Method, path: &str) -> bool {
+    *method == STREAM_ROUTE.0 && api_path(path) == STREAM_ROUTE.1
+}
+
+#[derive(serde::Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// 从查询参数 token 中取出令牌
+fn query_token(uri: &Uri) -> Option<String> {
+    let Query(query) = Query::<TokenQuery>::try_from_uri(uri).ok()?;
+    query.token.filter(|token| !token.is_empty())
+}
+
+/// 从 Authorization 头中取出 Bearer 令牌
+fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let token = value
+        .strip_prefix("Bearer ")
+        .or_else(|| value.strip_prefix("bearer "))?
+        .trim();
+    if token.is_empty() { None } else { Some(token) }
+}
+
+fn unauthenticated() -> AppError {
+    AppError::unauthorized("未登录或令牌无效")
+}
+
+/// 认证拦截器
+/// 公开接口直接放行，其余接口必须携带有效的 access 令牌；
+/// 事件流接口未携带 Authorization 头时可以在查询参数 token 中传入 stream 令牌
+pub async fn auth_interceptor(
+    State(config): State<Arc<Config>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if is_public(request.method(), request.uri().path()) {
+        return Ok(next.run(request).await);
+    }
+
+    let (token, token_type) = match bearer_token(request.headers()) {
+        Some(token) => (token.to_string(), "access"),
+        None if is_stream_route(request.method(), request.uri().path()) => {
+            (query_token(request.uri()).ok_or_else(unauthenticated)?, "stream")
+        }
+        None => return Err(unauthenticated()),
+    };
+    let claims = Claims::validate_token(&token, &config.jwt.secret, Some(token_type))
+        .map_err(|e| {
+            tracing::warn!("令牌验证失败: {} {:?}", request.uri().path(), e);
+            unauthenticated()
+        })?;
+
+    tracing::info!("权限验证通过: {} 用户ID: {}", request.uri().path(), claims.user_id);
+    request.extensions_mut().insert(claims);
+
+    Ok(next.run(request).await)
+}
+
+/// 当前登录用户
+/// 由 `auth_interceptor` 写入的 Claims 构造，未经过认证的请求返回 401
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: i32,
+    pub username: String,
+    pub user_type: UserType,
+}
+
+impl From<Claims> for AuthUser {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: claims.user_id,
+            username: claims.username,
+            user_type: claims.user_type,
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Claims>()
+            .cloned()
+            .map(AuthUser::from)
+            .ok_or_else(unauthenticated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_routes() {
+        assert!(is_public(&Method::POST, "/users/login"));
+        assert!(is_public(&Method::POST, "/api/users/register"));
+        assert!(is_public(&Method::POST, "/api/users/refresh/"));
+        assert!(!is_public(&Method::GET, "/api/users/login"));
+        assert!(!is_public(&Method::GET, "/api/users"));
+        assert!(!is_public(&Method::GET, "/api/orders/1"));
+        assert!(is_public(&Method::GET, "/api/services/3/slots"));
+        assert!(!is_public(&Method::GET, "/api/services//slots"));
+        assert!(!is_public(&Method::GET, "/api/services/3/slots/extra"));
+        assert!(!is_public(&Method::POST, "/api/services/3/slots"));
+        assert!(is_public(&Method::GET, "/api/workers"));
+        assert!(is_public(&Method::GET, "/api/workers/7"));
+        assert!(!is_public(&Method::GET, "/api/workers/applications"));
+        assert!(!is_public(&Method::GET, "/api/workers/me/profile"));
+    }
+
+    #[test]
+    fn test_stream_route_and_query_token() {
+        assert!(is_stream_route(&Method::GET, "/api/events"));
+        assert!(is_stream_route(&Method::GET, "/api/events/"));
+        assert!(!is_stream_route(&Method::POST, "/api/events/token"));
+        assert!(!is_stream_route(&Method::GET, "/api/orders"));
+
+        let uri: Uri = "/api/events?last_event_id=3&token=abc.def".parse().unwrap();
+        assert_eq!(query_token(&uri).as_deref(), Some("abc.def"));
+        let uri: Uri = "/api/events?token=".parse().unwrap();
+        assert_eq!(query_token(&uri), None);
+        let uri: Uri = "/api/events".parse().unwrap();
+        assert_eq!(query_token(&uri), None);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = axum::http::HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, "Bearer abc.def".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("abc.def"));
+
+        headers.insert(header::AUTHORIZATION, "Basic abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+    }
+}
@@ -0,0 +1,11 @@
+//! 中间件模块
+//!
+//! 包含请求拦截器和基于请求扩展的提取器
+
+pub mod auth;
+pub mod role;
+pub mod request_id;
+
+pub use auth::{auth_interceptor, AuthUser, STREAM_TOKEN_TTL_SECS};
+pub use request_id::{current_request_id, request_id_interceptor};
+pub use role::{forbidden, Admin, Customer, RequireRole, Role, Worker};
@@ -0,0 +1,73 @@
+//! 请求ID中间件
+//!
+//! 为每个请求分配请求ID：沿用客户端传入的 `x-request-id`，否则生成新的 UUID。
+//! 请求ID写入响应头，并在处理期间保存在任务局部变量中，供错误响应和日志读取
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 客户端传入的请求ID最大长度，超出则重新生成
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的请求ID，不在请求上下文中时返回 None
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+fn incoming_request_id(request: &Request) -> Option<String> {
+    let value = request.headers().get(&REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| value.to_string())
+}
+
+/// 请求ID拦截器，应作为最外层中间件注册
+pub async fn request_id_interceptor(request: Request, next: Next) -> Response {
+    let request_id = incoming_request_id(&request).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let header_value = HeaderValue::from_str(&request_id).ok();
+
+    let mut response = REQUEST_ID.scope(request_id, next.run(request)).await;
+    if let Some(value) = header_value {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_with_id(id: &str) -> Request {
+        Request::builder()
+            .header(REQUEST_ID_HEADER, id)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_incoming_request_id() {
+        assert_eq!(incoming_request_id(&request_with_id("abc-123")), Some("abc-123".to_string()));
+        assert_eq!(incoming_request_id(&request_with_id("has space")), None);
+        assert_eq!(incoming_request_id(&request_with_id(&"a".repeat(65))), None);
+        let request = Request::builder().body(axum::body::Body::empty()).unwrap();
+        assert_eq!(incoming_request_id(&request), None);
+    }
+
+    #[tokio::test]
+    async fn test_current_request_id_scope() {
+        assert_eq!(current_request_id(), None);
+        let id = REQUEST_ID.scope("req-1".to_string(), async { current_request_id() }).await;
+        assert_eq!(id.as_deref(), Some("req-1"));
+    }
+}
@@ -0,0 +1,129 @@
+//! 基于角色的访问控制
+//!
+//! 提供 `RequireRole<R>` 提取器，在处理器签名中声明接口所需的用户类型，
+//! 角色不符时统一返回 403
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use super::auth::AuthUser;
+use crate::error::AppError;
+use crate::models::user::UserType;
+
+/// 统一的权限不足响应
+pub fn forbidden() -> AppError {
+    AppError::forbidden("权限不足")
+}
+
+/// 角色标记
+/// `allows` 判断给定的用户类型是否满足该角色
+pub trait Role {
+    fn allows(user_type: UserType) -> bool;
+}
+
+/// 系统管理员
+pub struct Admin;
+
+/// 服务人员 (管理员同样放行)
+pub struct Worker;
+
+/// 普通客户 (管理员同样放行)
+pub struct Customer;
+
+impl Role for Admin {
+    fn allows(user_type: UserType) -> bool {
+        user_type == UserType::Admin
+    }
+}
+
+impl Role for Worker {
+    fn allows(user_type: UserType) -> bool {
+        matches!(user_type, UserType::Worker | UserType::Admin)
+    }
+}
+
+impl Role for Customer {
+    fn allows(user_type: UserType) -> bool {
+        matches!(user_type, UserType::Customer | UserType::Admin)
+    }
+}
+
+impl AuthUser {
+    pub fn is_admin(&self) -> bool {
+        Admin::allows(self.user_type)
+    }
+
+    /// 只允许访问本人数据，管理员不受限制
+    pub fn ensure_self_or_admin(&self, user_id: i32) -> Result<(), AppError> {
+        if self.user_id == user_id || self.is_admin() {
+            Ok(())
+        } else {
+            Err(forbidden())
+        }
+    }
+}
+
+/// 要求当前用户具备角色 `R`
+///
+/// ```ignore
+/// async fn list_users(RequireRole(user, ..): RequireRole<Admin>) { ... }
+/// ```
+pub struct RequireRole<R: Role>(pub AuthUser, PhantomData<R>);
+
+impl<R: Role> Deref for RequireRole<R> {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    S: Send + Sync,
+    R: Role,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if R::allows(user.user_type) {
+            Ok(RequireRole(user, PhantomData))
+        } else {
+            tracing::warn!("用户 {} ({}) 访问受限接口: {}", user.user_id, user.user_type, parts.uri.path());
+            Err(forbidden())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(user_id: i32, user_type: UserType) -> AuthUser {
+        AuthUser {
+            user_id,
+            username: "u".to_string(),
+            user_type,
+        }
+    }
+
+    #[test]
+    fn test_role_allows() {
+        assert!(Admin::allows(UserType::Admin));
+        assert!(!Admin::allows(UserType::Worker));
+        assert!(Worker::allows(UserType::Worker));
+        assert!(!Worker::allows(UserType::Customer));
+        assert!(Customer::allows(UserType::Customer));
+        assert!(Customer::allows(UserType::Admin));
+    }
+
+    #[test]
+    fn test_ensure_self_or_admin() {
+        assert!(user(1, UserType::Customer).ensure_self_or_admin(1).is_ok());
+        assert!(user(1, UserType::Customer).ensure_self_or_admin(2).is_err());
+        assert!(user(9, UserType::Admin).ensure_self_or_admin(2).is_ok());
+    }
+}
@@ -0,0 +1,75 @@
+//! 审计日志相关模型
+//!
+//! 对应数据库中的 audit_logs 表，记录管理员对目录数据的每一次写操作
+
+use serde::{Deserialize, Serialize};
+
+/// 审计对象类型
+/// 与 audit_logs.entity_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum AuditEntity {
+    Category,
+    Service,
+    Addon,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Category => "category",
+            AuditEntity::Service => "service",
+            AuditEntity::Addon => "addon",
+        }
+    }
+}
+
+/// 审计操作类型
+/// 与 audit_logs.action 枚举值一一对应，删除均为软删除
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+/// 待写入的审计日志
+#[derive(Debug, Clone)]
+pub struct NewAuditLog {
+    pub operator_id: i32,
+    pub entity: AuditEntity,
+    pub entity_id: i32,
+    pub action: AuditAction,
+    pub snapshot: serde_json::Value,
+}
+
+impl NewAuditLog {
+    pub fn new(
+        operator_id: i32,
+        entity: AuditEntity,
+        entity_id: i32,
+        action: AuditAction,
+        snapshot: &impl Serialize,
+    ) -> Self {
+        Self {
+            operator_id,
+            entity,
+            entity_id,
+            action,
+            snapshot: serde_json::to_value(snapshot).unwrap_or(serde_json::Value::Null),
+        }
+    }
+}
@@ -4,11 +4,37 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::money::Money;
+
+/// 折扣类型
+/// 与 coupons.discount_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DiscountType {
+    /// 百分比折扣
+    Percentage,
+    /// 固定金额折扣
+    Fixed,
+    /// 服务折扣
+    Service,
+}
+
+impl DiscountType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscountType::Percentage => "percentage",
+            DiscountType::Fixed => "fixed",
+            DiscountType::Service => "service",
+        }
+    }
+}
+
 /// 优惠券模型
 /// 对应 coupons 表
 /// 
 /// 定义系统中可使用的优惠券模板
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Coupon {
     /// 优惠券ID (主键)
     pub coupon_id: i32,
@@ -19,19 +45,17 @@ pub struct Coupon {
     /// 优惠券名称
     pub coupon_name: String,
     
-    /// 折扣类型，枚举值:
-    /// - "percentage": 百分比折扣
-    /// - "fixed": 固定金额折扣
-    /// - "service": 服务折扣
-    pub discount_type: String,
+    /// 折扣类型
+    pub discount_type: DiscountType,
     
     /// 折扣值
-    pub discount_value: f64,
+    pub discount_value: Money,
     
     /// 最低订单金额要求
-    pub min_order_amount: f64,
+    pub min_order_amount: Money,
     
     /// 适用服务列表 (JSON格式)
+    #[sqlx(json(nullable))]
     pub applicable_services: Option<Vec<i32>>,
     
     /// 有效期开始日期
@@ -57,7 +81,7 @@ pub struct Coupon {
 /// 对应 user_coupons 表
 /// 
 /// 记录用户获得的优惠券及其使用情况
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct UserCoupon {
     /// 用户优惠券ID (主键)
     pub user_coupon_id: i32,
@@ -82,4 +106,47 @@ pub struct UserCoupon {
     
     /// 创建时间
     pub created_at: chrono::NaiveDateTime,
-}
\ No newline at end of file
+}
+
+/// 用户优惠券及其对应的优惠券信息
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserCouponDetail {
+    /// 用户优惠券ID
+    pub user_coupon_id: i32,
+
+    /// 关联订单ID (已使用时)
+    pub order_id: Option<String>,
+
+    /// 是否已使用
+    pub is_used: bool,
+
+    /// 使用时间 (可选)
+    pub used_at: Option<chrono::NaiveDateTime>,
+
+    /// 过期时间
+    pub expires_at: chrono::NaiveDate,
+
+    /// 领取时间
+    pub claimed_at: chrono::NaiveDateTime,
+
+    /// 优惠券信息
+    #[sqlx(flatten)]
+    pub coupon: Coupon,
+}
+
+/// 优惠预览请求，与下单请求的计价字段一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CouponPreviewRequest {
+    /// 服务ID
+    pub service_id: i32,
+
+    /// 服务时长
+    pub duration: f64,
+
+    /// 附加服务 (可选)
+    #[serde(default)]
+    pub addons: Vec<crate::models::order::OrderAddonRequest>,
+
+    /// 优惠券码
+    pub coupon_code: String,
+}
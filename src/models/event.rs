@@ -0,0 +1,63 @@
+//! 实时推送事件模型
+//!
+//! 事件通过 SSE 推送给在线用户，事件ID单调递增，
+//! 客户端断线重连时携带 Last-Event-ID 即可补收错过的事件
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::NaiveDateTime;
+use crate::models::notification::Notification;
+use crate::models::order::OrderStatus;
+
+/// 订单状态变更事件
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderStatusEvent {
+    pub order_id: String,
+    pub order_status: OrderStatus,
+    pub worker_id: Option<i32>,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 推送事件内容，序列化后作为 SSE 的 data 字段
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum EventPayload {
+    /// 新的站内通知
+    Notification(Notification),
+    /// 订单状态变更
+    OrderStatus(OrderStatusEvent),
+}
+
+impl EventPayload {
+    /// SSE 的 event 字段
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            EventPayload::Notification(_) => "notification",
+            EventPayload::OrderStatus(_) => "order_status",
+        }
+    }
+}
+
+/// 订阅事件流使用的短期令牌
+/// 浏览器 EventSource 无法设置 Authorization 头，先用 access 令牌换取该令牌，
+/// 再以 `GET /api/events?token=...` 建立连接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamTokenResponse {
+    pub token: String,
+    /// 有效期 (秒)
+    pub expires_in: usize,
+}
+
+/// 订阅事件流的查询参数
+/// 无法设置 Last-Event-ID 请求头的客户端可以通过 last_event_id 参数传入
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventStreamQuery {
+    pub last_event_id: Option<u64>,
+}
+
+/// 发送给某个用户的一条推送事件
+#[derive(Debug, Clone, Serialize)]
+pub struct RealtimeEvent {
+    pub id: u64,
+    pub user_id: i32,
+    pub payload: EventPayload,
+}
@@ -1,19 +1,29 @@
+pub mod audit;
 pub mod auth;
 pub mod coupon;
+pub mod event;
+pub mod money;
 pub mod notification;
 pub mod order;
+pub mod pagination;
 pub mod payment;
+pub mod refresh_token;
 pub mod review;
 pub mod service;
 pub mod user;
+pub mod wallet;
 pub mod worker;
 
 
-pub use user::{User, UserAddress};
+pub use user::{User, UserAddress, UserStatus, UserType};
 pub use service::{ServiceCategory, Service, ServiceAddon};
-pub use worker::{WorkerProfile, WorkerSchedule};
-pub use order::{Order, OrderAddon};
-pub use coupon::{Coupon, UserCoupon};
-pub use review::{Review, Complaint};
-pub use payment::Payment;
-pub use notification::Notification;
\ No newline at end of file
+pub use worker::{ScheduleStatus, TimeSlot, WorkerProfile, WorkerSchedule, WorkerCandidate};
+pub use order::{Order, OrderAddon, OrderPaymentStatus, OrderStatus, OrderStatusHistory};
+pub use coupon::{Coupon, DiscountType, UserCoupon};
+pub use review::{Review, Complaint, ComplaintStatus, ComplaintType, CompensationType};
+pub use payment::{Payment, PaymentMethod, PaymentStatus};
+pub use notification::{NewNotification, Notification, NotificationType};
+pub use refresh_token::RefreshToken;
+pub use money::Money;
+pub use pagination::{CursorPage, CursorQuery, Page, PageQuery};
+pub use wallet::{BalanceTransaction, ReferenceType, TransactionType, WalletTopup};
\ No newline at end of file
@@ -0,0 +1,289 @@
+//! 金额类型
+//!
+//! 数据库中的金额列均为 DECIMAL(10, 2)，`Money` 以「分」为单位的整数保存金额，
+//! 加减和按数量相乘都是精确运算，只有按比例计算 (时长、折扣) 时才需要舍入，
+//! 舍入规则为四舍五入到分 (0.5 分远离零)。
+//!
+//! JSON 中金额序列化为两位小数的字符串，如 "12.50"；
+//! 反序列化接受字符串 (单位为元) 或整数 (单位为分)，不接受浮点数
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{
+    decode::Decode,
+    encode::{Encode, IsNull},
+    error::BoxDynError,
+    mysql::{MySql, MySqlTypeInfo, MySqlValueRef},
+    types::Type,
+    TypeInfo,
+};
+
+/// 金额，单位为分
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    /// 以分为单位构造
+    pub const fn from_fen(fen: i64) -> Self {
+        Money(fen)
+    }
+
+    /// 以整元构造
+    pub const fn from_yuan(yuan: i64) -> Self {
+        Money(yuan * 100)
+    }
+
+    /// 金额 (分)
+    pub const fn fen(self) -> i64 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub const fn abs(self) -> Money {
+        Money(self.0.abs())
+    }
+
+    /// 乘以整数数量，结果精确
+    pub const fn times(self, quantity: i64) -> Money {
+        Money(self.0 * quantity)
+    }
+
+    /// 乘以 numerator / denominator，四舍五入到分
+    pub fn mul_ratio(self, numerator: i64, denominator: i64) -> Money {
+        assert!(denominator != 0, "denominator must not be zero");
+        let product = i128::from(self.0) * i128::from(numerator);
+        let denominator = i128::from(denominator);
+        let quotient = product / denominator;
+        let remainder = product % denominator;
+        let rounded = if remainder.abs() * 2 >= denominator.abs() {
+            // 远离零舍入
+            if (product < 0) == (denominator < 0) { quotient + 1 } else { quotient - 1 }
+        } else {
+            quotient
+        };
+        Money(rounded as i64)
+    }
+
+    /// 按百分比计算，percent 以金额形式表示 (如 20.00 表示 20%)，四舍五入到分
+    pub fn percent(self, percent: Money) -> Money {
+        self.mul_ratio(percent.0, 100 * 100)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let fen = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, fen / 100, fen % 100)
+    }
+}
+
+impl FromStr for Money {
+    type Err = String;
+
+    /// 解析以元为单位的十进制字符串，最多两位有效小数
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("无效的金额: {}", s);
+        let trimmed = s.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(invalid());
+        }
+        if !int_part.bytes().all(|b| b.is_ascii_digit()) || !frac_part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        // 超出分的位数只允许为 0 (如数据库聚合结果 12.3400)
+        if frac_part.len() > 2 && frac_part[2..].bytes().any(|b| b != b'0') {
+            return Err(format!("金额最多保留两位小数: {}", s));
+        }
+
+        let yuan: i64 = if int_part.is_empty() { 0 } else { int_part.parse().map_err(|_| invalid())? };
+        let mut fen_digits: String = frac_part.chars().take(2).collect();
+        while fen_digits.len() < 2 {
+            fen_digits.push('0');
+        }
+        let fen: i64 = fen_digits.parse().map_err(|_| invalid())?;
+        let total = yuan
+            .checked_mul(100)
+            .and_then(|v| v.checked_add(fen))
+            .ok_or_else(invalid)?;
+        Ok(Money(if negative { -total } else { total }))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+
+        impl de::Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("以元为单位的金额字符串或以分为单位的整数")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+                Ok(Money(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+                i64::try_from(v).map(Money).map_err(|_| E::custom("金额超出范围"))
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+// MySQL 的 DECIMAL 在文本和二进制协议中都以十进制字符串传输，
+// 这里直接按字符串编解码，不经过浮点数。
+// 参数以字符串绑定，写入 DECIMAL 列时精确转换；
+// 在 SQL 算术表达式中使用时需写成 CAST(? AS DECIMAL(10, 2))，否则 MySQL 会按 DOUBLE 计算
+impl Type<MySql> for Money {
+    fn type_info() -> MySqlTypeInfo {
+        <str as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        ty.name() == "DECIMAL" || <str as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, MySql> for Money {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        <String as Encode<MySql>>::encode(self.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, MySql> for Money {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as Decode<MySql>>::decode(value)?;
+        Ok(s.parse::<Money>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(s: &str) -> Money {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(m("12.5"), Money::from_fen(1250));
+        assert_eq!(m("0.05").to_string(), "0.05");
+        assert_eq!(m("-3.10").to_string(), "-3.10");
+        assert_eq!(m("100"), Money::from_yuan(100));
+        assert_eq!(m(".5"), Money::from_fen(50));
+        assert_eq!(m("12.3400"), Money::from_fen(1234));
+        assert!("1.005".parse::<Money>().is_err());
+        assert!("abc".parse::<Money>().is_err());
+        assert!("".parse::<Money>().is_err());
+        assert!("1.2.3".parse::<Money>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic_is_exact() {
+        // 0.1 + 0.2 在浮点数下不等于 0.3
+        assert_eq!(m("0.10") + m("0.20"), m("0.30"));
+        assert_eq!(m("19.99").times(3), m("59.97"));
+        assert_eq!([m("1.01"), m("2.02"), m("3.03")].into_iter().sum::<Money>(), m("6.06"));
+        assert_eq!(-m("5") - m("1.5"), m("-6.5"));
+    }
+
+    #[test]
+    fn test_ratio_rounds_half_away_from_zero() {
+        // 45.50 × 2.5 小时 = 113.75
+        assert_eq!(m("45.50").mul_ratio(25, 10), m("113.75"));
+        // 0.25 × 0.5 = 0.125 → 0.13
+        assert_eq!(m("0.25").mul_ratio(1, 2), m("0.13"));
+        assert_eq!(m("-0.25").mul_ratio(1, 2), m("-0.13"));
+        // 0.24 × 0.5 = 0.12
+        assert_eq!(m("0.24").mul_ratio(1, 2), m("0.12"));
+        // 99.99 打 15% = 14.9985 → 15.00
+        assert_eq!(m("99.99").percent(m("15")), m("15.00"));
+    }
+
+    #[test]
+    fn test_serde() {
+        assert_eq!(serde_json::to_string(&m("12.5")).unwrap(), r#""12.50""#);
+        assert_eq!(serde_json::from_str::<Money>(r#""8.8""#).unwrap(), m("8.80"));
+        assert_eq!(serde_json::from_str::<Money>("1999").unwrap(), m("19.99"));
+        assert!(serde_json::from_str::<Money>("19.99").is_err());
+    }
+}
@@ -3,13 +3,63 @@
 //! 对应数据库中的 notifications 表
 
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+
+/// 消息类型
+/// 与 notifications.notification_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum NotificationType {
+    Order,
+    System,
+    Promotion,
+}
+
+impl NotificationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationType::Order => "order",
+            NotificationType::System => "system",
+            NotificationType::Promotion => "promotion",
+        }
+    }
+}
+
+/// 通知列表筛选条件，查询参数为 type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationFilter {
+    #[serde(rename = "type")]
+    pub notification_type: Option<NotificationType>,
+}
+
+/// 未读消息数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreadCount {
+    pub unread_count: i64,
+}
+
+/// 全部已读的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkAllReadResult {
+    /// 本次标记为已读的条数
+    pub updated: u64,
+}
+
+/// 待写入的通知
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewNotification {
+    pub user_id: i32,
+    pub notification_type: NotificationType,
+    pub title: String,
+    pub content: String,
+    pub related_id: Option<String>,
+}
 
 /// 通知模型
 /// 对应 notifications 表
 /// 
 /// 存储发送给用户的消息通知
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Notification {
     /// 消息ID (主键)
     pub notification_id: i32,
@@ -17,11 +67,8 @@ pub struct Notification {
     /// 用户ID (外键)
     pub user_id: i32,
     
-    /// 消息类型，枚举值:
-    /// - "order": 订单相关
-    /// - "system": 系统消息
-    /// - "promotion": 促销消息
-    pub notification_type: String,
+    /// 消息类型
+    pub notification_type: NotificationType,
     
     /// 消息标题
     pub title: String,
@@ -1,16 +1,117 @@
 //! 订单相关模型
 //!
-//! 对应数据库中的 orders 和 order_addons 表
+//! 对应数据库中的 orders、order_addons 和 order_status_history 表
+
+use std::fmt;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 use sqlx::types::chrono::{NaiveDateTime, NaiveDate};
+use crate::models::money::Money;
+use crate::models::worker::TimeSlot;
+
+/// 订单状态
+/// 与 orders.order_status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Pending,
+    Confirmed,
+    Assigned,
+    Ongoing,
+    Completed,
+    Cancelled,
+}
+
+/// 允许的状态流转 (from, to)
+/// pending -> confirmed -> assigned -> ongoing -> completed，
+/// 服务开始前的任一状态都可以取消
+const ORDER_STATUS_TRANSITIONS: &[(OrderStatus, OrderStatus)] = &[
+    (OrderStatus::Pending, OrderStatus::Confirmed),
+    (OrderStatus::Pending, OrderStatus::Cancelled),
+    (OrderStatus::Confirmed, OrderStatus::Assigned),
+    (OrderStatus::Confirmed, OrderStatus::Cancelled),
+    (OrderStatus::Assigned, OrderStatus::Ongoing),
+    (OrderStatus::Assigned, OrderStatus::Cancelled),
+    (OrderStatus::Ongoing, OrderStatus::Completed),
+];
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "pending",
+            OrderStatus::Confirmed => "confirmed",
+            OrderStatus::Assigned => "assigned",
+            OrderStatus::Ongoing => "ongoing",
+            OrderStatus::Completed => "completed",
+            OrderStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// 是否允许从当前状态流转到 next
+    pub fn can_transition_to(self, next: OrderStatus) -> bool {
+        ORDER_STATUS_TRANSITIONS.contains(&(self, next))
+    }
+
+    /// 是否为终态
+    pub fn is_terminal(self) -> bool {
+        matches!(self, OrderStatus::Completed | OrderStatus::Cancelled)
+    }
+}
+
+/// 订单支付状态
+/// 与 orders.payment_status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum OrderPaymentStatus {
+    /// 待支付
+    Pending,
+    /// 已支付
+    Paid,
+    /// 已退款
+    Refunded,
+}
+
+impl OrderPaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderPaymentStatus::Pending => "pending",
+            OrderPaymentStatus::Paid => "paid",
+            OrderPaymentStatus::Refunded => "refunded",
+        }
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(OrderStatus::Pending),
+            "confirmed" => Ok(OrderStatus::Confirmed),
+            "assigned" => Ok(OrderStatus::Assigned),
+            "ongoing" => Ok(OrderStatus::Ongoing),
+            "completed" => Ok(OrderStatus::Completed),
+            "cancelled" => Ok(OrderStatus::Cancelled),
+            _ => Err(format!("未知的订单状态: {}", s)),
+        }
+    }
+}
 
 /// 订单模型
 /// 对应 orders 表
 /// 
 /// orders 表存储系统中的所有订单信息
 /// 订单状态流转: pending -> confirmed -> assigned -> ongoing -> completed/cancelled
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Order {
     /// 订单编号 (主键)
     /// 格式: YYYYMMDDXXXXXX (日期+6位自增序列)
@@ -34,42 +135,29 @@ pub struct Order {
     /// 服务日期
     pub service_date: NaiveDate,
     
-    /// 时间段，枚举值:
-    /// - "morning": 上午 (09:00-12:00)
-    /// - "afternoon": 下午 (12:00-18:00)
-    /// - "evening": 晚上 (18:00-22:00)
-    /// - "full_day": 全天 (09:00-22:00)
-    pub time_slot: String,
+    /// 服务时间段
+    pub time_slot: TimeSlot,
     
     /// 服务时长/数量
     pub duration: f64,
     
     /// 单价
-    pub unit_price: f64,
+    pub unit_price: Money,
     
     /// 小计金额
-    pub subtotal: f64,
+    pub subtotal: Money,
     
     /// 优惠金额
-    pub discount_amount: f64,
+    pub discount_amount: Money,
     
     /// 总金额
-    pub total_amount: f64,
-    
-    /// 支付状态，枚举值:
-    /// - "pending": 待支付
-    /// - "paid": 已支付
-    /// - "refunded": 已退款
-    pub payment_status: String,
-    
-    /// 订单状态，枚举值:
-    /// - "pending": 待确认
-    /// - "confirmed": 已确认
-    /// - "assigned": 已指派
-    /// - "ongoing": 进行中
-    /// - "completed": 已完成
-    /// - "cancelled": 已取消
-    pub order_status: String,
+    pub total_amount: Money,
+    
+    /// 支付状态
+    pub payment_status: OrderPaymentStatus,
+    
+    /// 订单状态
+    pub order_status: OrderStatus,
     
     /// 特殊要求 (可选)
     pub special_instructions: Option<String>,
@@ -112,8 +200,146 @@ pub struct OrderAddon {
     pub quantity: i32,
     
     /// 单价
-    pub unit_price: f64,
+    pub unit_price: Money,
     
     /// 创建时间
     pub created_at: NaiveDateTime,
-}
\ No newline at end of file
+}
+
+/// 订单状态变更记录模型
+/// 对应 order_status_history 表
+///
+/// 每次订单状态变化都会记录操作人和变更时间，用于审计
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrderStatusHistory {
+    /// 记录ID (主键)
+    pub history_id: i64,
+
+    /// 订单ID (外键关联 orders 表)
+    pub order_id: String,
+
+    /// 变更前状态 (订单创建时为空)
+    pub from_status: Option<OrderStatus>,
+
+    /// 变更后状态
+    pub to_status: OrderStatus,
+
+    /// 操作人ID (外键关联 users 表)
+    pub changed_by: i32,
+
+    /// 备注，如取消原因 (可选)
+    pub remark: Option<String>,
+
+    /// 变更时间
+    pub created_at: NaiveDateTime,
+}
+
+/// 下单请求中的附加服务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderAddonRequest {
+    /// 附加服务ID
+    pub addon_id: i32,
+
+    /// 数量，默认 1
+    #[serde(default = "default_addon_quantity")]
+    pub quantity: i32,
+}
+
+fn default_addon_quantity() -> i32 {
+    1
+}
+
+/// 创建订单请求
+/// 价格由服务端根据服务、附加项和优惠券计算，客户端不传金额
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrderRequest {
+    /// 服务项目ID
+    pub service_id: i32,
+
+    /// 服务地址ID
+    pub address_id: i32,
+
+    /// 服务日期
+    pub service_date: NaiveDate,
+
+    /// 时间段: morning / afternoon / evening / full_day
+    pub time_slot: String,
+
+    /// 服务时长/数量
+    pub duration: f64,
+
+    /// 附加服务 (可选)
+    #[serde(default)]
+    pub addons: Vec<OrderAddonRequest>,
+
+    /// 优惠券码 (可选)
+    pub coupon_code: Option<String>,
+
+    /// 特殊要求 (可选)
+    pub special_instructions: Option<String>,
+}
+
+/// 待写入数据库的订单
+/// 订单编号由仓储层在事务中生成
+#[derive(Debug, Clone)]
+pub struct NewOrder {
+    pub customer_id: i32,
+    pub address_id: i32,
+    pub service_id: i32,
+    pub coupon_id: Option<i32>,
+    /// 使用的用户优惠券，与 coupon_id 同时存在
+    pub user_coupon_id: Option<i32>,
+    pub service_date: NaiveDate,
+    pub time_slot: TimeSlot,
+    pub duration: f64,
+    pub unit_price: Money,
+    pub subtotal: Money,
+    pub discount_amount: Money,
+    pub total_amount: Money,
+    pub special_instructions: Option<String>,
+    pub scheduled_start_time: Option<NaiveDateTime>,
+}
+
+/// 待写入数据库的订单附加服务
+#[derive(Debug, Clone)]
+pub struct NewOrderAddon {
+    pub addon_id: i32,
+    pub quantity: i32,
+    pub unit_price: Money,
+}
+
+/// 取消订单请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelOrderRequest {
+    /// 取消原因
+    pub cancellation_reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_status_transitions() {
+        use OrderStatus::*;
+        assert!(Pending.can_transition_to(Confirmed));
+        assert!(Confirmed.can_transition_to(Assigned));
+        assert!(Assigned.can_transition_to(Ongoing));
+        assert!(Ongoing.can_transition_to(Completed));
+        assert!(Assigned.can_transition_to(Cancelled));
+
+        assert!(!Pending.can_transition_to(Ongoing));
+        assert!(!Ongoing.can_transition_to(Cancelled));
+        assert!(!Completed.can_transition_to(Cancelled));
+        assert!(!Cancelled.can_transition_to(Pending));
+        assert!(Completed.is_terminal() && Cancelled.is_terminal());
+    }
+
+    #[test]
+    fn test_order_status_round_trip() {
+        for status in ["pending", "confirmed", "assigned", "ongoing", "completed", "cancelled"] {
+            assert_eq!(status.parse::<OrderStatus>().unwrap().as_str(), status);
+        }
+        assert!("unknown".parse::<OrderStatus>().is_err());
+    }
+}
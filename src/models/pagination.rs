@@ -0,0 +1,119 @@
+//! 分页相关模型
+//!
+//! 列表接口统一使用 page/page_size 查询参数，返回 `Page<T>`；
+//! 持续增长的流水类列表使用 cursor/limit 游标分页，返回 `CursorPage<T>`
+
+use serde::{Deserialize, Serialize};
+
+/// 默认每页条数
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+
+/// 每页最大条数
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// 分页查询参数，页码从 1 开始
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+impl PageQuery {
+    /// 规范化后的页码
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    /// 规范化后的每页条数
+    pub fn page_size(&self) -> u32 {
+        self.page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// SQL 偏移量
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page() - 1) * u64::from(self.page_size())
+    }
+}
+
+/// 分页结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub page_size: u32,
+    /// 总条数
+    pub total: i64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, query: &PageQuery, total: i64) -> Self {
+        Self {
+            items,
+            page: query.page(),
+            page_size: query.page_size(),
+            total,
+        }
+    }
+}
+
+/// 游标分页查询参数
+/// cursor 为上一页返回的 next_cursor，不传时从最新一条开始
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CursorQuery {
+    pub cursor: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+impl CursorQuery {
+    /// 规范化后的每页条数
+    pub fn limit(&self) -> u32 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// 游标分页结果，next_cursor 为空表示没有更多数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<i64>,
+}
+
+impl<T> CursorPage<T> {
+    /// 由多查询一条的结果构造分页，多出的一条说明还有下一页
+    pub fn from_overfetch(mut items: Vec<T>, query: &CursorQuery, cursor_of: impl Fn(&T) -> i64) -> Self {
+        let limit = query.limit() as usize;
+        let has_more = items.len() > limit;
+        items.truncate(limit);
+        let next_cursor = if has_more { items.last().map(cursor_of) } else { None };
+        Self { items, next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_query_normalization() {
+        let query = PageQuery::default();
+        assert_eq!((query.page(), query.page_size(), query.offset()), (1, DEFAULT_PAGE_SIZE, 0));
+
+        let query = PageQuery { page: Some(3), page_size: Some(10) };
+        assert_eq!(query.offset(), 20);
+
+        let query = PageQuery { page: Some(0), page_size: Some(1000) };
+        assert_eq!((query.page(), query.page_size()), (1, MAX_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_cursor_page_from_overfetch() {
+        let query = CursorQuery { cursor: None, limit: Some(2) };
+        let page = CursorPage::from_overfetch(vec![9, 8, 7], &query, |id| *id);
+        assert_eq!(page.items, vec![9, 8]);
+        assert_eq!(page.next_cursor, Some(8));
+
+        let page = CursorPage::from_overfetch(vec![9, 8], &query, |id| *id);
+        assert_eq!(page.items, vec![9, 8]);
+        assert_eq!(page.next_cursor, None);
+    }
+}
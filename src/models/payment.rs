@@ -2,14 +2,96 @@
 //!
 //! 对应数据库中的 payments 表
 
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+
+use crate::models::money::Money;
+
+/// 支付方式
+/// 与 payments.payment_method 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum PaymentMethod {
+    Wechat,
+    Alipay,
+    Balance,
+    Card,
+}
+
+impl PaymentMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::Wechat => "wechat",
+            PaymentMethod::Alipay => "alipay",
+            PaymentMethod::Balance => "balance",
+            PaymentMethod::Card => "card",
+        }
+    }
+}
+
+/// 支付记录状态
+/// 与 payments.payment_status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    /// 待支付
+    Pending,
+    /// 渠道已受理，等待支付结果
+    Processing,
+    /// 支付成功
+    Success,
+    /// 支付失败
+    Failed,
+    /// 已退款
+    Refunded,
+}
+
+impl PaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Processing => "processing",
+            PaymentStatus::Success => "success",
+            PaymentStatus::Failed => "failed",
+            PaymentStatus::Refunded => "refunded",
+        }
+    }
+
+    /// 尚未得到最终结果
+    pub fn is_in_progress(self) -> bool {
+        matches!(self, PaymentStatus::Pending | PaymentStatus::Processing)
+    }
+}
+
+impl fmt::Display for PaymentMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PaymentMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wechat" => Ok(PaymentMethod::Wechat),
+            "alipay" => Ok(PaymentMethod::Alipay),
+            "balance" => Ok(PaymentMethod::Balance),
+            "card" => Ok(PaymentMethod::Card),
+            _ => Err(format!("无效的支付方式: {}", s)),
+        }
+    }
+}
 
 /// 支付模型
 /// 对应 payments 表
 /// 
 /// 记录订单的支付信息和状态
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Payment {
     /// 支付ID，唯一标识符
     pub payment_id: String,
@@ -20,29 +102,57 @@ pub struct Payment {
     /// 用户ID (外键)
     pub user_id: i32,
     
-    /// 支付方式，枚举值:
-    /// - "wechat": 微信支付
-    /// - "alipay": 支付宝
-    /// - "balance": 余额支付
-    /// - "card": 银行卡支付
-    pub payment_method: String,
+    /// 支付方式
+    pub payment_method: PaymentMethod,
     
     /// 支付金额
-    pub payment_amount: f64,
+    pub payment_amount: Money,
     
-    /// 支付状态，枚举值:
-    /// - "pending": 待支付
-    /// - "processing": 处理中
-    /// - "success": 支付成功
-    /// - "failed": 支付失败
-    pub payment_status: String,
+    /// 支付状态
+    pub payment_status: PaymentStatus,
     
     /// 第三方交易号 (可选)
     pub thirdparty_trade_no: Option<String>,
+
+    /// 渠道退款单号，渠道退款成功后记录 (可选)
+    pub refund_no: Option<String>,
     
     /// 支付时间 (可选)
     pub payment_time: Option<chrono::NaiveDateTime>,
     
     /// 支付记录创建时间
     pub created_at: chrono::NaiveDateTime,
-}
\ No newline at end of file
+}
+
+/// 新建支付记录，由支付业务层构造
+#[derive(Debug, Clone)]
+pub struct NewPayment {
+    pub payment_id: String,
+    pub order_id: String,
+    pub user_id: i32,
+    pub payment_method: PaymentMethod,
+    pub payment_amount: Money,
+}
+
+/// 订单支付请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayOrderRequest {
+    /// 支付方式
+    pub payment_method: PaymentMethod,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_method_round_trip() {
+        for method in [PaymentMethod::Wechat, PaymentMethod::Alipay, PaymentMethod::Balance, PaymentMethod::Card] {
+            assert_eq!(method.as_str().parse::<PaymentMethod>(), Ok(method));
+        }
+        assert!("cash".parse::<PaymentMethod>().is_err());
+
+        let payload: PayOrderRequest = serde_json::from_str(r#"{"payment_method":"wechat"}"#).unwrap();
+        assert_eq!(payload.payment_method, PaymentMethod::Wechat);
+    }
+}
@@ -0,0 +1,53 @@
+//! 刷新令牌模型
+//!
+//! 对应数据库中的 refresh_tokens 表
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::NaiveDateTime;
+
+/// 刷新令牌记录
+/// 对应 refresh_tokens 表
+///
+/// 数据库中只保存令牌的 SHA-256 哈希，原始令牌只在签发时返回给客户端。
+/// 每次刷新都会撤销旧令牌并签发新令牌，新旧令牌共享同一个 family_id，
+/// 并通过 parent_id 串成轮换链
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RefreshToken {
+    /// 令牌ID (主键)
+    pub token_id: i64,
+
+    /// 令牌的 SHA-256 哈希 (十六进制)
+    pub token_hash: String,
+
+    /// 用户ID (外键)
+    pub user_id: i32,
+
+    /// 令牌族ID，同一次登录轮换出的所有令牌共享
+    pub family_id: String,
+
+    /// 轮换前的令牌ID (首次登录签发时为空)
+    pub parent_id: Option<i64>,
+
+    /// 设备信息 (可选)
+    pub device_info: Option<String>,
+
+    /// 过期时间
+    pub expires_at: NaiveDateTime,
+
+    /// 是否已撤销 (已轮换或已登出)
+    pub revoked: bool,
+
+    /// 创建时间
+    pub created_at: NaiveDateTime,
+}
+
+/// 新建刷新令牌所需的字段
+#[derive(Debug, Clone)]
+pub struct NewRefreshToken {
+    pub token_hash: String,
+    pub user_id: i32,
+    pub family_id: String,
+    pub parent_id: Option<i64>,
+    pub device_info: Option<String>,
+    pub expires_at: NaiveDateTime,
+}
@@ -1,15 +1,18 @@
 //! 评价与投诉相关模型
 //!
-//! 对应数据库中的 reviews 和 complaints 表
+//! 对应数据库中的 reviews、complaints 和 complaint_notes 表
+
+use std::fmt;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use crate::models::notification::{NewNotification, NotificationType};
 
 /// 评价模型
 /// 对应 reviews 表
 /// 
 /// 存储用户对服务的评价信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Review {
     /// 评价ID (主键)
     pub review_id: i32,
@@ -42,11 +45,50 @@ pub struct Review {
     pub created_at: chrono::NaiveDateTime,
 }
 
+/// 提交评价请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReviewRequest {
+    /// 总评分 (1-5分)
+    pub rating: i8,
+
+    /// 服务评分 (可选，1-5分)
+    pub service_rating: Option<i8>,
+
+    /// 守时评分 (可选，1-5分)
+    pub punctuality_rating: Option<i8>,
+
+    /// 评价内容 (可选)
+    pub review_text: Option<String>,
+
+    /// 是否匿名评价，默认否
+    #[serde(default)]
+    pub is_anonymous: bool,
+}
+
+/// 服务人员主页展示的评价
+/// 匿名评价不返回评价人ID，评价人名称显示为「匿名用户」
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerReview {
+    pub review_id: i32,
+
+    /// 评价人ID，匿名评价时为空
+    pub reviewer_id: Option<i32>,
+
+    /// 评价人名称
+    pub reviewer_name: String,
+
+    pub rating: i8,
+    pub service_rating: Option<i8>,
+    pub punctuality_rating: Option<i8>,
+    pub review_text: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
 /// 投诉模型
 /// 对应 complaints 表
 /// 
 /// 存储用户提交的投诉信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Complaint {
     /// 投诉ID (主键)
     pub complaint_id: i32,
@@ -60,26 +102,345 @@ pub struct Complaint {
     /// 被投诉人ID (外键)
     pub target_id: i32,
     
-    /// 投诉类型，枚举值:
-    /// - "service": 服务质量
-    /// - "punctuality": 守时问题
-    /// - "attitude": 服务态度
-    /// - "other": 其他问题
-    pub complaint_type: String,
+    /// 投诉类型
+    pub complaint_type: ComplaintType,
     
     /// 投诉内容
     pub complaint_text: String,
     
-    /// 处理状态，枚举值:
-    /// - "pending": 待处理
-    /// - "investigating": 调查中
-    /// - "resolved": 已解决
-    /// - "rejected": 已驳回
-    pub status: String,
+    /// 处理状态
+    pub status: ComplaintStatus,
     
+    /// 证据说明 (可选)
+    pub evidence_text: Option<String>,
+
+    /// 处理人ID (可选，分配给管理员后填写)
+    pub handler_id: Option<i32>,
+
+    /// 处理结果说明 (可选)
+    pub resolution: Option<String>,
+
+    /// 补偿方式
+    pub compensation_type: CompensationType,
+
+    /// 补偿优惠券ID (可选)
+    pub compensation_coupon_id: Option<i32>,
+
     /// 处理时间 (可选)
     pub resolved_at: Option<chrono::NaiveDateTime>,
     
     /// 投诉创建时间
     pub created_at: chrono::NaiveDateTime,
+}
+
+impl Complaint {
+    /// 投诉状态变更后发给投诉人和被投诉人的通知
+    pub fn status_notifications(&self) -> Vec<NewNotification> {
+        let status = self.status;
+        let order_id = &self.order_id;
+        let resolution = self.resolution.as_deref().unwrap_or_default();
+        let (complainant, target) = match status {
+            ComplaintStatus::Pending => (
+                ("投诉已提交", format!("您对订单 {} 的投诉已提交，平台会尽快处理", order_id)),
+                ("收到新的投诉", format!("订单 {} 收到一条投诉，平台将进行核实", order_id)),
+            ),
+            ComplaintStatus::Investigating | ComplaintStatus::Resolving => (
+                ("投诉处理中", format!("您对订单 {} 的投诉已受理，正在调查中", order_id)),
+                ("投诉调查中", format!("订单 {} 的投诉正在调查中，请配合平台核实", order_id)),
+            ),
+            ComplaintStatus::Resolved => {
+                let compensation = match self.compensation_type {
+                    CompensationType::Refund => "，订单款项将退回原支付账户",
+                    CompensationType::Coupon => "，补偿优惠券已发放到您的账户",
+                    CompensationType::None => "",
+                };
+                (
+                    ("投诉已解决", format!("您对订单 {} 的投诉已处理：{}{}", order_id, resolution, compensation)),
+                    ("投诉已处理", format!("订单 {} 的投诉已处理：{}", order_id, resolution)),
+                )
+            }
+            ComplaintStatus::Rejected => (
+                ("投诉已驳回", format!("您对订单 {} 的投诉经核实不成立：{}", order_id, resolution)),
+                ("投诉已驳回", format!("订单 {} 的投诉经核实不成立", order_id)),
+            ),
+        };
+
+        [(self.complainant_id, complainant), (self.target_id, target)]
+            .into_iter()
+            .map(|(user_id, (title, content))| NewNotification {
+                user_id,
+                notification_type: NotificationType::System,
+                title: title.to_string(),
+                content,
+                related_id: Some(self.complaint_id.to_string()),
+            })
+            .collect()
+    }
+}
+
+/// 投诉类型
+/// 与 complaints.complaint_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ComplaintType {
+    Service,
+    Punctuality,
+    Attitude,
+    Other,
+}
+
+impl ComplaintType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplaintType::Service => "service",
+            ComplaintType::Punctuality => "punctuality",
+            ComplaintType::Attitude => "attitude",
+            ComplaintType::Other => "other",
+        }
+    }
+}
+
+/// 投诉处理状态
+/// 与 complaints.status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ComplaintStatus {
+    Pending,
+    Investigating,
+    /// 结案中，退款补偿进行期间的占位状态，不能由管理员直接设置
+    Resolving,
+    Resolved,
+    Rejected,
+}
+
+/// 允许的状态流转 (from, to)
+/// pending -> investigating -> resolved，未解决前的任一状态都可以驳回
+const COMPLAINT_STATUS_TRANSITIONS: &[(ComplaintStatus, ComplaintStatus)] = &[
+    (ComplaintStatus::Pending, ComplaintStatus::Investigating),
+    (ComplaintStatus::Pending, ComplaintStatus::Rejected),
+    (ComplaintStatus::Investigating, ComplaintStatus::Resolved),
+    (ComplaintStatus::Investigating, ComplaintStatus::Rejected),
+];
+
+impl ComplaintStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplaintStatus::Pending => "pending",
+            ComplaintStatus::Investigating => "investigating",
+            ComplaintStatus::Resolving => "resolving",
+            ComplaintStatus::Resolved => "resolved",
+            ComplaintStatus::Rejected => "rejected",
+        }
+    }
+
+    /// 是否允许从当前状态流转到 next
+    pub fn can_transition_to(self, next: ComplaintStatus) -> bool {
+        COMPLAINT_STATUS_TRANSITIONS.contains(&(self, next))
+    }
+
+    /// 是否仍可分配处理人和结案，结案中的投诉不再接受操作
+    pub fn is_open(self) -> bool {
+        matches!(self, ComplaintStatus::Pending | ComplaintStatus::Investigating)
+    }
+}
+
+impl fmt::Display for ComplaintStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ComplaintStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ComplaintStatus::Pending),
+            "investigating" => Ok(ComplaintStatus::Investigating),
+            "resolving" => Ok(ComplaintStatus::Resolving),
+            "resolved" => Ok(ComplaintStatus::Resolved),
+            "rejected" => Ok(ComplaintStatus::Rejected),
+            _ => Err(format!("未知的投诉状态: {}", s)),
+        }
+    }
+}
+
+/// 补偿方式
+/// 与 complaints.compensation_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum CompensationType {
+    /// 无补偿
+    None,
+    /// 退款
+    Refund,
+    /// 发放优惠券
+    Coupon,
+}
+
+impl CompensationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompensationType::None => "none",
+            CompensationType::Refund => "refund",
+            CompensationType::Coupon => "coupon",
+        }
+    }
+}
+
+/// 投诉补偿方式
+/// JSON 格式: {"type": "refund"} 或 {"type": "coupon", "coupon_id": 1}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Compensation {
+    /// 退还订单款项
+    Refund,
+    /// 向投诉人发放一张优惠券
+    Coupon { coupon_id: i32 },
+}
+
+impl Compensation {
+    /// 对应 complaints.compensation_type 的取值
+    pub fn compensation_type(&self) -> CompensationType {
+        match self {
+            Compensation::Refund => CompensationType::Refund,
+            Compensation::Coupon { .. } => CompensationType::Coupon,
+        }
+    }
+
+    pub fn coupon_id(&self) -> Option<i32> {
+        match self {
+            Compensation::Refund => None,
+            Compensation::Coupon { coupon_id } => Some(*coupon_id),
+        }
+    }
+}
+
+/// 待写入的投诉
+#[derive(Debug, Clone)]
+pub struct NewComplaint {
+    pub order_id: String,
+    pub complainant_id: i32,
+    pub target_id: i32,
+    pub complaint_type: ComplaintType,
+    pub complaint_text: String,
+    pub evidence_text: Option<String>,
+}
+
+/// 提交投诉请求，被投诉人为订单的服务人员
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateComplaintRequest {
+    pub order_id: String,
+    pub complaint_type: ComplaintType,
+    pub complaint_text: String,
+
+    /// 证据说明 (可选)，如现场情况描述、沟通记录等
+    pub evidence_text: Option<String>,
+}
+
+/// 投诉列表筛选条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComplaintFilter {
+    pub status: Option<ComplaintStatus>,
+}
+
+/// 分配投诉处理人请求，不指定处理人时分配给当前管理员
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssignComplaintRequest {
+    pub handler_id: Option<i32>,
+}
+
+/// 添加内部备注请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplaintNoteRequest {
+    pub note: String,
+}
+
+/// 处理投诉请求
+/// status 只能为 resolved 或 rejected，仅 resolved 可附带补偿
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveComplaintRequest {
+    pub status: ComplaintStatus,
+    pub resolution: String,
+    pub compensation: Option<Compensation>,
+}
+
+/// 投诉内部备注，仅管理员可见
+/// 对应 complaint_notes 表
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ComplaintNote {
+    pub note_id: i64,
+    pub complaint_id: i32,
+    pub author_id: i32,
+    pub note: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complaint(status: ComplaintStatus) -> Complaint {
+        Complaint {
+            complaint_id: 12,
+            order_id: "20240101000001".to_string(),
+            complainant_id: 7,
+            target_id: 3,
+            complaint_type: ComplaintType::Punctuality,
+            complaint_text: "迟到一小时".to_string(),
+            status,
+            evidence_text: None,
+            handler_id: None,
+            resolution: Some("已核实".to_string()),
+            compensation_type: CompensationType::Coupon,
+            compensation_coupon_id: Some(2),
+            resolved_at: None,
+            created_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap().and_hms_opt(10, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_complaint_status_transitions() {
+        use ComplaintStatus::*;
+        assert!(Pending.can_transition_to(Investigating));
+        assert!(Pending.can_transition_to(Rejected));
+        assert!(Investigating.can_transition_to(Resolved));
+        assert!(Investigating.can_transition_to(Rejected));
+        assert!(!Pending.can_transition_to(Resolved));
+        assert!(!Resolved.can_transition_to(Rejected));
+        assert!(!Rejected.can_transition_to(Investigating));
+        assert!(!Investigating.can_transition_to(Resolving));
+        assert!(!Resolving.can_transition_to(Resolved));
+        assert!(Pending.is_open() && Investigating.is_open());
+        assert!(!Resolving.is_open() && !Resolved.is_open() && !Rejected.is_open());
+        for status in [Pending, Investigating, Resolving, Resolved, Rejected] {
+            assert_eq!(status.as_str().parse::<ComplaintStatus>(), Ok(status));
+        }
+    }
+
+    #[test]
+    fn test_compensation_json() {
+        let refund: Compensation = serde_json::from_str(r#"{"type":"refund"}"#).unwrap();
+        assert_eq!(refund, Compensation::Refund);
+        let coupon: Compensation = serde_json::from_str(r#"{"type":"coupon","coupon_id":5}"#).unwrap();
+        assert_eq!(coupon.compensation_type(), CompensationType::Coupon);
+        assert_eq!(coupon.coupon_id(), Some(5));
+        assert!(serde_json::from_str::<Compensation>(r#"{"type":"coupon"}"#).is_err());
+    }
+
+    #[test]
+    fn test_status_notifications_reach_both_parties() {
+        let notifications = complaint(ComplaintStatus::Resolved).status_notifications();
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].user_id, 7);
+        assert_eq!(notifications[1].user_id, 3);
+        assert!(notifications.iter().all(|n| n.related_id.as_deref() == Some("12")));
+        assert!(notifications[0].content.contains("优惠券"));
+        assert!(!notifications[1].content.contains("优惠券"));
+
+        assert_eq!(complaint(ComplaintStatus::Pending).status_notifications().len(), 2);
+    }
 }
\ No newline at end of file
@@ -2,12 +2,15 @@
 //!
 //! 对应数据库中的 service_categories 和 services 表
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use sqlx::types::chrono::NaiveDateTime;
+use crate::models::money::Money;
 
 /// 服务分类模型
 /// 对应 service_categories 表
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ServiceCategory {
     /// 分类唯一标识符 (主键)
     pub category_id: i32,
@@ -33,7 +36,7 @@ pub struct ServiceCategory {
 
 /// 服务项目模型
 /// 对应 services 表
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Service {
     /// 服务项目唯一标识符 (主键)
     pub service_id: i32,
@@ -48,14 +51,10 @@ pub struct Service {
     pub description: Option<String>,
     
     /// 基础价格
-    pub base_price: f64,
+    pub base_price: Money,
     
-    /// 计价单位，枚举值:
-    /// - "hour": 按小时计费
-    /// - "square_meter": 按平方米计费
-    /// - "item": 按项计费
-    /// - "fixed": 固定价格
-    pub unit: String,
+    /// 计价单位
+    pub unit: ServiceUnit,
     
     /// 最小时长/数量
     pub min_duration: i32,
@@ -74,7 +73,7 @@ pub struct Service {
 /// 对应 service_addons 表
 /// 
 /// 定义服务的可选项或增值服务，可附加到主服务上
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ServiceAddon {
     /// 附加项唯一标识符 (主键)
     pub addon_id: i32,
@@ -86,11 +85,169 @@ pub struct ServiceAddon {
     pub addon_name: String,
     
     /// 附加项价格
-    pub addon_price: f64,
+    pub addon_price: Money,
     
     /// 附加项是否启用
     pub is_active: bool,
     
     /// 附加项创建时间
     pub created_at: chrono::NaiveDateTime,
-}
\ No newline at end of file
+}
+
+/// 分类树节点，子分类按 sort_order 排列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: ServiceCategory,
+    pub children: Vec<CategoryNode>,
+}
+
+/// 计价单位
+/// 与 services.unit 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ServiceUnit {
+    Hour,
+    SquareMeter,
+    Item,
+    Fixed,
+}
+
+impl ServiceUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceUnit::Hour => "hour",
+            ServiceUnit::SquareMeter => "square_meter",
+            ServiceUnit::Item => "item",
+            ServiceUnit::Fixed => "fixed",
+        }
+    }
+}
+
+/// 服务列表排序方式，默认按服务ID升序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceSort {
+    #[default]
+    Default,
+    PriceAsc,
+    PriceDesc,
+    Newest,
+}
+
+/// 服务列表查询参数，分页参数见 `PageQuery`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceQuery {
+    /// 分类ID，包含其全部子分类
+    pub category_id: Option<i32>,
+
+    /// 按名称或描述模糊搜索
+    pub keyword: Option<String>,
+
+    pub min_price: Option<Money>,
+    pub max_price: Option<Money>,
+    pub unit: Option<ServiceUnit>,
+
+    /// 是否只返回启用的服务，默认是；仅管理员可以查看停用的服务
+    pub active_only: Option<bool>,
+
+    #[serde(default)]
+    pub sort: ServiceSort,
+}
+
+/// 数据访问层使用的服务筛选条件
+#[derive(Debug, Clone, Default)]
+pub struct ServiceFilter {
+    /// 为空表示不按分类筛选
+    pub category_ids: Option<Vec<i32>>,
+    /// 已转义的 LIKE 匹配模式
+    pub keyword_pattern: Option<String>,
+    pub min_price: Option<Money>,
+    pub max_price: Option<Money>,
+    pub unit: Option<ServiceUnit>,
+    pub active_only: bool,
+    pub sort: ServiceSort,
+}
+
+/// 服务详情，包含启用的附加项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDetail {
+    #[serde(flatten)]
+    pub service: Service,
+    pub addons: Vec<ServiceAddon>,
+}
+
+/// 分类列表查询参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryQuery {
+    /// 是否只返回启用的分类，默认是；仅管理员可以查看停用的分类
+    pub active_only: Option<bool>,
+}
+
+/// 管理员新增或修改服务分类
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRequest {
+    pub category_name: String,
+    pub parent_id: Option<i32>,
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+    /// 不传时新增为启用，修改时保持不变
+    pub is_active: Option<bool>,
+}
+
+/// 管理员新增或修改服务项目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceRequest {
+    pub category_id: i32,
+    pub service_name: String,
+    pub description: Option<String>,
+    pub base_price: Money,
+    pub unit: ServiceUnit,
+    pub min_duration: i32,
+    pub max_duration: i32,
+    /// 不传时新增为启用，修改时保持不变
+    pub is_active: Option<bool>,
+}
+
+/// 管理员新增或修改服务附加项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddonRequest {
+    pub addon_name: String,
+    pub addon_price: Money,
+    /// 不传时新增为启用，修改时保持不变
+    pub is_active: Option<bool>,
+}
+
+/// 将分类 category_id 的父分类改为 parent_id 是否会形成环
+/// parents 为全部分类的 分类ID -> 父分类ID 映射
+pub fn creates_cycle(parents: &HashMap<i32, Option<i32>>, category_id: i32, parent_id: Option<i32>) -> bool {
+    let mut current = parent_id;
+    let mut steps = 0;
+    while let Some(id) = current {
+        if id == category_id || steps > parents.len() {
+            return true;
+        }
+        current = parents.get(&id).copied().flatten();
+        steps += 1;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_creates_cycle() {
+        // 1 -> 2 -> 3，4 为独立的根分类
+        let parents = HashMap::from([(1, None), (2, Some(1)), (3, Some(2)), (4, None)]);
+
+        assert!(creates_cycle(&parents, 1, Some(3)));
+        assert!(creates_cycle(&parents, 2, Some(2)));
+        assert!(!creates_cycle(&parents, 3, Some(4)));
+        assert!(!creates_cycle(&parents, 4, Some(3)));
+        assert!(!creates_cycle(&parents, 2, None));
+    }
+}
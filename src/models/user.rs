@@ -2,15 +2,72 @@
 //!
 //! 对应数据库中的 users 和 user_addresses 表
 
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 use sqlx::types::chrono::NaiveDateTime;
+use crate::models::money::Money;
+
+/// 用户类型
+/// 与 users.user_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum UserType {
+    /// 普通客户
+    Customer,
+    /// 服务人员
+    Worker,
+    /// 系统管理员
+    Admin,
+}
+
+impl UserType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserType::Customer => "customer",
+            UserType::Worker => "worker",
+            UserType::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for UserType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 账户状态
+/// 与 users.status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum UserStatus {
+    /// 活跃账户
+    Active,
+    /// 非活跃账户
+    Inactive,
+    /// 已封禁账户
+    Banned,
+}
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserStatus::Active => "active",
+            UserStatus::Inactive => "inactive",
+            UserStatus::Banned => "banned",
+        }
+    }
+}
 
 /// 用户模型
 /// 对应 users 表
 /// 
 /// users 表存储系统中的所有用户信息，包括客户、服务人员和管理员
 /// 用户可以通过不同角色参与系统活动
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
     /// 用户唯一标识符 (主键)
     pub user_id: i32,
@@ -18,7 +75,8 @@ pub struct User {
     /// 用户名，系统内唯一
     pub username: String,
     
-    /// 经过 Argon2 哈希处理的密码
+    /// 经过 Argon2 哈希处理的密码 (不对外输出)
+    #[serde(skip_serializing, default)]
     pub password_hash: String,
     
     /// 用户邮箱，系统内唯一
@@ -27,12 +85,9 @@ pub struct User {
     /// 用户手机号，系统内唯一
     pub phone: String,
     
-    /// 用户类型，枚举值:
-    /// - "customer": 普通客户
-    /// - "worker": 服务人员
-    /// - "admin": 系统管理员
+    /// 用户类型
     #[serde(rename = "type")]
-    pub user_type: String,
+    pub user_type: UserType,
     
     /// 用户头像图片URL (可选)
     pub avatar_url: Option<String>,
@@ -44,13 +99,10 @@ pub struct User {
     pub is_verified: bool,
     
     /// 用户账户余额
-    pub balance: f64,
+    pub balance: Money,
     
-    /// 账户状态，枚举值:
-    /// - "active": 活跃账户
-    /// - "inactive": 非活跃账户
-    /// - "banned": 已封禁账户
-    pub status: String,
+    /// 账户状态
+    pub status: UserStatus,
     
     /// 账户创建时间
     pub created_at: NaiveDateTime,
@@ -63,7 +115,7 @@ pub struct User {
 /// 对应 user_addresses 表
 /// 
 /// 存储用户的常用服务地址信息，用户可以设置默认地址
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct UserAddress {
     /// 地址唯一标识符 (主键)
     pub address_id: i32,
@@ -94,4 +146,16 @@ pub struct UserAddress {
     
     /// 地址创建时间
     pub created_at: NaiveDateTime,
-}
\ No newline at end of file
+}
+/// 新增或修改地址请求
+/// is_default 为空时：新增的地址不设为默认 (用户的第一个地址除外)，修改时保持原状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressRequest {
+    pub contact_name: String,
+    pub contact_phone: String,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street_address: String,
+    pub is_default: Option<bool>,
+}
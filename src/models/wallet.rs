@@ -0,0 +1,290 @@
+//! 钱包相关模型
+//!
+//! 对应数据库中的 balance_transactions 和 wallet_topups 表。
+//! users.balance 的每一次变动都对应一条流水，流水记录变动前后的余额，
+//! 任意时刻用户余额都等于其全部流水金额之和。
+//! 每条流水同时在 platform_ledger 的平台科目上记一笔金额相反的分录，
+//! 用户余额总和与平台科目余额总和相加恒为 0
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::money::Money;
+use crate::models::payment::PaymentMethod;
+
+/// 余额流水类型
+/// 与 balance_transactions.transaction_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum TransactionType {
+    /// 充值
+    Topup,
+    /// 余额支付订单
+    Payment,
+    /// 订单退款到余额
+    Refund,
+    /// 管理员调整
+    Adjustment,
+}
+
+impl TransactionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionType::Topup => "topup",
+            TransactionType::Payment => "payment",
+            TransactionType::Refund => "refund",
+            TransactionType::Adjustment => "adjustment",
+        }
+    }
+}
+
+impl TransactionType {
+    /// 该类流水的对应平台科目
+    pub fn platform_account(&self) -> PlatformAccount {
+        match self {
+            TransactionType::Topup => PlatformAccount::ChannelClearing,
+            TransactionType::Payment | TransactionType::Refund => PlatformAccount::OrderSettlement,
+            TransactionType::Adjustment => PlatformAccount::ManualAdjustment,
+        }
+    }
+}
+
+impl fmt::Display for TransactionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 平台科目
+/// 与 platform_ledger.account 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum PlatformAccount {
+    /// 渠道待清算资金，充值入账时减少
+    ChannelClearing,
+    /// 订单结算，余额支付时增加、退款到余额时减少
+    OrderSettlement,
+    /// 人工调整
+    ManualAdjustment,
+}
+
+impl PlatformAccount {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlatformAccount::ChannelClearing => "channel_clearing",
+            PlatformAccount::OrderSettlement => "order_settlement",
+            PlatformAccount::ManualAdjustment => "manual_adjustment",
+        }
+    }
+}
+
+/// 流水关联对象类型
+/// 与 balance_transactions.reference_type 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ReferenceType {
+    Order,
+    Payment,
+    Topup,
+    Manual,
+}
+
+impl ReferenceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceType::Order => "order",
+            ReferenceType::Payment => "payment",
+            ReferenceType::Topup => "topup",
+            ReferenceType::Manual => "manual",
+        }
+    }
+}
+
+/// 流水关联的业务对象
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionReference {
+    /// 订单ID
+    Order(String),
+    /// 支付ID
+    Payment(String),
+    /// 充值的第三方交易号
+    Topup(String),
+    /// 人工操作，无关联对象
+    Manual,
+}
+
+impl TransactionReference {
+    pub fn reference_type(&self) -> ReferenceType {
+        match self {
+            TransactionReference::Order(_) => ReferenceType::Order,
+            TransactionReference::Payment(_) => ReferenceType::Payment,
+            TransactionReference::Topup(_) => ReferenceType::Topup,
+            TransactionReference::Manual => ReferenceType::Manual,
+        }
+    }
+
+    pub fn reference_id(&self) -> Option<&str> {
+        match self {
+            TransactionReference::Order(id)
+            | TransactionReference::Payment(id)
+            | TransactionReference::Topup(id) => Some(id),
+            TransactionReference::Manual => None,
+        }
+    }
+}
+
+/// 余额流水
+/// 对应 balance_transactions 表
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BalanceTransaction {
+    /// 流水ID (主键)
+    pub transaction_id: i64,
+
+    /// 用户ID (外键)
+    pub user_id: i32,
+
+    /// 交易类型
+    pub transaction_type: TransactionType,
+
+    /// 变动金额，入账为正，出账为负
+    pub amount: Money,
+
+    /// 变动前余额
+    pub balance_before: Money,
+
+    /// 变动后余额
+    pub balance_after: Money,
+
+    /// 关联对象类型
+    pub reference_type: ReferenceType,
+
+    /// 关联对象ID (可选)
+    pub reference_id: Option<String>,
+
+    /// 操作人ID，管理员调整时记录 (可选)
+    pub operator_id: Option<i32>,
+
+    /// 备注 (可选)
+    pub remark: Option<String>,
+
+    /// 流水创建时间
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// 记账请求，由业务层构造
+#[derive(Debug, Clone)]
+pub struct LedgerEntry {
+    pub user_id: i32,
+    pub transaction_type: TransactionType,
+    /// 变动金额，入账为正，出账为负
+    pub amount: Money,
+    pub reference: TransactionReference,
+    pub operator_id: Option<i32>,
+    pub remark: Option<String>,
+}
+
+/// 钱包概览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wallet {
+    /// 当前余额
+    pub balance: Money,
+
+    /// 余额流水 (分页)
+    pub transactions: crate::models::pagination::Page<BalanceTransaction>,
+}
+
+/// 充值状态
+/// 与 wallet_topups.topup_status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum TopupStatus {
+    /// 已创建，尚未向渠道下单
+    Pending,
+    /// 渠道已受理，等待支付结果
+    Processing,
+    /// 已入账
+    Success,
+    Failed,
+}
+
+/// 充值记录
+/// 对应 wallet_topups 表
+///
+/// 渠道返回处理中时充值记录保持 processing，查询时向渠道同步结果，成功后入账
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WalletTopup {
+    /// 充值ID，作为商户订单号传给渠道
+    pub topup_id: String,
+
+    /// 用户ID (外键)
+    pub user_id: i32,
+
+    /// 充值渠道
+    pub payment_method: PaymentMethod,
+
+    /// 充值金额
+    pub amount: Money,
+
+    /// 充值状态
+    pub topup_status: TopupStatus,
+
+    /// 第三方交易号 (可选)
+    pub thirdparty_trade_no: Option<String>,
+
+    /// 入账后对应的余额流水ID (可选)
+    pub transaction_id: Option<i64>,
+
+    /// 入账时间 (可选)
+    pub settled_at: Option<chrono::NaiveDateTime>,
+
+    /// 充值记录创建时间
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// 新建充值记录，由钱包业务层构造
+#[derive(Debug, Clone)]
+pub struct NewTopup {
+    pub topup_id: String,
+    pub user_id: i32,
+    pub payment_method: PaymentMethod,
+    pub amount: Money,
+}
+
+/// 充值请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopUpRequest {
+    /// 充值金额
+    pub amount: Money,
+
+    /// 充值渠道，不能为 balance
+    pub payment_method: PaymentMethod,
+}
+
+/// 管理员调整余额请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjustBalanceRequest {
+    /// 调整金额，正数增加、负数扣减
+    pub amount: Money,
+
+    /// 调整原因
+    pub remark: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_account() {
+        assert_eq!(TransactionType::Topup.platform_account(), PlatformAccount::ChannelClearing);
+        assert_eq!(TransactionType::Payment.platform_account(), PlatformAccount::OrderSettlement);
+        assert_eq!(TransactionType::Refund.platform_account(), PlatformAccount::OrderSettlement);
+        assert_eq!(TransactionType::Adjustment.platform_account(), PlatformAccount::ManualAdjustment);
+        assert_eq!(PlatformAccount::ChannelClearing.as_str(), "channel_clearing");
+    }
+}
@@ -2,13 +2,102 @@
 //!
 //! 对应数据库中的 worker_profiles 和 worker_schedules 表
 
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+use crate::models::money::Money;
+use crate::models::notification::{NewNotification, NotificationType};
+
+/// 服务时间段
+/// 与 orders.time_slot、worker_schedules.time_slot 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum TimeSlot {
+    /// 上午 (09:00-12:00)
+    Morning,
+    /// 下午 (12:00-18:00)
+    Afternoon,
+    /// 晚上 (18:00-22:00)
+    Evening,
+    /// 全天 (09:00-22:00)
+    FullDay,
+}
+
+impl TimeSlot {
+    pub const ALL: [TimeSlot; 4] = [TimeSlot::Morning, TimeSlot::Afternoon, TimeSlot::Evening, TimeSlot::FullDay];
+
+    /// 全天拆分后的各个分段
+    pub const PARTS: [TimeSlot; 3] = [TimeSlot::Morning, TimeSlot::Afternoon, TimeSlot::Evening];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeSlot::Morning => "morning",
+            TimeSlot::Afternoon => "afternoon",
+            TimeSlot::Evening => "evening",
+            TimeSlot::FullDay => "full_day",
+        }
+    }
+
+    /// 面向用户展示的时间段名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeSlot::Morning => "上午",
+            TimeSlot::Afternoon => "下午",
+            TimeSlot::Evening => "晚上",
+            TimeSlot::FullDay => "全天",
+        }
+    }
+
+    /// 时间段的开始时间
+    pub fn start_time(&self) -> chrono::NaiveTime {
+        let hour = match self {
+            TimeSlot::Morning | TimeSlot::FullDay => 9,
+            TimeSlot::Afternoon => 12,
+            TimeSlot::Evening => 18,
+        };
+        chrono::NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    /// 两个时间段是否重叠，全天与任意时间段都重叠
+    pub fn overlaps(self, other: TimeSlot) -> bool {
+        self == other || self == TimeSlot::FullDay || other == TimeSlot::FullDay
+    }
+
+    /// 该日程时间段能否承接 order_slot 时间段的订单
+    /// 全天日程可以承接任意时间段的订单，承接分段订单时先拆分为各个分段，只占用订单所在的分段
+    pub fn can_serve(self, order_slot: TimeSlot) -> bool {
+        self == order_slot || self == TimeSlot::FullDay
+    }
+}
+
+impl fmt::Display for TimeSlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TimeSlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "morning" => Ok(TimeSlot::Morning),
+            "afternoon" => Ok(TimeSlot::Afternoon),
+            "evening" => Ok(TimeSlot::Evening),
+            "full_day" => Ok(TimeSlot::FullDay),
+            _ => Err(format!("无效的时间段: {}", s)),
+        }
+    }
+}
+
 /// 服务人员详情模型
 /// 对应 worker_profiles 表
 /// 
 /// 存储服务人员的详细资料和专业信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct WorkerProfile {
     /// 服务人员ID，与用户ID一致 (主键)
     pub worker_id: i32,
@@ -17,21 +106,25 @@ pub struct WorkerProfile {
     pub service_category_id: i32,
     
     /// 时薪
-    pub hourly_rate: f64,
+    pub hourly_rate: Money,
     
     /// 个人简介 (可选)
     pub bio: Option<String>,
     
     /// 技能标签列表 (JSON格式)
+    #[sqlx(json(nullable))]
     pub skills: Option<Vec<String>>,
     
     /// 服务区域列表 (JSON格式)
+    #[sqlx(json(nullable))]
     pub service_area: Option<Vec<String>>,
     
     /// 总订单数
+    #[sqlx(try_from = "i64")]
     pub total_orders: i32,
     
     /// 已完成订单数
+    #[sqlx(try_from = "i64")]
     pub completed_orders: i32,
     
     /// 平均评分
@@ -50,11 +143,35 @@ pub struct WorkerProfile {
     pub updated_at: chrono::NaiveDateTime,
 }
 
+/// 日程状态
+/// 与 worker_schedules.status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ScheduleStatus {
+    /// 可用
+    Available,
+    /// 已预订
+    Booked,
+    /// 不可用
+    Unavailable,
+}
+
+impl ScheduleStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleStatus::Available => "available",
+            ScheduleStatus::Booked => "booked",
+            ScheduleStatus::Unavailable => "unavailable",
+        }
+    }
+}
+
 /// 服务人员日程模型
 /// 对应 worker_schedules 表
 /// 
 /// 管理服务人员的工作时间安排
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct WorkerSchedule {
     /// 日程唯一标识符 (主键)
     pub schedule_id: i32,
@@ -65,22 +182,336 @@ pub struct WorkerSchedule {
     /// 排班日期
     pub schedule_date: chrono::NaiveDate,
     
-    /// 时间段，枚举值:
-    /// - "morning": 上午
-    /// - "afternoon": 下午
-    /// - "evening": 晚上
-    /// - "full_day": 全天
-    pub time_slot: String,
+    /// 时间段
+    pub time_slot: TimeSlot,
     
-    /// 状态，枚举值:
-    /// - "available": 可用
-    /// - "booked": 已预订
-    /// - "unavailable": 不可用
-    pub status: String,
+    /// 日程状态
+    pub status: ScheduleStatus,
     
     /// 关联订单ID (可选)
     pub order_id: Option<String>,
     
     /// 日程创建时间
     pub created_at: chrono::NaiveDateTime,
-}
\ No newline at end of file
+}
+
+/// 派单候选人
+///
+/// 满足订单服务分类、服务区域、时间段空闲且未达到每日接单上限的服务人员
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkerCandidate {
+    /// 服务人员ID
+    pub worker_id: i32,
+
+    /// 服务人员用户名
+    pub username: String,
+
+    /// 平均评分
+    pub avg_rating: f64,
+
+    /// 服务当日已接订单数
+    pub daily_orders: i64,
+
+    /// 每日最大接单数
+    pub max_daily_orders: i32,
+
+    /// 可预订的日程ID
+    pub schedule_id: i32,
+
+    /// 综合得分，越高越优先，查询时为 0，由派单服务计算
+    #[sqlx(default)]
+    pub score: f64,
+}
+
+/// 手动派单请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignWorkerRequest {
+    /// 服务人员ID
+    pub worker_id: i32,
+}
+
+/// 批量设置可预约时间请求
+///
+/// 在 [start_date, end_date] 范围内为每个日期设置 time_slots，
+/// 指定 weekdays 时只对这些星期生效 (1 = 周一 ... 7 = 周日)，用于按周循环排班
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetAvailabilityRequest {
+    /// 开始日期
+    pub start_date: chrono::NaiveDate,
+
+    /// 结束日期 (包含)
+    pub end_date: chrono::NaiveDate,
+
+    /// 可预约时间段
+    pub time_slots: Vec<TimeSlot>,
+
+    /// 每周循环模板 (可选)
+    pub weekdays: Option<Vec<u32>>,
+}
+
+/// 标记不可用日期请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkUnavailableRequest {
+    /// 不可用的日期列表
+    pub dates: Vec<chrono::NaiveDate>,
+}
+
+/// 日程查询参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleQuery {
+    /// 开始日期
+    pub start_date: chrono::NaiveDate,
+
+    /// 结束日期 (包含)
+    pub end_date: chrono::NaiveDate,
+}
+
+/// 未能设置的日程及原因
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleConflict {
+    pub schedule_date: chrono::NaiveDate,
+    pub time_slot: TimeSlot,
+    pub reason: String,
+}
+
+/// 批量设置日程的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleUpdateResult {
+    /// 成功设置的日程数
+    pub updated: usize,
+
+    /// 因冲突被跳过的日程
+    pub conflicts: Vec<ScheduleConflict>,
+}
+
+/// 某日某时间段的可预约情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotAvailability {
+    pub time_slot: TimeSlot,
+
+    /// 可承接该时间段的服务人员数
+    pub available_workers: i64,
+
+    /// 是否可预约
+    pub bookable: bool,
+}
+
+/// 入驻申请审核状态
+/// 与 worker_applications.status 枚举值一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ApplicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ApplicationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationStatus::Pending => "pending",
+            ApplicationStatus::Approved => "approved",
+            ApplicationStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// 服务人员入驻申请
+/// 对应 worker_applications 表
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkerApplication {
+    pub application_id: i32,
+    pub user_id: i32,
+    pub service_category_id: i32,
+    pub real_name: String,
+    pub hourly_rate: Money,
+    pub bio: Option<String>,
+    #[sqlx(json(nullable))]
+    pub skills: Option<Vec<String>>,
+    #[sqlx(json(nullable))]
+    pub service_area: Option<Vec<String>>,
+
+    /// 审核状态
+    pub status: ApplicationStatus,
+
+    pub reviewer_id: Option<i32>,
+    pub review_remark: Option<String>,
+    pub reviewed_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl WorkerApplication {
+    /// 审核完成后发给申请人的通知，待审核的申请没有通知
+    pub fn review_notification(&self) -> Option<NewNotification> {
+        let remark = self.review_remark.as_deref().map(|r| format!("：{}", r)).unwrap_or_default();
+        let (title, content) = match self.status {
+            ApplicationStatus::Approved => (
+                "入驻申请已通过",
+                format!("您已成为平台服务人员，请重新登录后完善资料并设置可预约时间{}", remark),
+            ),
+            ApplicationStatus::Rejected => ("入驻申请未通过", format!("您的服务人员入驻申请未通过审核{}", remark)),
+            ApplicationStatus::Pending => return None,
+        };
+        Some(NewNotification {
+            user_id: self.user_id,
+            notification_type: NotificationType::System,
+            title: title.to_string(),
+            content,
+            related_id: Some(self.application_id.to_string()),
+        })
+    }
+}
+
+/// 申请成为服务人员
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerApplicationRequest {
+    pub service_category_id: i32,
+    pub real_name: String,
+    pub hourly_rate: Money,
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    /// 可服务的区县
+    pub service_area: Vec<String>,
+}
+
+/// 入驻申请列表筛选条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplicationFilter {
+    pub status: Option<ApplicationStatus>,
+}
+
+/// 管理员审核入驻申请
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplicationDecisionRequest {
+    /// 审核意见，驳回时必填
+    pub remark: Option<String>,
+}
+
+/// 服务人员修改自己的资料
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWorkerProfileRequest {
+    pub hourly_rate: Money,
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    pub service_area: Vec<String>,
+    pub max_daily_orders: i32,
+}
+
+/// 服务人员切换是否接单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerAvailabilityRequest {
+    pub is_available: bool,
+}
+
+/// 服务人员搜索条件，分页参数见 `PageQuery`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkerSearchQuery {
+    pub category_id: Option<i32>,
+    /// 服务区域 (区县)
+    pub area: Option<String>,
+    pub min_rating: Option<f64>,
+    /// 是否只返回可接单的服务人员，默认否
+    #[serde(default)]
+    pub available_only: bool,
+}
+
+/// 对外公开的服务人员资料和接单统计
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkerPublicProfile {
+    pub worker_id: i32,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub service_category_id: i32,
+    pub category_name: String,
+    pub hourly_rate: Money,
+    pub bio: Option<String>,
+    #[sqlx(json(nullable))]
+    pub skills: Option<Vec<String>>,
+    #[sqlx(json(nullable))]
+    pub service_area: Option<Vec<String>>,
+    pub is_available: bool,
+    pub avg_rating: f64,
+    pub review_count: i64,
+    /// 派给该服务人员的订单数 (不含已取消)
+    pub total_orders: i64,
+    pub completed_orders: i64,
+    /// 完成率，没有订单时为 0，由订单统计计算得出
+    #[sqlx(skip)]
+    pub completion_rate: f64,
+}
+
+impl WorkerPublicProfile {
+    /// 根据订单统计填写完成率
+    pub fn with_completion_rate(mut self) -> Self {
+        self.completion_rate = completion_rate(self.completed_orders, self.total_orders);
+        self
+    }
+}
+
+/// 完成率，保留两位小数
+pub fn completion_rate(completed_orders: i64, total_orders: i64) -> f64 {
+    if total_orders <= 0 {
+        return 0.0;
+    }
+    (completed_orders as f64 / total_orders as f64 * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_slot_overlaps() {
+        assert!(TimeSlot::Morning.overlaps(TimeSlot::Morning));
+        assert!(TimeSlot::Morning.overlaps(TimeSlot::FullDay));
+        assert!(TimeSlot::FullDay.overlaps(TimeSlot::Evening));
+        assert!(!TimeSlot::Morning.overlaps(TimeSlot::Afternoon));
+        assert!(!TimeSlot::Afternoon.overlaps(TimeSlot::Evening));
+    }
+
+    #[test]
+    fn test_time_slot_can_serve() {
+        assert!(TimeSlot::FullDay.can_serve(TimeSlot::Morning));
+        assert!(TimeSlot::Morning.can_serve(TimeSlot::Morning));
+        assert!(!TimeSlot::Morning.can_serve(TimeSlot::FullDay));
+        assert!(!TimeSlot::Morning.can_serve(TimeSlot::Evening));
+    }
+
+    #[test]
+    fn test_completion_rate() {
+        assert_eq!(completion_rate(0, 0), 0.0);
+        assert_eq!(completion_rate(2, 3), 0.67);
+        assert_eq!(completion_rate(5, 5), 1.0);
+    }
+
+    #[test]
+    fn test_application_review_notification() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(8, 0, 0).unwrap();
+        let mut application = WorkerApplication {
+            application_id: 7,
+            user_id: 3,
+            service_category_id: 1,
+            real_name: "张三".to_string(),
+            hourly_rate: Money::from_yuan(50),
+            bio: None,
+            skills: None,
+            service_area: Some(vec!["朝阳区".to_string()]),
+            status: ApplicationStatus::Pending,
+            reviewer_id: None,
+            review_remark: None,
+            reviewed_at: None,
+            created_at: now,
+        };
+        assert!(application.review_notification().is_none());
+
+        application.status = ApplicationStatus::Rejected;
+        application.review_remark = Some("资料不完整".to_string());
+        let notification = application.review_notification().unwrap();
+        assert_eq!(notification.user_id, 3);
+        assert_eq!(notification.related_id.as_deref(), Some("7"));
+        assert!(notification.content.ends_with("：资料不完整"));
+    }
+}
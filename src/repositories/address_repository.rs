@@ -0,0 +1,435 @@
+//! 地址数据访问层
+//!
+//! 负责 user_addresses 表的读写。地址只做软删除，历史订单仍可引用。
+//! 每个有地址的用户恰好有一个默认地址：所有写操作先锁定用户行，再在同一事务中维护 is_default。
+//! 地址的读写由 `AddressStore` 抽象，测试中可使用内存实现
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{mysql::MySqlPool, MySqlConnection};
+use tokio::sync::RwLock;
+use crate::models::user::{AddressRequest, UserAddress};
+
+/// 查询完整的地址行，参数为 `FROM user_addresses` 之后的条件
+macro_rules! query_address {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            UserAddress,
+            "SELECT address_id, user_id, contact_name, contact_phone, province, city, district, street_address, \
+            is_default AS `is_default!: bool`, created_at AS `created_at!: NaiveDateTime` FROM user_addresses " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 删除地址的结果
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeleteAddressOutcome {
+    Deleted,
+    NotFound,
+    /// 地址被未结束的订单使用
+    InUse,
+}
+
+/// 锁定用户行，使同一用户的地址写操作串行执行
+async fn lock_user(conn: &mut MySqlConnection, user_id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT user_id FROM users WHERE user_id = ? FOR UPDATE", user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn fetch_address(
+    conn: &mut MySqlConnection,
+    user_id: i32,
+    address_id: i32,
+) -> Result<Option<UserAddress>, sqlx::Error> {
+    query_address!("WHERE address_id = ? AND user_id = ? AND is_deleted = FALSE", address_id, user_id)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+/// 查找并锁定地址行 (排他锁)
+/// 与下单事务对地址加的共享锁互斥，下单和删除地址不会交错执行
+async fn lock_address(
+    conn: &mut MySqlConnection,
+    user_id: i32,
+    address_id: i32,
+) -> Result<Option<UserAddress>, sqlx::Error> {
+    query_address!("WHERE address_id = ? AND user_id = ? AND is_deleted = FALSE FOR UPDATE", address_id, user_id)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+/// 将指定地址设为默认，同时取消用户其他地址的默认状态
+async fn set_default(conn: &mut MySqlConnection, user_id: i32, address_id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE user_addresses SET is_default = (address_id = ?) WHERE user_id = ? AND is_deleted = FALSE",
+        address_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+#[async_trait]
+pub trait AddressStore: Send + Sync {
+    /// 用户的地址列表，默认地址在前
+    async fn list_by_user(&self, user_id: i32) -> Result<Vec<UserAddress>, sqlx::Error>;
+
+    /// 查找用户自己的未删除地址
+    async fn find_by_user(&self, user_id: i32, address_id: i32) -> Result<Option<UserAddress>, sqlx::Error>;
+
+    /// 新增地址，用户的第一个地址或要求设为默认时成为默认地址
+    /// 用户的地址数已达到 max_addresses 时不新增并返回 None
+    async fn create(
+        &self,
+        user_id: i32,
+        address: &AddressRequest,
+        max_addresses: i64,
+    ) -> Result<Option<UserAddress>, sqlx::Error>;
+
+    /// 修改地址，地址不存在或不属于该用户时返回 None
+    /// 取消默认地址的校验由业务层完成
+    async fn update(
+        &self,
+        user_id: i32,
+        address_id: i32,
+        address: &AddressRequest,
+    ) -> Result<Option<UserAddress>, sqlx::Error>;
+
+    /// 删除地址，删除的是默认地址时将最近添加的其他地址设为默认
+    async fn delete(&self, user_id: i32, address_id: i32) -> Result<DeleteAddressOutcome, sqlx::Error>;
+}
+
+/// 基于 MySQL 的地址存储
+pub struct AddressRepository {
+    pool: MySqlPool,
+}
+
+impl AddressRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AddressStore for AddressRepository {
+    /// 用户的地址列表，默认地址在前
+    async fn list_by_user(&self, user_id: i32) -> Result<Vec<UserAddress>, sqlx::Error> {
+        query_address!(
+            "WHERE user_id = ? AND is_deleted = FALSE \
+            ORDER BY is_default DESC, address_id DESC",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// 查找用户自己的未删除地址
+    async fn find_by_user(&self, user_id: i32, address_id: i32) -> Result<Option<UserAddress>, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        fetch_address(&mut conn, user_id, address_id).await
+    }
+
+    /// 新增地址，用户的第一个地址或要求设为默认时成为默认地址
+    /// 用户的地址数已达到 max_addresses 时不新增并返回 None
+    async fn create(
+        &self,
+        user_id: i32,
+        address: &AddressRequest,
+        max_addresses: i64,
+    ) -> Result<Option<UserAddress>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        lock_user(&mut tx, user_id).await?;
+
+        let existing: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM user_addresses WHERE user_id = ? AND is_deleted = FALSE",
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if existing >= max_addresses {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let result = sqlx::query!(
+            "INSERT INTO user_addresses (user_id, contact_name, contact_phone, province, city, district, street_address) \
+            VALUES (?, ?, ?, ?, ?, ?, ?)",
+            user_id,
+            &address.contact_name,
+            &address.contact_phone,
+            &address.province,
+            &address.city,
+            &address.district,
+            &address.street_address
+        )
+        .execute(&mut *tx)
+        .await?;
+        let address_id = result.last_insert_id() as i32;
+
+        if existing == 0 || address.is_default == Some(true) {
+            set_default(&mut tx, user_id, address_id).await?;
+        }
+
+        let created = fetch_address(&mut tx, user_id, address_id).await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// 修改地址，地址不存在或不属于该用户时返回 None
+    /// 取消默认地址的校验由业务层完成
+    async fn update(
+        &self,
+        user_id: i32,
+        address_id: i32,
+        address: &AddressRequest,
+    ) -> Result<Option<UserAddress>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        lock_user(&mut tx, user_id).await?;
+
+        if fetch_address(&mut tx, user_id, address_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE user_addresses SET contact_name = ?, contact_phone = ?, province = ?, city = ?, \
+            district = ?, street_address = ? WHERE address_id = ?",
+            &address.contact_name,
+            &address.contact_phone,
+            &address.province,
+            &address.city,
+            &address.district,
+            &address.street_address,
+            address_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if address.is_default == Some(true) {
+            set_default(&mut tx, user_id, address_id).await?;
+        }
+
+        let updated = fetch_address(&mut tx, user_id, address_id).await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    /// 软删除地址，被未结束的订单使用时拒绝
+    /// 删除的是默认地址时，将最近添加的其他地址设为默认
+    async fn delete(&self, user_id: i32, address_id: i32) -> Result<DeleteAddressOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        lock_user(&mut tx, user_id).await?;
+
+        // 先锁定地址再统计订单：等待中的下单事务提交后，统计结果包含其订单
+        let Some(address) = lock_address(&mut tx, user_id, address_id).await? else {
+            tx.rollback().await?;
+            return Ok(DeleteAddressOutcome::NotFound);
+        };
+
+        let active_orders: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM orders WHERE address_id = ? \
+            AND order_status IN ('pending', 'confirmed', 'assigned', 'ongoing')",
+            address_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if active_orders > 0 {
+            tx.rollback().await?;
+            return Ok(DeleteAddressOutcome::InUse);
+        }
+
+        sqlx::query!(
+            "UPDATE user_addresses SET is_deleted = TRUE, is_default = FALSE WHERE address_id = ?",
+            address_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if address.is_default {
+            let next: Option<i32> = sqlx::query_scalar!(
+                "SELECT address_id FROM user_addresses WHERE user_id = ? AND is_deleted = FALSE \
+                ORDER BY address_id DESC LIMIT 1",
+                user_id
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(next) = next {
+                set_default(&mut tx, user_id, next).await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(DeleteAddressOutcome::Deleted)
+    }
+}
+
+/// 内存中的地址存储，仅用于测试和本地调试
+/// 不关联订单，删除时不检查地址是否被进行中的订单使用
+#[derive(Default)]
+pub struct InMemoryAddressStore {
+    addresses: RwLock<Vec<UserAddress>>,
+}
+
+impl InMemoryAddressStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用已有地址初始化，新地址的ID从最大ID之后开始分配
+    pub fn with_addresses(addresses: Vec<UserAddress>) -> Self {
+        Self { addresses: RwLock::new(addresses) }
+    }
+}
+
+/// 将指定地址设为默认，同时取消用户其他地址的默认状态
+fn set_default_in(addresses: &mut [UserAddress], user_id: i32, address_id: i32) {
+    for address in addresses.iter_mut().filter(|a| a.user_id == user_id) {
+        address.is_default = address.address_id == address_id;
+    }
+}
+
+#[async_trait]
+impl AddressStore for InMemoryAddressStore {
+    async fn list_by_user(&self, user_id: i32) -> Result<Vec<UserAddress>, sqlx::Error> {
+        let addresses = self.addresses.read().await;
+        let mut list: Vec<UserAddress> = addresses.iter().filter(|a| a.user_id == user_id).cloned().collect();
+        list.sort_by_key(|a| (std::cmp::Reverse(a.is_default), std::cmp::Reverse(a.address_id)));
+        Ok(list)
+    }
+
+    async fn find_by_user(&self, user_id: i32, address_id: i32) -> Result<Option<UserAddress>, sqlx::Error> {
+        let addresses = self.addresses.read().await;
+        Ok(addresses.iter().find(|a| a.user_id == user_id && a.address_id == address_id).cloned())
+    }
+
+    async fn create(
+        &self,
+        user_id: i32,
+        address: &AddressRequest,
+        max_addresses: i64,
+    ) -> Result<Option<UserAddress>, sqlx::Error> {
+        let mut addresses = self.addresses.write().await;
+        let existing = addresses.iter().filter(|a| a.user_id == user_id).count() as i64;
+        if existing >= max_addresses {
+            return Ok(None);
+        }
+
+        let address_id = addresses.iter().map(|a| a.address_id).max().unwrap_or(0) + 1;
+        let created = UserAddress {
+            address_id,
+            user_id,
+            contact_name: address.contact_name.clone(),
+            contact_phone: address.contact_phone.clone(),
+            province: address.province.clone(),
+            city: address.city.clone(),
+            district: address.district.clone(),
+            street_address: address.street_address.clone(),
+            is_default: false,
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+        addresses.push(created);
+
+        if existing == 0 || address.is_default == Some(true) {
+            set_default_in(&mut addresses, user_id, address_id);
+        }
+        Ok(addresses.iter().find(|a| a.address_id == address_id).cloned())
+    }
+
+    async fn update(
+        &self,
+        user_id: i32,
+        address_id: i32,
+        address: &AddressRequest,
+    ) -> Result<Option<UserAddress>, sqlx::Error> {
+        let mut addresses = self.addresses.write().await;
+        let Some(current) = addresses.iter_mut().find(|a| a.user_id == user_id && a.address_id == address_id) else {
+            return Ok(None);
+        };
+
+        current.contact_name = address.contact_name.clone();
+        current.contact_phone = address.contact_phone.clone();
+        current.province = address.province.clone();
+        current.city = address.city.clone();
+        current.district = address.district.clone();
+        current.street_address = address.street_address.clone();
+
+        if address.is_default == Some(true) {
+            set_default_in(&mut addresses, user_id, address_id);
+        }
+        Ok(addresses.iter().find(|a| a.address_id == address_id).cloned())
+    }
+
+    async fn delete(&self, user_id: i32, address_id: i32) -> Result<DeleteAddressOutcome, sqlx::Error> {
+        let mut addresses = self.addresses.write().await;
+        let Some(index) = addresses.iter().position(|a| a.user_id == user_id && a.address_id == address_id) else {
+            return Ok(DeleteAddressOutcome::NotFound);
+        };
+
+        let removed = addresses.remove(index);
+        if removed.is_default {
+            let next = addresses.iter().filter(|a| a.user_id == user_id).map(|a| a.address_id).max();
+            if let Some(next) = next {
+                set_default_in(&mut addresses, user_id, next);
+            }
+        }
+        Ok(DeleteAddressOutcome::Deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(name: &str, is_default: Option<bool>) -> AddressRequest {
+        AddressRequest {
+            contact_name: name.to_string(),
+            contact_phone: "13800000000".to_string(),
+            province: "广东省".to_string(),
+            city: "深圳市".to_string(),
+            district: "南山区".to_string(),
+            street_address: "科技园1号".to_string(),
+            is_default,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_first_address_is_default() {
+        let store = InMemoryAddressStore::new();
+        let first = store.create(1, &request("甲", None), 20).await.unwrap().unwrap();
+        let second = store.create(1, &request("乙", None), 20).await.unwrap().unwrap();
+        assert!(first.is_default);
+        assert!(!second.is_default);
+
+        let third = store.create(1, &request("丙", Some(true)), 20).await.unwrap().unwrap();
+        let list = store.list_by_user(1).await.unwrap();
+        assert_eq!(list[0].address_id, third.address_id);
+        assert_eq!(list.iter().filter(|a| a.is_default).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_address_limit_and_owner() {
+        let store = InMemoryAddressStore::new();
+        let created = store.create(1, &request("甲", None), 1).await.unwrap().unwrap();
+        assert!(store.create(1, &request("乙", None), 1).await.unwrap().is_none());
+        assert!(store.find_by_user(2, created.address_id).await.unwrap().is_none());
+        assert!(store.update(2, created.address_id, &request("乙", None)).await.unwrap().is_none());
+        assert_eq!(store.delete(2, created.address_id).await.unwrap(), DeleteAddressOutcome::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_delete_default_promotes_newest() {
+        let store = InMemoryAddressStore::new();
+        let first = store.create(1, &request("甲", None), 20).await.unwrap().unwrap();
+        store.create(1, &request("乙", None), 20).await.unwrap();
+        let newest = store.create(1, &request("丙", None), 20).await.unwrap().unwrap();
+
+        assert_eq!(store.delete(1, first.address_id).await.unwrap(), DeleteAddressOutcome::Deleted);
+        let promoted = store.find_by_user(1, newest.address_id).await.unwrap().unwrap();
+        assert!(promoted.is_default);
+        assert!(store.find_by_user(1, first.address_id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,21 @@
+//! 审计日志数据访问层
+//!
+//! 审计日志与被审计的写操作在同一事务中写入
+
+use sqlx::MySqlConnection;
+use crate::models::audit::NewAuditLog;
+
+/// 写入一条审计日志
+pub(crate) async fn record_audit(conn: &mut MySqlConnection, log: &NewAuditLog) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO audit_logs (operator_id, entity_type, entity_id, action, snapshot) VALUES (?, ?, ?, ?, ?)",
+        log.operator_id,
+        log.entity.as_str(),
+        log.entity_id,
+        log.action.as_str(),
+        log.snapshot.to_string()
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
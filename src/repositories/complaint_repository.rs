@@ -0,0 +1,510 @@
+//! 投诉数据访问层
+//!
+//! 负责 complaints 和 complaint_notes 表的读写。
+//! 投诉的读写由 `ComplaintStore` 抽象，测试中可使用内存实现
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::{mysql::MySqlPool, MySqlConnection};
+use tokio::sync::RwLock;
+use crate::models::pagination::PageQuery;
+use crate::models::review::{
+    CompensationType, Complaint, ComplaintNote, ComplaintStatus, ComplaintType, NewComplaint, ResolveComplaintRequest,
+};
+use crate::repositories::coupon_repository::{grant_coupon, InMemoryCouponStore};
+
+/// 查询完整的投诉行，参数为 `FROM complaints` 之后的条件
+macro_rules! query_complaint {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            Complaint,
+            "SELECT complaint_id, order_id, complainant_id, target_id, complaint_type AS `complaint_type: ComplaintType`, \
+            complaint_text, status AS `status!: ComplaintStatus`, evidence_text, handler_id, resolution, \
+            compensation_type AS `compensation_type!: CompensationType`, compensation_coupon_id, resolved_at, \
+            created_at AS `created_at!: NaiveDateTime` FROM complaints " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 查询内部备注，参数为 `FROM complaint_notes` 之后的条件
+macro_rules! query_note {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            ComplaintNote,
+            "SELECT note_id, complaint_id, author_id, note, created_at AS `created_at!: NaiveDateTime` \
+            FROM complaint_notes " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 处理投诉的结果
+#[derive(Debug)]
+pub enum ResolveOutcome {
+    Resolved(Box<Complaint>),
+    /// 投诉状态已被其他请求修改
+    StatusChanged,
+    /// 补偿优惠券不存在、已停用、不在有效期或已领完
+    CouponNotAvailable,
+}
+
+async fn fetch_complaint(conn: &mut MySqlConnection, complaint_id: i32) -> Result<Complaint, sqlx::Error> {
+    query_complaint!("WHERE complaint_id = ?", complaint_id)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+#[async_trait]
+pub trait ComplaintStore: Send + Sync {
+    /// 根据ID查找投诉，不存在时返回 `RowNotFound`
+    async fn find_by_id(&self, complaint_id: i32) -> Result<Complaint, sqlx::Error>;
+
+    /// 用户提交的全部投诉，按时间倒序
+    async fn list_by_complainant(&self, complainant_id: i32) -> Result<Vec<Complaint>, sqlx::Error>;
+
+    /// 管理员投诉队列，按提交时间先后排列，返回 (投诉, 总条数)
+    async fn list(&self, status: Option<ComplaintStatus>, page: &PageQuery) -> Result<(Vec<Complaint>, i64), sqlx::Error>;
+
+    /// 提交投诉，同一投诉人对同一订单只能有一条未结束的投诉，已存在时返回 None
+    async fn create(&self, complaint: &NewComplaint) -> Result<Option<Complaint>, sqlx::Error>;
+
+    /// 分配处理人，待处理的投诉同时进入调查中
+    /// 返回分配前的状态和更新后的投诉，投诉已结束或正在结案时返回 None
+    async fn assign(&self, complaint_id: i32, handler_id: i32) -> Result<Option<(ComplaintStatus, Complaint)>, sqlx::Error>;
+
+    /// 仅当投诉仍处于 from 状态时置为 resolving，返回是否占用成功
+    async fn claim_for_resolution(&self, complaint_id: i32, from: ComplaintStatus) -> Result<bool, sqlx::Error>;
+
+    /// 释放结案占用，把投诉恢复为 to 状态
+    async fn release_claim(&self, complaint_id: i32, to: ComplaintStatus) -> Result<bool, sqlx::Error>;
+
+    /// 结束投诉，补偿为优惠券时在同一事务中发放给投诉人
+    async fn resolve(
+        &self,
+        complaint_id: i32,
+        from: ComplaintStatus,
+        handler_id: i32,
+        request: &ResolveComplaintRequest,
+        today: NaiveDate,
+    ) -> Result<ResolveOutcome, sqlx::Error>;
+
+    /// 添加内部备注
+    async fn add_note(&self, complaint_id: i32, author_id: i32, note: &str) -> Result<ComplaintNote, sqlx::Error>;
+
+    /// 投诉的全部内部备注，按时间先后排列
+    async fn list_notes(&self, complaint_id: i32) -> Result<Vec<ComplaintNote>, sqlx::Error>;
+}
+
+/// 基于 MySQL 的投诉存储
+pub struct ComplaintRepository {
+    pool: MySqlPool,
+}
+
+impl ComplaintRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ComplaintStore for ComplaintRepository {
+    async fn find_by_id(&self, complaint_id: i32) -> Result<Complaint, sqlx::Error> {
+        query_complaint!("WHERE complaint_id = ?", complaint_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// 用户提交的全部投诉，按时间倒序
+    async fn list_by_complainant(&self, complainant_id: i32) -> Result<Vec<Complaint>, sqlx::Error> {
+        query_complaint!("WHERE complainant_id = ? ORDER BY complaint_id DESC", complainant_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// 管理员投诉队列，按提交时间先后排列，返回 (投诉, 总条数)
+    async fn list(
+        &self,
+        status: Option<ComplaintStatus>,
+        page: &PageQuery,
+    ) -> Result<(Vec<Complaint>, i64), sqlx::Error> {
+        let status = status.map(|s| s.as_str());
+        let total: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM complaints WHERE (? IS NULL OR status = ?)",
+            status,
+            status
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = query_complaint!(
+            "WHERE (? IS NULL OR status = ?) \
+            ORDER BY complaint_id LIMIT ? OFFSET ?",
+            status,
+            status,
+            page.page_size(),
+            page.offset()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((rows, total))
+    }
+
+    /// 提交投诉
+    /// 锁定订单行，同一投诉人对同一订单只能有一条未结束的投诉，已存在时返回 None
+    async fn create(&self, complaint: &NewComplaint) -> Result<Option<Complaint>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("SELECT order_id FROM orders WHERE order_id = ? FOR UPDATE", &complaint.order_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let open: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM complaints \
+            WHERE order_id = ? AND complainant_id = ? AND status IN ('pending', 'investigating', 'resolving')",
+            &complaint.order_id,
+            complaint.complainant_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if open > 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let result = sqlx::query!(
+            "INSERT INTO complaints (order_id, complainant_id, target_id, complaint_type, complaint_text, evidence_text) \
+            VALUES (?, ?, ?, ?, ?, ?)",
+            &complaint.order_id,
+            complaint.complainant_id,
+            complaint.target_id,
+            complaint.complaint_type.as_str(),
+            &complaint.complaint_text,
+            complaint.evidence_text.as_deref()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let created = fetch_complaint(&mut tx, result.last_insert_id() as i32).await?;
+        tx.commit().await?;
+        Ok(Some(created))
+    }
+
+    /// 分配处理人，待处理的投诉同时进入调查中
+    /// 返回分配前的状态和更新后的投诉，投诉已结束或正在结案时返回 None
+    async fn assign(
+        &self,
+        complaint_id: i32,
+        handler_id: i32,
+    ) -> Result<Option<(ComplaintStatus, Complaint)>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let from = sqlx::query_scalar!(
+            "SELECT status AS `status!: ComplaintStatus` FROM complaints WHERE complaint_id = ? FOR UPDATE",
+            complaint_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if !from.is_open() {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE complaints SET handler_id = ?, status = ? WHERE complaint_id = ?",
+            handler_id,
+            ComplaintStatus::Investigating.as_str(),
+            complaint_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let complaint = fetch_complaint(&mut tx, complaint_id).await?;
+        tx.commit().await?;
+        Ok(Some((from, complaint)))
+    }
+
+    /// 占用投诉准备结案
+    /// 仅当投诉仍处于 from 状态时置为 resolving，返回是否占用成功；
+    /// 退款补偿在占用成功后进行，并发结案只有一方能发起退款
+    async fn claim_for_resolution(&self, complaint_id: i32, from: ComplaintStatus) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE complaints SET status = ? WHERE complaint_id = ? AND status = ?",
+            ComplaintStatus::Resolving.as_str(),
+            complaint_id,
+            from.as_str()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 释放结案占用，退款失败时把投诉恢复为 to 状态
+    async fn release_claim(&self, complaint_id: i32, to: ComplaintStatus) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE complaints SET status = ? WHERE complaint_id = ? AND status = ?",
+            to.as_str(),
+            complaint_id,
+            ComplaintStatus::Resolving.as_str()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 结束投诉
+    /// 仅当投诉仍处于 from 状态时更新；补偿为优惠券时在同一事务中发放给投诉人，
+    /// 退款补偿由调用方在此之前完成
+    async fn resolve(
+        &self,
+        complaint_id: i32,
+        from: ComplaintStatus,
+        handler_id: i32,
+        request: &ResolveComplaintRequest,
+        today: NaiveDate,
+    ) -> Result<ResolveOutcome, sqlx::Error> {
+        let compensation = request.compensation;
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            "UPDATE complaints SET status = ?, resolution = ?, compensation_type = ?, \
+            compensation_coupon_id = ?, handler_id = COALESCE(handler_id, ?), resolved_at = NOW() \
+            WHERE complaint_id = ? AND status = ?",
+            request.status.as_str(),
+            &request.resolution,
+            compensation.map_or(CompensationType::None, |c| c.compensation_type()).as_str(),
+            compensation.and_then(|c| c.coupon_id()),
+            handler_id,
+            complaint_id,
+            from.as_str()
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(ResolveOutcome::StatusChanged);
+        }
+
+        if let Some(coupon_id) = compensation.and_then(|c| c.coupon_id()) {
+            let complainant_id: i32 = sqlx::query_scalar!(
+                "SELECT complainant_id FROM complaints WHERE complaint_id = ?",
+                complaint_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            if grant_coupon(&mut tx, complainant_id, coupon_id, today).await?.is_none() {
+                tx.rollback().await?;
+                return Ok(ResolveOutcome::CouponNotAvailable);
+            }
+        }
+
+        let complaint = fetch_complaint(&mut tx, complaint_id).await?;
+        tx.commit().await?;
+        Ok(ResolveOutcome::Resolved(Box::new(complaint)))
+    }
+
+    /// 添加内部备注
+    async fn add_note(&self, complaint_id: i32, author_id: i32, note: &str) -> Result<ComplaintNote, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO complaint_notes (complaint_id, author_id, note) VALUES (?, ?, ?)",
+            complaint_id,
+            author_id,
+            note
+        )
+        .execute(&self.pool)
+        .await?;
+
+        query_note!("WHERE note_id = ?", result.last_insert_id())
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// 投诉的全部内部备注，按时间先后排列
+    async fn list_notes(&self, complaint_id: i32) -> Result<Vec<ComplaintNote>, sqlx::Error> {
+        query_note!("WHERE complaint_id = ? ORDER BY note_id", complaint_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+}
+
+/// 内存中的投诉存储，仅用于测试和本地调试
+/// 优惠券补偿通过共享的内存优惠券存储发放；提交投诉时不锁定、不校验订单
+pub struct InMemoryComplaintStore {
+    coupons: Arc<InMemoryCouponStore>,
+    tables: RwLock<ComplaintTables>,
+}
+
+#[derive(Default)]
+struct ComplaintTables {
+    complaints: Vec<Complaint>,
+    notes: Vec<ComplaintNote>,
+}
+
+impl InMemoryComplaintStore {
+    pub fn new(coupons: Arc<InMemoryCouponStore>) -> Self {
+        Self::with_complaints(coupons, Vec::new())
+    }
+
+    /// 使用已有投诉初始化
+    pub fn with_complaints(coupons: Arc<InMemoryCouponStore>, complaints: Vec<Complaint>) -> Self {
+        Self {
+            coupons,
+            tables: RwLock::new(ComplaintTables { complaints, notes: Vec::new() }),
+        }
+    }
+
+    /// 投诉处于 from 状态时改为 to
+    async fn set_status(&self, complaint_id: i32, from: ComplaintStatus, to: ComplaintStatus) -> bool {
+        let mut tables = self.tables.write().await;
+        match tables.complaints.iter_mut().find(|c| c.complaint_id == complaint_id && c.status == from) {
+            Some(complaint) => {
+                complaint.status = to;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl ComplaintStore for InMemoryComplaintStore {
+    async fn find_by_id(&self, complaint_id: i32) -> Result<Complaint, sqlx::Error> {
+        let tables = self.tables.read().await;
+        tables
+            .complaints
+            .iter()
+            .find(|c| c.complaint_id == complaint_id)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn list_by_complainant(&self, complainant_id: i32) -> Result<Vec<Complaint>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        Ok(tables.complaints.iter().rev().filter(|c| c.complainant_id == complainant_id).cloned().collect())
+    }
+
+    async fn list(&self, status: Option<ComplaintStatus>, page: &PageQuery) -> Result<(Vec<Complaint>, i64), sqlx::Error> {
+        let tables = self.tables.read().await;
+        let matched: Vec<&Complaint> = tables
+            .complaints
+            .iter()
+            .filter(|c| status.is_none_or(|s| c.status == s))
+            .collect();
+        let rows = matched
+            .iter()
+            .skip(page.offset() as usize)
+            .take(page.page_size() as usize)
+            .map(|c| (*c).clone())
+            .collect();
+        Ok((rows, matched.len() as i64))
+    }
+
+    async fn create(&self, complaint: &NewComplaint) -> Result<Option<Complaint>, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        let open = tables.complaints.iter().any(|c| {
+            c.order_id == complaint.order_id
+                && c.complainant_id == complaint.complainant_id
+                && (c.status.is_open() || c.status == ComplaintStatus::Resolving)
+        });
+        if open {
+            return Ok(None);
+        }
+
+        let created = Complaint {
+            complaint_id: tables.complaints.len() as i32 + 1,
+            order_id: complaint.order_id.clone(),
+            complainant_id: complaint.complainant_id,
+            target_id: complaint.target_id,
+            complaint_type: complaint.complaint_type,
+            complaint_text: complaint.complaint_text.clone(),
+            status: ComplaintStatus::Pending,
+            evidence_text: complaint.evidence_text.clone(),
+            handler_id: None,
+            resolution: None,
+            compensation_type: CompensationType::None,
+            compensation_coupon_id: None,
+            resolved_at: None,
+            created_at: chrono::Local::now().naive_local(),
+        };
+        tables.complaints.push(created.clone());
+        Ok(Some(created))
+    }
+
+    async fn assign(&self, complaint_id: i32, handler_id: i32) -> Result<Option<(ComplaintStatus, Complaint)>, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        let complaint = tables
+            .complaints
+            .iter_mut()
+            .find(|c| c.complaint_id == complaint_id)
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let from = complaint.status;
+        if !from.is_open() {
+            return Ok(None);
+        }
+        complaint.handler_id = Some(handler_id);
+        complaint.status = ComplaintStatus::Investigating;
+        Ok(Some((from, complaint.clone())))
+    }
+
+    async fn claim_for_resolution(&self, complaint_id: i32, from: ComplaintStatus) -> Result<bool, sqlx::Error> {
+        Ok(self.set_status(complaint_id, from, ComplaintStatus::Resolving).await)
+    }
+
+    async fn release_claim(&self, complaint_id: i32, to: ComplaintStatus) -> Result<bool, sqlx::Error> {
+        Ok(self.set_status(complaint_id, ComplaintStatus::Resolving, to).await)
+    }
+
+    async fn resolve(
+        &self,
+        complaint_id: i32,
+        from: ComplaintStatus,
+        handler_id: i32,
+        request: &ResolveComplaintRequest,
+        today: NaiveDate,
+    ) -> Result<ResolveOutcome, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        let Some(complaint) = tables
+            .complaints
+            .iter_mut()
+            .find(|c| c.complaint_id == complaint_id && c.status == from)
+        else {
+            return Ok(ResolveOutcome::StatusChanged);
+        };
+
+        let compensation = request.compensation;
+        if let Some(coupon_id) = compensation.and_then(|c| c.coupon_id())
+            && self.coupons.grant(complaint.complainant_id, coupon_id, today).await.is_none()
+        {
+            return Ok(ResolveOutcome::CouponNotAvailable);
+        }
+
+        complaint.status = request.status;
+        complaint.resolution = Some(request.resolution.clone());
+        complaint.compensation_type = compensation.map_or(CompensationType::None, |c| c.compensation_type());
+        complaint.compensation_coupon_id = compensation.and_then(|c| c.coupon_id());
+        complaint.handler_id.get_or_insert(handler_id);
+        complaint.resolved_at = Some(chrono::Local::now().naive_local());
+        Ok(ResolveOutcome::Resolved(Box::new(complaint.clone())))
+    }
+
+    async fn add_note(&self, complaint_id: i32, author_id: i32, note: &str) -> Result<ComplaintNote, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        let created = ComplaintNote {
+            note_id: tables.notes.len() as i64 + 1,
+            complaint_id,
+            author_id,
+            note: note.to_string(),
+            created_at: chrono::Local::now().naive_local(),
+        };
+        tables.notes.push(created.clone());
+        Ok(created)
+    }
+
+    async fn list_notes(&self, complaint_id: i32) -> Result<Vec<ComplaintNote>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        Ok(tables.notes.iter().filter(|n| n.complaint_id == complaint_id).cloned().collect())
+    }
+}
@@ -0,0 +1,430 @@
+//! 优惠券数据访问层
+//!
+//! 负责优惠券和用户优惠券相关的数据库操作。
+//! 优惠券的核销和退还与订单写在同一事务中，见 `OrderRepository`。
+//! 优惠券的查询和领取由 `CouponStore` 抽象，测试中可使用内存实现
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::{mysql::MySqlPool, types::Json, MySqlConnection};
+use tokio::sync::RwLock;
+use crate::models::coupon::{Coupon, DiscountType, UserCoupon, UserCouponDetail};
+use crate::models::money::Money;
+
+/// 查询完整的优惠券行并组装为模型，参数为 `FROM coupons` 之后的条件
+/// 适用服务以 `Json<Vec<i32>>` 解码后取出内部列表
+macro_rules! query_coupon {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query!(
+            "SELECT coupon_id, coupon_code, coupon_name, discount_type AS `discount_type: DiscountType`, \
+            discount_value AS `discount_value: Money`, min_order_amount AS `min_order_amount!: Money`, \
+            applicable_services AS `applicable_services: Json<Vec<i32>>`, valid_from, valid_until, \
+            usage_limit AS `usage_limit!`, used_count AS `used_count!`, is_active AS `is_active!: bool`, \
+            created_at AS `created_at!: NaiveDateTime` FROM coupons " + $tail
+            $(, $arg)*
+        )
+        .map(|row| Coupon {
+            coupon_id: row.coupon_id,
+            coupon_code: row.coupon_code,
+            coupon_name: row.coupon_name,
+            discount_type: row.discount_type,
+            discount_value: row.discount_value,
+            min_order_amount: row.min_order_amount,
+            applicable_services: row.applicable_services.map(|services| services.0),
+            valid_from: row.valid_from,
+            valid_until: row.valid_until,
+            usage_limit: row.usage_limit,
+            used_count: row.used_count,
+            is_active: row.is_active,
+            created_at: row.created_at,
+        })
+    };
+}
+
+/// 查询完整的用户优惠券行，参数为 `FROM user_coupons` 之后的条件
+macro_rules! query_user_coupon {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            UserCoupon,
+            "SELECT user_coupon_id, user_id, coupon_id, order_id, is_used AS `is_used!: bool`, used_at, \
+            expires_at, created_at AS `created_at!: NaiveDateTime` FROM user_coupons " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 领取优惠券的结果
+#[derive(Debug)]
+pub enum ClaimOutcome {
+    Claimed(UserCoupon),
+    /// 优惠券不存在、已停用、不在有效期或已用完
+    NotAvailable,
+    /// 用户已领取过该优惠券
+    AlreadyClaimed,
+}
+
+/// 在调用方的事务中向用户发放一张优惠券，不受每人限领一张的限制，返回用户优惠券ID
+/// 用户领取和投诉补偿都通过这里发放：锁定优惠券行，
+/// 优惠券未启用、不在有效期内或已领完时不发放并返回 None
+pub(crate) async fn grant_coupon(
+    conn: &mut MySqlConnection,
+    user_id: i32,
+    coupon_id: i32,
+    today: NaiveDate,
+) -> Result<Option<u64>, sqlx::Error> {
+    let valid_until: Option<NaiveDate> = sqlx::query_scalar!(
+        "SELECT valid_until FROM coupons \
+        WHERE coupon_id = ? AND is_active = TRUE AND ? BETWEEN valid_from AND valid_until \
+        AND used_count < usage_limit FOR UPDATE",
+        coupon_id,
+        today
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(valid_until) = valid_until else {
+        return Ok(None);
+    };
+
+    let result = sqlx::query!(
+        "INSERT INTO user_coupons (user_id, coupon_id, expires_at) VALUES (?, ?, ?)",
+        user_id,
+        coupon_id,
+        valid_until
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(Some(result.last_insert_id()))
+}
+
+#[async_trait]
+pub trait CouponStore: Send + Sync {
+    /// 根据优惠券码查找优惠券
+    async fn find_by_code(&self, code: &str) -> Result<Option<Coupon>, sqlx::Error>;
+
+    /// 当前可领取的优惠券
+    async fn list_available(&self, today: NaiveDate) -> Result<Vec<Coupon>, sqlx::Error>;
+
+    /// 领取优惠券，每个用户每张优惠券只能领取一次
+    async fn claim(&self, user_id: i32, coupon_id: i32, today: NaiveDate) -> Result<ClaimOutcome, sqlx::Error>;
+
+    /// 用户领取的全部优惠券，未使用的在前
+    async fn list_user_coupons(&self, user_id: i32) -> Result<Vec<UserCouponDetail>, sqlx::Error>;
+
+    /// 用户领取的某张优惠券中尚未使用且未过期的一张
+    async fn find_usable_user_coupon(
+        &self,
+        user_id: i32,
+        coupon_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<UserCoupon>, sqlx::Error>;
+}
+
+/// 基于 MySQL 的优惠券存储
+pub struct CouponRepository {
+    pool: MySqlPool,
+}
+
+impl CouponRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CouponStore for CouponRepository {
+    /// 根据优惠券码查找优惠券
+    async fn find_by_code(&self, code: &str) -> Result<Option<Coupon>, sqlx::Error> {
+        let row = query_coupon!("WHERE coupon_code = ?", code)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    /// 当前可领取的优惠券
+    async fn list_available(&self, today: NaiveDate) -> Result<Vec<Coupon>, sqlx::Error> {
+        let rows = query_coupon!(
+            "WHERE is_active = TRUE AND ? BETWEEN valid_from AND valid_until AND used_count < usage_limit \
+            ORDER BY valid_until, coupon_id",
+            today
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 领取优惠券，每个用户每张优惠券只能领取一次
+    /// 锁定用户行使同一用户的并发领取串行执行，未领取过时通过 `grant_coupon` 在同一事务中发放
+    async fn claim(&self, user_id: i32, coupon_id: i32, today: NaiveDate) -> Result<ClaimOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("SELECT user_id FROM users WHERE user_id = ? FOR UPDATE", user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let claimed: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM user_coupons WHERE user_id = ? AND coupon_id = ?",
+            user_id,
+            coupon_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if claimed > 0 {
+            tx.rollback().await?;
+            return Ok(ClaimOutcome::AlreadyClaimed);
+        }
+
+        let Some(user_coupon_id) = grant_coupon(&mut tx, user_id, coupon_id, today).await? else {
+            tx.rollback().await?;
+            return Ok(ClaimOutcome::NotAvailable);
+        };
+
+        let row = query_user_coupon!("WHERE user_coupon_id = ?", user_coupon_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(ClaimOutcome::Claimed(row))
+    }
+
+    /// 用户领取的全部优惠券，未使用的在前
+    async fn list_user_coupons(&self, user_id: i32) -> Result<Vec<UserCouponDetail>, sqlx::Error> {
+        sqlx::query!(
+            "SELECT uc.user_coupon_id, uc.order_id, uc.is_used AS `is_used!: bool`, uc.used_at, uc.expires_at, \
+            uc.created_at AS `claimed_at!: NaiveDateTime`, c.coupon_id, c.coupon_code, c.coupon_name, \
+            c.discount_type AS `discount_type: DiscountType`, c.discount_value AS `discount_value: Money`, \
+            c.min_order_amount AS `min_order_amount!: Money`, \
+            c.applicable_services AS `applicable_services: Json<Vec<i32>>`, c.valid_from, c.valid_until, \
+            c.usage_limit AS `usage_limit!`, c.used_count AS `used_count!`, c.is_active AS `is_active!: bool`, \
+            c.created_at AS `coupon_created_at!: NaiveDateTime` \
+            FROM user_coupons uc JOIN coupons c ON c.coupon_id = uc.coupon_id \
+            WHERE uc.user_id = ? ORDER BY uc.is_used, uc.expires_at, uc.user_coupon_id",
+            user_id
+        )
+        .map(|row| UserCouponDetail {
+            user_coupon_id: row.user_coupon_id,
+            order_id: row.order_id,
+            is_used: row.is_used,
+            used_at: row.used_at,
+            expires_at: row.expires_at,
+            claimed_at: row.claimed_at,
+            coupon: Coupon {
+                coupon_id: row.coupon_id,
+                coupon_code: row.coupon_code,
+                coupon_name: row.coupon_name,
+                discount_type: row.discount_type,
+                discount_value: row.discount_value,
+                min_order_amount: row.min_order_amount,
+                applicable_services: row.applicable_services.map(|services| services.0),
+                valid_from: row.valid_from,
+                valid_until: row.valid_until,
+                usage_limit: row.usage_limit,
+                used_count: row.used_count,
+                is_active: row.is_active,
+                created_at: row.coupon_created_at,
+            },
+        })
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// 用户领取的某张优惠券中尚未使用且未过期的一张
+    async fn find_usable_user_coupon(
+        &self,
+        user_id: i32,
+        coupon_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<UserCoupon>, sqlx::Error> {
+        let row = query_user_coupon!(
+            "WHERE user_id = ? AND coupon_id = ? AND is_used = FALSE AND expires_at >= ? \
+            ORDER BY expires_at LIMIT 1",
+            user_id,
+            coupon_id,
+            today
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+}
+
+/// 优惠券在 today 可以领取：已启用、在有效期内且未领完
+fn is_claimable(coupon: &Coupon, today: NaiveDate) -> bool {
+    coupon.is_active
+        && coupon.valid_from <= today
+        && today <= coupon.valid_until
+        && coupon.used_count < coupon.usage_limit
+}
+
+/// 内存中的优惠券存储，仅用于测试和本地调试
+/// 下单核销由订单存储负责，内存实现中领取的优惠券不会被标记为已使用
+#[derive(Default)]
+pub struct InMemoryCouponStore {
+    tables: RwLock<CouponTables>,
+}
+
+#[derive(Default)]
+struct CouponTables {
+    coupons: Vec<Coupon>,
+    user_coupons: Vec<UserCoupon>,
+}
+
+impl CouponTables {
+    /// 与 `grant_coupon` 相同：优惠券可领取时发放一张，不受每人限领一张的限制
+    fn grant(&mut self, user_id: i32, coupon_id: i32, today: NaiveDate) -> Option<UserCoupon> {
+        let valid_until = self
+            .coupons
+            .iter()
+            .find(|c| c.coupon_id == coupon_id && is_claimable(c, today))?
+            .valid_until;
+        let user_coupon = UserCoupon {
+            user_coupon_id: self.user_coupons.len() as i32 + 1,
+            user_id,
+            coupon_id,
+            order_id: None,
+            is_used: false,
+            used_at: None,
+            expires_at: valid_until,
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+        self.user_coupons.push(user_coupon.clone());
+        Some(user_coupon)
+    }
+}
+
+impl InMemoryCouponStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用已有优惠券初始化
+    pub fn with_coupons(coupons: Vec<Coupon>) -> Self {
+        Self {
+            tables: RwLock::new(CouponTables { coupons, user_coupons: Vec::new() }),
+        }
+    }
+
+    /// 向用户发放一张优惠券，规则与 `grant_coupon` 相同，供内存投诉存储发放补偿
+    pub async fn grant(&self, user_id: i32, coupon_id: i32, today: NaiveDate) -> Option<UserCoupon> {
+        self.tables.write().await.grant(user_id, coupon_id, today)
+    }
+}
+
+#[async_trait]
+impl CouponStore for InMemoryCouponStore {
+    async fn find_by_code(&self, code: &str) -> Result<Option<Coupon>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        Ok(tables.coupons.iter().find(|c| c.coupon_code == code).cloned())
+    }
+
+    async fn list_available(&self, today: NaiveDate) -> Result<Vec<Coupon>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        let mut coupons: Vec<Coupon> = tables.coupons.iter().filter(|c| is_claimable(c, today)).cloned().collect();
+        coupons.sort_by_key(|c| (c.valid_until, c.coupon_id));
+        Ok(coupons)
+    }
+
+    async fn claim(&self, user_id: i32, coupon_id: i32, today: NaiveDate) -> Result<ClaimOutcome, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        if tables.user_coupons.iter().any(|uc| uc.user_id == user_id && uc.coupon_id == coupon_id) {
+            return Ok(ClaimOutcome::AlreadyClaimed);
+        }
+        Ok(tables
+            .grant(user_id, coupon_id, today)
+            .map_or(ClaimOutcome::NotAvailable, ClaimOutcome::Claimed))
+    }
+
+    async fn list_user_coupons(&self, user_id: i32) -> Result<Vec<UserCouponDetail>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        let mut details: Vec<UserCouponDetail> = tables
+            .user_coupons
+            .iter()
+            .filter(|uc| uc.user_id == user_id)
+            .filter_map(|uc| {
+                let coupon = tables.coupons.iter().find(|c| c.coupon_id == uc.coupon_id)?;
+                Some(UserCouponDetail {
+                    user_coupon_id: uc.user_coupon_id,
+                    order_id: uc.order_id.clone(),
+                    is_used: uc.is_used,
+                    used_at: uc.used_at,
+                    expires_at: uc.expires_at,
+                    claimed_at: uc.created_at,
+                    coupon: coupon.clone(),
+                })
+            })
+            .collect();
+        details.sort_by_key(|d| (d.is_used, d.expires_at, d.user_coupon_id));
+        Ok(details)
+    }
+
+    async fn find_usable_user_coupon(
+        &self,
+        user_id: i32,
+        coupon_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<UserCoupon>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .user_coupons
+            .iter()
+            .filter(|uc| uc.user_id == user_id && uc.coupon_id == coupon_id && !uc.is_used && uc.expires_at >= today)
+            .min_by_key(|uc| uc.expires_at)
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::coupon::DiscountType;
+    use crate::models::Money;
+
+    fn coupon(coupon_id: i32, usage_limit: i32) -> Coupon {
+        Coupon {
+            coupon_id,
+            coupon_code: format!("CODE{}", coupon_id),
+            coupon_name: "新客立减".to_string(),
+            discount_type: DiscountType::Fixed,
+            discount_value: Money::from_yuan(10),
+            min_order_amount: Money::ZERO,
+            applicable_services: None,
+            valid_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            valid_until: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+            usage_limit,
+            used_count: 0,
+            is_active: true,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_claim_once_per_user() {
+        let store = InMemoryCouponStore::with_coupons(vec![coupon(1, 10)]);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        let ClaimOutcome::Claimed(claimed) = store.claim(1, 1, today).await.unwrap() else {
+            panic!("首次领取应成功");
+        };
+        assert_eq!(claimed.expires_at, NaiveDate::from_ymd_opt(2025, 12, 31).unwrap());
+        assert!(matches!(store.claim(1, 1, today).await.unwrap(), ClaimOutcome::AlreadyClaimed));
+        assert!(matches!(store.claim(2, 1, today).await.unwrap(), ClaimOutcome::Claimed(_)));
+
+        let usable = store.find_usable_user_coupon(1, 1, today).await.unwrap().unwrap();
+        assert_eq!(usable.user_coupon_id, claimed.user_coupon_id);
+        assert_eq!(store.list_user_coupons(1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_claim_outside_validity() {
+        let store = InMemoryCouponStore::with_coupons(vec![coupon(1, 10), coupon(2, 0)]);
+        let expired = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        assert!(matches!(store.claim(1, 1, expired).await.unwrap(), ClaimOutcome::NotAvailable));
+        assert!(matches!(store.claim(1, 2, today).await.unwrap(), ClaimOutcome::NotAvailable));
+        assert!(store.list_available(expired).await.unwrap().is_empty());
+        assert_eq!(store.list_available(today).await.unwrap().len(), 1);
+    }
+}
@@ -1,7 +1,30 @@
 pub mod user_repository;
+pub mod address_repository;
 pub mod service_repository;
 pub mod order_repository;
+pub mod refresh_token_repository;
+pub mod coupon_repository;
+pub mod worker_repository;
+pub mod worker_application_repository;
+pub mod schedule_repository;
+pub mod payment_repository;
+pub mod wallet_repository;
+pub mod review_repository;
+pub mod complaint_repository;
+pub mod notification_repository;
+pub mod audit_repository;
 
-pub use user_repository::UserRepository;
-pub use service_repository::ServiceRepository;
-pub use order_repository::OrderRepository;
\ No newline at end of file
+pub use user_repository::{UserStore, UserRepository, InMemoryUserStore};
+pub use address_repository::{AddressStore, AddressRepository, DeleteAddressOutcome, InMemoryAddressStore};
+pub use service_repository::{CatalogDeleteOutcome, CatalogWriteOutcome, ServiceStore, ServiceRepository, InMemoryServiceStore};
+pub use order_repository::{AssignOutcome, CreateOrderOutcome, OrderStore, OrderRepository, InMemoryOrderStore};
+pub use coupon_repository::{ClaimOutcome, CouponStore, CouponRepository, InMemoryCouponStore};
+pub use worker_repository::WorkerRepository;
+pub use worker_application_repository::{CreateApplicationOutcome, ReviewApplicationOutcome, WorkerApplicationRepository};
+pub use schedule_repository::ScheduleRepository;
+pub use payment_repository::{CreatePaymentOutcome, PaymentStore, PaymentRepository, InMemoryPaymentStore};
+pub use wallet_repository::WalletRepository;
+pub use review_repository::ReviewRepository;
+pub use complaint_repository::{ComplaintRepository, ComplaintStore, InMemoryComplaintStore, ResolveOutcome};
+pub use notification_repository::NotificationRepository;
+pub use refresh_token_repository::{RefreshTokenStore, RefreshTokenRepository, InMemoryRefreshTokenStore};
\ No newline at end of file
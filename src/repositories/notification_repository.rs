@@ -0,0 +1,121 @@
+//! 通知数据访问层
+//!
+//! 负责 notifications 表的读写。业务代码通过 `Notifier` 发送通知，不直接调用本模块
+
+use chrono::NaiveDateTime;
+use sqlx::mysql::MySqlPool;
+use crate::models::notification::{NewNotification, Notification, NotificationType};
+use crate::models::pagination::CursorQuery;
+
+/// 查询完整的通知行，参数为 `FROM notifications` 之后的条件
+macro_rules! query_notification {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            Notification,
+            "SELECT notification_id, user_id, notification_type AS `notification_type: NotificationType`, \
+            title, content, related_id, is_read AS `is_read!: bool`, created_at AS `created_at!: NaiveDateTime` \
+            FROM notifications " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+#[derive(Clone)]
+pub struct NotificationRepository {
+    pool: MySqlPool,
+}
+
+impl NotificationRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 在一个事务中写入一批通知，返回写入后的通知
+    pub async fn create_many(&self, notifications: &[NewNotification]) -> Result<Vec<Notification>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut created = Vec::with_capacity(notifications.len());
+        for notification in notifications {
+            let result = sqlx::query!(
+                "INSERT INTO notifications (user_id, notification_type, title, content, related_id) \
+                VALUES (?, ?, ?, ?, ?)",
+                notification.user_id,
+                notification.notification_type.as_str(),
+                &notification.title,
+                &notification.content,
+                notification.related_id.as_deref()
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            let row = query_notification!("WHERE notification_id = ?", result.last_insert_id())
+                .fetch_one(&mut *tx)
+                .await?;
+            created.push(row);
+        }
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// 用户的通知，按时间倒序，从游标之前开始多取一条用于判断是否还有下一页
+    pub async fn list_by_user(
+        &self,
+        user_id: i32,
+        notification_type: Option<NotificationType>,
+        query: &CursorQuery,
+    ) -> Result<Vec<Notification>, sqlx::Error> {
+        let notification_type = notification_type.map(|t| t.as_str());
+        query_notification!(
+            "WHERE user_id = ? AND (? IS NULL OR notification_id < ?) AND (? IS NULL OR notification_type = ?) \
+            ORDER BY notification_id DESC LIMIT ?",
+            user_id,
+            query.cursor,
+            query.cursor,
+            notification_type,
+            notification_type,
+            query.limit() + 1
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn count_unread(&self, user_id: i32) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!("SELECT COUNT(*) FROM notifications WHERE user_id = ? AND is_read = FALSE", user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// 标记单条通知为已读，通知不存在或不属于该用户时返回 false
+    /// MySQL 的影响行数不包含值未变化的行，因此先单独确认通知归属
+    pub async fn mark_read(&self, user_id: i32, notification_id: i32) -> Result<bool, sqlx::Error> {
+        let exists: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM notifications WHERE notification_id = ? AND user_id = ?",
+            notification_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if exists == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            "UPDATE notifications SET is_read = TRUE WHERE notification_id = ? AND user_id = ?",
+            notification_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(true)
+    }
+
+    /// 标记用户的全部未读通知为已读，返回更新条数
+    pub async fn mark_all_read(&self, user_id: i32) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE notifications SET is_read = TRUE WHERE user_id = ? AND is_read = FALSE",
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
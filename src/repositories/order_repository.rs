@@ -1,10 +1,160 @@
 //! 订单数据访问层
 //!
-//! 负责订单相关的数据库操作
+//! 负责订单相关的数据库操作。
+//! 订单的查询、创建和状态流转由 `OrderStore` 抽象，测试中可使用内存实现
 
-use sqlx::{mysql::MySqlPool, Row};
-use crate::models::order::Order;
+use std::sync::Arc;
 
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::{mysql::MySqlPool, MySqlConnection};
+use tokio::sync::RwLock;
+use crate::models::money::Money;
+use crate::models::order::{NewOrder, NewOrderAddon, Order, OrderPaymentStatus, OrderStatus, OrderStatusHistory};
+use crate::models::worker::TimeSlot;
+use crate::repositories::AddressStore;
+
+/// 查询完整的订单行，参数为 `FROM orders` 之后的条件
+/// duration 为 DECIMAL(5,1)，不是金额，转换为 DOUBLE 后读取为 f64
+macro_rules! query_order {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            Order,
+            "SELECT order_id, customer_id, worker_id, address_id, service_id, coupon_id, service_date, \
+            time_slot AS `time_slot: TimeSlot`, CAST(duration AS DOUBLE) AS `duration!: f64`, \
+            unit_price AS `unit_price: Money`, subtotal AS `subtotal: Money`, \
+            discount_amount AS `discount_amount!: Money`, total_amount AS `total_amount: Money`, \
+            payment_status AS `payment_status!: OrderPaymentStatus`, order_status AS `order_status!: OrderStatus`, \
+            special_instructions, cancellation_reason, scheduled_start_time, actual_start_time, actual_end_time, \
+            created_at AS `created_at!: NaiveDateTime`, updated_at AS `updated_at!: NaiveDateTime` \
+            FROM orders " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 订单编号: YYYYMMDD + 6位当日序号
+fn format_order_id(date: NaiveDate, seq: u64) -> String {
+    format!("{}{:06}", date.format("%Y%m%d"), seq)
+}
+
+/// 从 order_sequences 分配当日的下一个序号
+/// 计数行在事务提交前保持锁定，当日首单并发创建时也不会分到相同的序号
+async fn allocate_order_seq(conn: &mut MySqlConnection, date: NaiveDate) -> Result<u64, sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO order_sequences (seq_date, seq) VALUES (?, LAST_INSERT_ID(1)) \
+        ON DUPLICATE KEY UPDATE seq = LAST_INSERT_ID(seq + 1)",
+        date
+    )
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query_scalar!("SELECT LAST_INSERT_ID()").fetch_one(&mut *conn).await
+}
+
+/// 将全天日程拆分为上午、下午、晚上三个空闲分段，返回 order_slot 对应分段的日程ID
+/// 分段订单只占用所在分段，其余分段仍可接单
+async fn split_full_day(
+    conn: &mut MySqlConnection,
+    schedule_id: i32,
+    worker_id: i32,
+    schedule_date: NaiveDate,
+    order_slot: TimeSlot,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query!("DELETE FROM worker_schedules WHERE schedule_id = ?", schedule_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let mut order_schedule_id = 0;
+    for slot in TimeSlot::PARTS {
+        let result = sqlx::query!(
+            "INSERT INTO worker_schedules (worker_id, schedule_date, time_slot, status) VALUES (?, ?, ?, 'available')",
+            worker_id,
+            schedule_date,
+            slot
+        )
+        .execute(&mut *conn)
+        .await?;
+        if slot == order_slot {
+            order_schedule_id = result.last_insert_id() as i32;
+        }
+    }
+    Ok(order_schedule_id)
+}
+
+/// 派单结果
+#[derive(Debug)]
+pub enum AssignOutcome {
+    /// 派单成功
+    Assigned(Box<Order>),
+    /// 订单已不是待派单 (confirmed) 状态
+    OrderNotAssignable,
+    /// 日程已被预订或不可用
+    SlotUnavailable,
+    /// 服务人员当日接单数已达上限
+    DailyLimitReached,
+}
+
+/// 创建订单结果
+#[derive(Debug)]
+pub enum CreateOrderOutcome {
+    /// 创建成功
+    Created(Box<Order>),
+    /// 服务地址不存在、已删除或不属于该客户
+    AddressNotFound,
+    /// 优惠券已失效、已领完或用户优惠券已被使用
+    CouponUnavailable,
+}
+
+#[async_trait]
+pub trait OrderStore: Send + Sync {
+    /// 获取所有订单列表
+    async fn list_orders(&self) -> Result<Vec<Order>, sqlx::Error>;
+
+    /// 获取客户本人的订单列表，按下单时间倒序
+    async fn list_by_customer(&self, customer_id: i32) -> Result<Vec<Order>, sqlx::Error>;
+
+    /// 获取指派给服务人员的订单列表，按服务日期倒序
+    async fn list_by_worker(&self, worker_id: i32) -> Result<Vec<Order>, sqlx::Error>;
+
+    /// 根据ID查找订单，不存在时返回 `RowNotFound`
+    async fn find_by_id(&self, id: String) -> Result<Order, sqlx::Error>;
+
+    /// 创建订单及其附加服务
+    /// 服务地址在同一事务中校验并加共享锁，事务提交前地址不会被并发删除。
+    /// 订单编号在同一事务中生成，当日最大编号行被锁定以避免并发下单时编号冲突。
+    /// 使用优惠券时在同一事务中核销用户优惠券并递增优惠券使用次数，
+    /// 使用次数已达上限、优惠券失效或用户优惠券已被使用时回滚
+    async fn create_order(&self, order: NewOrder, addons: Vec<NewOrderAddon>) -> Result<CreateOrderOutcome, sqlx::Error>;
+
+    /// 变更订单状态并记录变更历史
+    /// 仅当订单当前状态仍为 from 时才会更新，返回 None 表示状态已被并发修改
+    /// 进入 ongoing 时记录实际开始时间，进入 completed 时记录实际结束时间，
+    /// 进入 cancelled 时 remark 作为取消原因保存
+    async fn update_status(
+        &self,
+        order_id: &str,
+        from: OrderStatus,
+        to: OrderStatus,
+        changed_by: i32,
+        remark: Option<&str>,
+    ) -> Result<Option<Order>, sqlx::Error>;
+
+    /// 将订单指派给服务人员
+    /// 在同一事务中锁定订单和日程行，预订日程并把订单状态改为 assigned，
+    /// 保证日程预订与订单更新同时生效；订单不存在时返回 `RowNotFound`
+    async fn assign_worker(
+        &self,
+        order_id: &str,
+        worker_id: i32,
+        schedule_id: i32,
+        changed_by: i32,
+    ) -> Result<AssignOutcome, sqlx::Error>;
+
+    /// 获取订单状态变更历史，按时间先后排序
+    async fn list_status_history(&self, order_id: &str) -> Result<Vec<OrderStatusHistory>, sqlx::Error>;
+}
+
+/// 基于 MySQL 的订单存储
 pub struct OrderRepository {
     pool: MySqlPool,
 }
@@ -13,97 +163,644 @@ impl OrderRepository {
     pub fn new(pool: MySqlPool) -> Self {
         Self { pool }
     }
-    
-    /// 获取所有订单列表
-    pub async fn list_orders(&self) -> Result<Vec<Order>, sqlx::Error> {
-        let orders_result = sqlx::query(
-            "SELECT order_id, customer_id, worker_id, address_id, service_id, coupon_id, \
-            service_date, time_slot, duration, unit_price, subtotal, discount_amount, \
-            total_amount, payment_status, order_status, special_instructions, \
-            cancellation_reason, scheduled_start_time, actual_start_time, actual_end_time, \
-            created_at, updated_at \
-            FROM orders"
+}
+
+#[async_trait]
+impl OrderStore for OrderRepository {
+    async fn list_orders(&self) -> Result<Vec<Order>, sqlx::Error> {
+        let rows = query_order!("")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    async fn list_by_customer(&self, customer_id: i32) -> Result<Vec<Order>, sqlx::Error> {
+        let rows = query_order!("WHERE customer_id = ? ORDER BY created_at DESC", customer_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    async fn list_by_worker(&self, worker_id: i32) -> Result<Vec<Order>, sqlx::Error> {
+        let rows = query_order!("WHERE worker_id = ? ORDER BY service_date DESC", worker_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    async fn find_by_id(&self, id: String) -> Result<Order, sqlx::Error> {
+        let row = query_order!("WHERE order_id = ?", id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    async fn create_order(&self, order: NewOrder, addons: Vec<NewOrderAddon>) -> Result<CreateOrderOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // 共享锁与删除地址时的排他锁互斥：地址先被删除时这里读到 is_deleted，
+        // 先下单时删除会等待本事务提交，随后统计到这笔未结束的订单
+        let address: Option<i32> = sqlx::query_scalar!(
+            "SELECT address_id FROM user_addresses \
+            WHERE address_id = ? AND user_id = ? AND is_deleted = FALSE FOR SHARE",
+            order.address_id,
+            order.customer_id
         )
-        .fetch_all(&self.pool)
-        .await;
-
-        match orders_result {
-            Ok(rows) => {
-                let orders: Vec<Order> = rows.into_iter().map(|row| {
-                    Order {
-                        order_id: row.get("order_id"),
-                        customer_id: row.get("customer_id"),
-                        worker_id: row.get("worker_id"),
-                        address_id: row.get("address_id"),
-                        service_id: row.get("service_id"),
-                        coupon_id: row.get("coupon_id"),
-                        service_date: row.get("service_date"),
-                        time_slot: row.get("time_slot"),
-                        duration: row.get("duration"),
-                        unit_price: row.get("unit_price"),
-                        subtotal: row.get("subtotal"),
-                        discount_amount: row.get("discount_amount"),
-                        total_amount: row.get("total_amount"),
-                        payment_status: row.get("payment_status"),
-                        order_status: row.get("order_status"),
-                        special_instructions: row.get("special_instructions"),
-                        cancellation_reason: row.get("cancellation_reason"),
-                        scheduled_start_time: row.get("scheduled_start_time"),
-                        actual_start_time: row.get("actual_start_time"),
-                        actual_end_time: row.get("actual_end_time"),
-                        created_at: row.get("created_at"),
-                        updated_at: row.get("updated_at"),
-                    }
-                }).collect();
-                Ok(orders)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if address.is_none() {
+            tx.rollback().await?;
+            return Ok(CreateOrderOutcome::AddressNotFound);
+        }
+
+        let today = chrono::Local::now().date_naive();
+        let seq = allocate_order_seq(&mut tx, today).await?;
+        let order_id = format_order_id(today, seq);
+
+        sqlx::query!(
+            "INSERT INTO orders (order_id, customer_id, address_id, service_id, coupon_id, service_date, \
+            time_slot, duration, unit_price, subtotal, discount_amount, total_amount, payment_status, \
+            order_status, special_instructions, scheduled_start_time) \
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'pending', 'pending', ?, ?)",
+            &order_id,
+            order.customer_id,
+            order.address_id,
+            order.service_id,
+            order.coupon_id,
+            order.service_date,
+            order.time_slot,
+            order.duration,
+            order.unit_price,
+            order.subtotal,
+            order.discount_amount,
+            order.total_amount,
+            &order.special_instructions,
+            order.scheduled_start_time
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO order_status_history (order_id, from_status, to_status, changed_by) VALUES (?, NULL, 'pending', ?)",
+            &order_id,
+            order.customer_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for addon in &addons {
+            sqlx::query!(
+                "INSERT INTO order_addons (order_id, addon_id, quantity, unit_price) VALUES (?, ?, ?, ?)",
+                &order_id,
+                addon.addon_id,
+                addon.quantity,
+                addon.unit_price
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let (Some(coupon_id), Some(user_coupon_id)) = (order.coupon_id, order.user_coupon_id) {
+            // 条件更新保证并发下单时 used_count 不会超过 usage_limit
+            let coupon = sqlx::query!(
+                "UPDATE coupons SET used_count = used_count + 1 \
+                WHERE coupon_id = ? AND is_active = TRUE AND used_count < usage_limit \
+                AND CURDATE() BETWEEN valid_from AND valid_until",
+                coupon_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            let user_coupon = sqlx::query!(
+                "UPDATE user_coupons SET is_used = TRUE, used_at = NOW(), order_id = ? \
+                WHERE user_coupon_id = ? AND user_id = ? AND coupon_id = ? AND is_used = FALSE \
+                AND expires_at >= CURDATE()",
+                &order_id,
+                user_coupon_id,
+                order.customer_id,
+                coupon_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            if coupon.rows_affected() == 0 || user_coupon.rows_affected() == 0 {
+                tx.rollback().await?;
+                return Ok(CreateOrderOutcome::CouponUnavailable);
+            }
+        }
+
+        let row = query_order!("WHERE order_id = ?", &order_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(CreateOrderOutcome::Created(Box::new(row)))
+    }
+
+    async fn update_status(
+        &self,
+        order_id: &str,
+        from: OrderStatus,
+        to: OrderStatus,
+        changed_by: i32,
+        remark: Option<&str>,
+    ) -> Result<Option<Order>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let extra = match to {
+            OrderStatus::Ongoing => ", actual_start_time = NOW()",
+            OrderStatus::Completed => ", actual_end_time = NOW()",
+            OrderStatus::Cancelled => ", cancellation_reason = ?",
+            _ => "",
+        };
+        let sql = format!(
+            "UPDATE orders SET order_status = ?{} WHERE order_id = ? AND order_status = ?",
+            extra
+        );
+        let mut query = sqlx::query(&sql).bind(to.as_str());
+        if to == OrderStatus::Cancelled {
+            query = query.bind(remark);
+        }
+        let result = query
+            .bind(order_id)
+            .bind(from.as_str())
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        // 取消订单时释放已预订的日程，并退还使用的优惠券
+        if to == OrderStatus::Cancelled {
+            sqlx::query!(
+                "UPDATE worker_schedules SET status = 'available', order_id = NULL WHERE order_id = ?",
+                order_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            let returned = sqlx::query!(
+                "UPDATE user_coupons SET is_used = FALSE, used_at = NULL, order_id = NULL WHERE order_id = ?",
+                order_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            if returned.rows_affected() > 0 {
+                sqlx::query!(
+                    "UPDATE coupons c JOIN orders o ON o.coupon_id = c.coupon_id \
+                    SET c.used_count = c.used_count - 1 WHERE o.order_id = ? AND c.used_count > 0",
+                    order_id
+                )
+                .execute(&mut *tx)
+                .await?;
             }
-            Err(e) => Err(e),
         }
+
+        sqlx::query!(
+            "INSERT INTO order_status_history (order_id, from_status, to_status, changed_by, remark) VALUES (?, ?, ?, ?, ?)",
+            order_id,
+            from.as_str(),
+            to.as_str(),
+            changed_by,
+            remark
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let row = query_order!("WHERE order_id = ?", order_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(row))
     }
-    
-    /// 根据ID查找订单
-    pub async fn find_by_id(&self, id: String) -> Result<Order, sqlx::Error> {
-        let order_result = sqlx::query(
-            "SELECT order_id, customer_id, worker_id, address_id, service_id, coupon_id, \
-            service_date, time_slot, duration, unit_price, subtotal, discount_amount, \
-            total_amount, payment_status, order_status, special_instructions, \
-            cancellation_reason, scheduled_start_time, actual_start_time, actual_end_time, \
-            created_at, updated_at \
-            FROM orders WHERE order_id = ?"
+
+    async fn assign_worker(
+        &self,
+        order_id: &str,
+        worker_id: i32,
+        schedule_id: i32,
+        changed_by: i32,
+    ) -> Result<AssignOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let locked = sqlx::query!(
+            "SELECT order_status AS `order_status!: OrderStatus`, service_date, time_slot AS `time_slot: TimeSlot` \
+            FROM orders WHERE order_id = ? FOR UPDATE",
+            order_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let (status, service_date, time_slot) = (locked.order_status, locked.service_date, locked.time_slot);
+        if status != OrderStatus::Confirmed {
+            tx.rollback().await?;
+            return Ok(AssignOutcome::OrderNotAssignable);
+        }
+
+        // 锁定服务人员当日的接单上限，避免并发派单超过上限
+        let max_daily_orders: Option<i32> = sqlx::query_scalar!(
+            "SELECT max_daily_orders AS `max_daily_orders!` FROM worker_profiles WHERE worker_id = ? FOR UPDATE",
+            worker_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let daily_orders: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM orders WHERE worker_id = ? AND service_date = ? \
+            AND order_status IN ('assigned', 'ongoing', 'completed')",
+            worker_id,
+            service_date
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        match max_daily_orders {
+            Some(max) if daily_orders < max as i64 => {}
+            _ => {
+                tx.rollback().await?;
+                return Ok(AssignOutcome::DailyLimitReached);
+            }
+        }
+
+        let schedule_slot: Option<TimeSlot> = sqlx::query_scalar!(
+            "SELECT time_slot AS `time_slot: TimeSlot` FROM worker_schedules \
+            WHERE schedule_id = ? AND worker_id = ? AND schedule_date = ? AND status = 'available' FOR UPDATE",
+            schedule_id,
+            worker_id,
+            service_date
         )
-        .bind(id)
-        .fetch_one(&self.pool)
-        .await;
-
-        match order_result {
-            Ok(row) => {
-                let order = Order {
-                    order_id: row.get("order_id"),
-                    customer_id: row.get("customer_id"),
-                    worker_id: row.get("worker_id"),
-                    address_id: row.get("address_id"),
-                    service_id: row.get("service_id"),
-                    coupon_id: row.get("coupon_id"),
-                    service_date: row.get("service_date"),
-                    time_slot: row.get("time_slot"),
-                    duration: row.get("duration"),
-                    unit_price: row.get("unit_price"),
-                    subtotal: row.get("subtotal"),
-                    discount_amount: row.get("discount_amount"),
-                    total_amount: row.get("total_amount"),
-                    payment_status: row.get("payment_status"),
-                    order_status: row.get("order_status"),
-                    special_instructions: row.get("special_instructions"),
-                    cancellation_reason: row.get("cancellation_reason"),
-                    scheduled_start_time: row.get("scheduled_start_time"),
-                    actual_start_time: row.get("actual_start_time"),
-                    actual_end_time: row.get("actual_end_time"),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                };
-                Ok(order)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let schedule_id = match schedule_slot {
+            Some(slot) if slot == time_slot => schedule_id,
+            Some(slot) if slot.can_serve(time_slot) => {
+                split_full_day(&mut tx, schedule_id, worker_id, service_date, time_slot).await?
             }
-            Err(e) => Err(e),
+            _ => {
+                tx.rollback().await?;
+                return Ok(AssignOutcome::SlotUnavailable);
+            }
+        };
+
+        sqlx::query!(
+            "UPDATE worker_schedules SET status = 'booked', order_id = ? WHERE schedule_id = ?",
+            order_id,
+            schedule_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE orders SET worker_id = ?, order_status = 'assigned' WHERE order_id = ?",
+            worker_id,
+            order_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO order_status_history (order_id, from_status, to_status, changed_by, remark) \
+            VALUES (?, 'confirmed', 'assigned', ?, ?)",
+            order_id,
+            changed_by,
+            format!("指派服务人员 {}", worker_id)
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let row = query_order!("WHERE order_id = ?", order_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(AssignOutcome::Assigned(Box::new(row)))
+    }
+
+    async fn list_status_history(&self, order_id: &str) -> Result<Vec<OrderStatusHistory>, sqlx::Error> {
+        sqlx::query_as!(
+            OrderStatusHistory,
+            "SELECT history_id, order_id, from_status AS `from_status: OrderStatus`, \
+            to_status AS `to_status: OrderStatus`, changed_by, remark, created_at AS `created_at!: NaiveDateTime` \
+            FROM order_status_history WHERE order_id = ? ORDER BY created_at, history_id",
+            order_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+/// 内存中的订单存储，仅用于测试和本地调试
+/// 不维护优惠券、附加服务和服务人员日程，只记录订单本身及其状态历史。
+/// 设置了地址存储时下单会校验服务地址属于该客户，未设置时不校验
+#[derive(Default)]
+pub struct InMemoryOrderStore {
+    tables: RwLock<OrderTables>,
+    addresses: Option<Arc<dyn AddressStore>>,
+}
+
+#[derive(Default)]
+struct OrderTables {
+    orders: Vec<Order>,
+    history: Vec<OrderStatusHistory>,
+}
+
+impl OrderTables {
+    fn record_history(&mut self, order_id: &str, from: Option<OrderStatus>, to: OrderStatus, changed_by: i32, remark: Option<&str>) {
+        self.history.push(OrderStatusHistory {
+            history_id: self.history.len() as i64 + 1,
+            order_id: order_id.to_string(),
+            from_status: from,
+            to_status: to,
+            changed_by,
+            remark: remark.map(str::to_string),
+            created_at: chrono::Utc::now().naive_utc(),
+        });
+    }
+}
+
+impl InMemoryOrderStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用已有订单初始化
+    pub fn with_orders(orders: Vec<Order>) -> Self {
+        Self {
+            tables: RwLock::new(OrderTables { orders, history: Vec::new() }),
+            addresses: None,
         }
     }
-}
\ No newline at end of file
+
+    /// 下单时通过该地址存储校验服务地址
+    pub fn with_address_store(mut self, addresses: Arc<dyn AddressStore>) -> Self {
+        self.addresses = Some(addresses);
+        self
+    }
+
+    /// 订单支付状态为 from 时改为 to，供内存支付存储结算和退款使用
+    /// 订单不存在或支付状态不是 from 时返回 false
+    pub async fn set_payment_status(&self, order_id: &str, from: OrderPaymentStatus, to: OrderPaymentStatus) -> bool {
+        let mut tables = self.tables.write().await;
+        let Some(order) = tables
+            .orders
+            .iter_mut()
+            .find(|o| o.order_id == order_id && o.payment_status == from)
+        else {
+            return false;
+        };
+        order.payment_status = to;
+        order.updated_at = chrono::Local::now().naive_local();
+        true
+    }
+}
+
+#[async_trait]
+impl OrderStore for InMemoryOrderStore {
+    async fn list_orders(&self) -> Result<Vec<Order>, sqlx::Error> {
+        Ok(self.tables.read().await.orders.clone())
+    }
+
+    async fn list_by_customer(&self, customer_id: i32) -> Result<Vec<Order>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        let mut orders: Vec<Order> = tables.orders.iter().filter(|o| o.customer_id == customer_id).cloned().collect();
+        orders.sort_by_key(|o| std::cmp::Reverse(o.created_at));
+        Ok(orders)
+    }
+
+    async fn list_by_worker(&self, worker_id: i32) -> Result<Vec<Order>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        let mut orders: Vec<Order> = tables.orders.iter().filter(|o| o.worker_id == Some(worker_id)).cloned().collect();
+        orders.sort_by_key(|o| std::cmp::Reverse(o.service_date));
+        Ok(orders)
+    }
+
+    async fn find_by_id(&self, id: String) -> Result<Order, sqlx::Error> {
+        let tables = self.tables.read().await;
+        tables.orders.iter().find(|o| o.order_id == id).cloned().ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn create_order(&self, order: NewOrder, _addons: Vec<NewOrderAddon>) -> Result<CreateOrderOutcome, sqlx::Error> {
+        if let Some(addresses) = &self.addresses
+            && addresses.find_by_user(order.customer_id, order.address_id).await?.is_none()
+        {
+            return Ok(CreateOrderOutcome::AddressNotFound);
+        }
+
+        let mut tables = self.tables.write().await;
+        let now = chrono::Local::now().naive_local();
+        let prefix = now.date().format("%Y%m%d").to_string();
+        let seq = tables
+            .orders
+            .iter()
+            .filter_map(|o| o.order_id.strip_prefix(prefix.as_str())?.parse::<u64>().ok())
+            .max()
+            .unwrap_or(0);
+        let order_id = format_order_id(now.date(), seq + 1);
+
+        let created = Order {
+            order_id: order_id.clone(),
+            customer_id: order.customer_id,
+            worker_id: None,
+            address_id: order.address_id,
+            service_id: order.service_id,
+            coupon_id: order.coupon_id,
+            service_date: order.service_date,
+            time_slot: order.time_slot,
+            duration: order.duration,
+            unit_price: order.unit_price,
+            subtotal: order.subtotal,
+            discount_amount: order.discount_amount,
+            total_amount: order.total_amount,
+            payment_status: OrderPaymentStatus::Pending,
+            order_status: OrderStatus::Pending,
+            special_instructions: order.special_instructions,
+            cancellation_reason: None,
+            scheduled_start_time: order.scheduled_start_time,
+            actual_start_time: None,
+            actual_end_time: None,
+            created_at: now,
+            updated_at: now,
+        };
+        tables.orders.push(created.clone());
+        tables.record_history(&order_id, None, OrderStatus::Pending, order.customer_id, None);
+        Ok(CreateOrderOutcome::Created(Box::new(created)))
+    }
+
+    async fn update_status(
+        &self,
+        order_id: &str,
+        from: OrderStatus,
+        to: OrderStatus,
+        changed_by: i32,
+        remark: Option<&str>,
+    ) -> Result<Option<Order>, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        let Some(order) = tables
+            .orders
+            .iter_mut()
+            .find(|o| o.order_id == order_id && o.order_status == from)
+        else {
+            return Ok(None);
+        };
+
+        let now = chrono::Local::now().naive_local();
+        order.order_status = to;
+        order.updated_at = now;
+        match to {
+            OrderStatus::Ongoing => order.actual_start_time = Some(now),
+            OrderStatus::Completed => order.actual_end_time = Some(now),
+            OrderStatus::Cancelled => order.cancellation_reason = remark.map(str::to_string),
+            _ => {}
+        }
+        let updated = order.clone();
+
+        tables.record_history(order_id, Some(from), to, changed_by, remark);
+        Ok(Some(updated))
+    }
+
+    /// 内存实现不维护服务人员日程和接单上限，只校验订单状态
+    async fn assign_worker(
+        &self,
+        order_id: &str,
+        worker_id: i32,
+        _schedule_id: i32,
+        changed_by: i32,
+    ) -> Result<AssignOutcome, sqlx::Error> {
+        let mut tables = self.tables.write().await;
+        let order = tables
+            .orders
+            .iter_mut()
+            .find(|o| o.order_id == order_id)
+            .ok_or(sqlx::Error::RowNotFound)?;
+        if order.order_status != OrderStatus::Confirmed {
+            return Ok(AssignOutcome::OrderNotAssignable);
+        }
+
+        order.worker_id = Some(worker_id);
+        order.order_status = OrderStatus::Assigned;
+        order.updated_at = chrono::Local::now().naive_local();
+        let updated = order.clone();
+
+        let remark = format!("指派服务人员 {}", worker_id);
+        tables.record_history(order_id, Some(OrderStatus::Confirmed), OrderStatus::Assigned, changed_by, Some(&remark));
+        Ok(AssignOutcome::Assigned(Box::new(updated)))
+    }
+
+    async fn list_status_history(&self, order_id: &str) -> Result<Vec<OrderStatusHistory>, sqlx::Error> {
+        let tables = self.tables.read().await;
+        Ok(tables.history.iter().filter(|h| h.order_id == order_id).cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Money, UserAddress};
+    use crate::repositories::InMemoryAddressStore;
+
+    #[test]
+    fn test_format_order_id() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 8).unwrap();
+        assert_eq!(format_order_id(date, 1), "20250308000001");
+        assert_eq!(format_order_id(date, 42), "20250308000042");
+    }
+
+    fn new_order(customer_id: i32) -> NewOrder {
+        NewOrder {
+            customer_id,
+            address_id: 1,
+            service_id: 1,
+            coupon_id: None,
+            user_coupon_id: None,
+            service_date: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+            time_slot: TimeSlot::Morning,
+            duration: 2.0,
+            unit_price: Money::from_yuan(50),
+            subtotal: Money::from_yuan(100),
+            discount_amount: Money::ZERO,
+            total_amount: Money::from_yuan(100),
+            special_instructions: None,
+            scheduled_start_time: None,
+        }
+    }
+
+    async fn create(store: &InMemoryOrderStore, order: NewOrder) -> Order {
+        match store.create_order(order, Vec::new()).await.unwrap() {
+            CreateOrderOutcome::Created(order) => *order,
+            outcome => panic!("下单失败: {:?}", outcome),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_update_status_requires_expected_status() {
+        let store = InMemoryOrderStore::new();
+        let first = create(&store, new_order(1)).await;
+        let second = create(&store, new_order(1)).await;
+        assert_eq!(first.order_status, OrderStatus::Pending);
+        assert!(second.order_id > first.order_id);
+
+        let id = first.order_id.as_str();
+        let cancelled = store
+            .update_status(id, OrderStatus::Pending, OrderStatus::Cancelled, 1, Some("不需要了"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cancelled.cancellation_reason.as_deref(), Some("不需要了"));
+
+        // 状态已被修改时不再更新
+        let stale = store.update_status(id, OrderStatus::Pending, OrderStatus::Confirmed, 2, None).await.unwrap();
+        assert!(stale.is_none());
+
+        let history = store.list_status_history(id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].from_status, Some(OrderStatus::Pending));
+        assert_eq!(history[1].to_status, OrderStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_assign_worker_requires_confirmed() {
+        let store = InMemoryOrderStore::new();
+        let order = create(&store, new_order(1)).await;
+        let id = order.order_id.as_str();
+        assert!(matches!(store.assign_worker(id, 3, 1, 9).await.unwrap(), AssignOutcome::OrderNotAssignable));
+
+        store.update_status(id, OrderStatus::Pending, OrderStatus::Confirmed, 1, None).await.unwrap().unwrap();
+        let AssignOutcome::Assigned(assigned) = store.assign_worker(id, 3, 1, 9).await.unwrap() else {
+            panic!("已确认的订单应指派成功");
+        };
+        assert_eq!((assigned.worker_id, assigned.order_status), (Some(3), OrderStatus::Assigned));
+        assert!(matches!(store.assign_worker("missing", 3, 1, 9).await, Err(sqlx::Error::RowNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_create_order_checks_address_owner() {
+        let now = chrono::Utc::now().naive_utc();
+        let address = UserAddress {
+            address_id: 1,
+            user_id: 1,
+            contact_name: "张三".to_string(),
+            contact_phone: "13800000000".to_string(),
+            province: "广东省".to_string(),
+            city: "深圳市".to_string(),
+            district: "南山区".to_string(),
+            street_address: "科技园1号".to_string(),
+            is_default: true,
+            created_at: now,
+        };
+        let store = InMemoryOrderStore::new()
+            .with_address_store(Arc::new(InMemoryAddressStore::with_addresses(vec![address])));
+
+        assert!(matches!(
+            store.create_order(new_order(2), Vec::new()).await.unwrap(),
+            CreateOrderOutcome::AddressNotFound
+        ));
+        assert_eq!(create(&store, new_order(1)).await.address_id, 1);
+    }
+}
@@ -0,0 +1,723 @@
+//! 支付数据访问层
+//!
+//! 负责 payments 表的读写以及支付结果在订单上的结算。
+//! 支付记录的读写由 `PaymentStore` 抽象，测试中可使用内存实现
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{mysql::MySqlPool, MySqlConnection};
+use tokio::sync::RwLock;
+use crate::models::order::{OrderPaymentStatus, OrderStatus};
+use crate::models::money::Money;
+use crate::models::payment::{NewPayment, Payment, PaymentMethod, PaymentStatus};
+use crate::models::wallet::{LedgerEntry, TransactionReference, TransactionType};
+use crate::repositories::order_repository::{InMemoryOrderStore, OrderStore};
+use crate::repositories::wallet_repository::apply_entry;
+
+/// 查询完整的支付记录行，参数为 `FROM payments` 之后的条件
+macro_rules! query_payment {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            Payment,
+            "SELECT payment_id, order_id, user_id, payment_method AS `payment_method: PaymentMethod`, \
+            payment_amount AS `payment_amount: Money`, payment_status AS `payment_status!: PaymentStatus`, \
+            thirdparty_trade_no, refund_no, payment_time, created_at AS `created_at!: NaiveDateTime` \
+            FROM payments " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 处理中的支付超过该时长 (分钟) 后不再阻止发起新的支付
+const IN_PROGRESS_WINDOW_MINUTES: i64 = 30;
+
+/// 创建支付记录的结果
+#[derive(Debug)]
+pub enum CreatePaymentOutcome {
+    Created(Box<Payment>),
+    /// 订单已取消或已支付
+    OrderNotPayable,
+    /// 订单有尚未完成的支付
+    PaymentInProgress,
+    /// 余额支付时余额不足
+    InsufficientBalance,
+}
+
+/// 锁定订单行并检查订单能否发起新的支付
+/// 返回 None 表示可以支付
+async fn check_payable(conn: &mut MySqlConnection, order_id: &str) -> Result<Option<CreatePaymentOutcome>, sqlx::Error> {
+    let payable = sqlx::query!(
+        "SELECT order_id FROM orders \
+        WHERE order_id = ? AND payment_status = 'pending' AND order_status <> 'cancelled' FOR UPDATE",
+        order_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    if payable.is_none() {
+        return Ok(Some(CreatePaymentOutcome::OrderNotPayable));
+    }
+
+    let in_progress: i64 = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM payments \
+        WHERE order_id = ? AND payment_status IN ('pending', 'processing') \
+        AND created_at > NOW() - INTERVAL ? MINUTE",
+        order_id,
+        IN_PROGRESS_WINDOW_MINUTES
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if in_progress > 0 {
+        return Ok(Some(CreatePaymentOutcome::PaymentInProgress));
+    }
+
+    Ok(None)
+}
+
+/// 写入一条已成功的支付记录，并把订单支付状态置为 paid
+/// 调用方需先通过 check_payable 锁定订单
+async fn insert_settled(conn: &mut MySqlConnection, payment: &NewPayment) -> Result<Payment, sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO payments (payment_id, order_id, user_id, payment_method, payment_amount, payment_status, payment_time) \
+        VALUES (?, ?, ?, ?, ?, 'success', NOW())",
+        &payment.payment_id,
+        &payment.order_id,
+        payment.user_id,
+        payment.payment_method.as_str(),
+        payment.payment_amount
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!("UPDATE orders SET payment_status = 'paid' WHERE order_id = ?", &payment.order_id)
+        .execute(&mut *conn)
+        .await?;
+
+    query_payment!("WHERE payment_id = ?", &payment.payment_id)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// 把订单支付状态由 paid 改为 refunded，同时把支付记录由 success 改为 refunded
+/// 订单已退款时返回 false
+async fn set_refunded(conn: &mut MySqlConnection, payment: &Payment) -> Result<bool, sqlx::Error> {
+    let order = sqlx::query!(
+        "UPDATE orders SET payment_status = 'refunded' WHERE order_id = ? AND payment_status = 'paid'",
+        &payment.order_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    if order.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query!(
+        "UPDATE payments SET payment_status = 'refunded' WHERE payment_id = ? AND payment_status = 'success'",
+        &payment.payment_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(true)
+}
+
+#[async_trait]
+pub trait PaymentStore: Send + Sync {
+    /// 根据ID查找支付记录，不存在时返回 `RowNotFound`
+    async fn find_by_id(&self, payment_id: &str) -> Result<Payment, sqlx::Error>;
+
+    /// 获取订单的支付记录，按创建时间倒序
+    async fn list_by_order(&self, order_id: &str) -> Result<Vec<Payment>, sqlx::Error>;
+
+    /// 获取订单支付成功的记录
+    async fn find_success_by_order(&self, order_id: &str) -> Result<Option<Payment>, sqlx::Error>;
+
+    /// 订单仍可支付且没有进行中的支付时创建待支付记录
+    async fn create_pending(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error>;
+
+    /// 渠道已受理，等待支付结果
+    async fn mark_processing(&self, payment_id: &str, trade_no: &str) -> Result<bool, sqlx::Error>;
+
+    /// 支付失败
+    async fn mark_failed(&self, payment_id: &str, trade_no: Option<&str>) -> Result<bool, sqlx::Error>;
+
+    /// 支付成功，支付记录置为 success 的同时订单置为 paid，任一条件不满足时都不更新并返回 false
+    async fn settle_success(&self, payment_id: &str, order_id: &str, trade_no: &str) -> Result<bool, sqlx::Error>;
+
+    /// 发起渠道退款前把订单和支付记录置为 refunded，订单已退款时返回 false
+    async fn mark_refunded(&self, payment: &Payment) -> Result<bool, sqlx::Error>;
+
+    /// 渠道退款失败，把订单和支付记录恢复为已支付
+    async fn revert_refund(&self, payment: &Payment) -> Result<(), sqlx::Error>;
+
+    /// 渠道退款成功，在已退款的支付记录上记录退款单号
+    async fn record_refund_no(&self, payment_id: &str, refund_no: &str) -> Result<(), sqlx::Error>;
+
+    /// 渠道已扣款但订单无法结算，退款成功后把支付记录置为 refunded 并记录交易号和退款单号，
+    /// 订单支付状态不变；支付已结束时返回 false
+    async fn refund_unsettled(&self, payment_id: &str, trade_no: &str, refund_no: &str) -> Result<bool, sqlx::Error>;
+
+    /// 零元订单不经过支付渠道直接结算
+    async fn settle_zero_amount(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error>;
+
+    /// 余额支付，扣款与订单结算同时完成
+    async fn pay_with_balance(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error>;
+
+    /// 余额支付的订单退款到余额，订单已退款时返回 false
+    async fn refund_to_balance(&self, payment: &Payment) -> Result<bool, sqlx::Error>;
+}
+
+/// 基于 MySQL 的支付存储
+pub struct PaymentRepository {
+    pool: MySqlPool,
+}
+
+impl PaymentRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PaymentStore for PaymentRepository {
+    async fn find_by_id(&self, payment_id: &str) -> Result<Payment, sqlx::Error> {
+        query_payment!("WHERE payment_id = ?", payment_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// 获取订单的支付记录，按创建时间倒序
+    async fn list_by_order(&self, order_id: &str) -> Result<Vec<Payment>, sqlx::Error> {
+        query_payment!("WHERE order_id = ? ORDER BY created_at DESC", order_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// 获取订单支付成功的记录
+    async fn find_success_by_order(&self, order_id: &str) -> Result<Option<Payment>, sqlx::Error> {
+        query_payment!("WHERE order_id = ? AND payment_status = 'success' LIMIT 1", order_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// 创建待支付记录
+    /// 锁定订单行，确认订单仍可支付且没有进行中的支付，避免同一订单被重复扣款
+    async fn create_pending(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(outcome) = check_payable(&mut tx, &payment.order_id).await? {
+            tx.rollback().await?;
+            return Ok(outcome);
+        }
+
+        sqlx::query!(
+            "INSERT INTO payments (payment_id, order_id, user_id, payment_method, payment_amount, payment_status) \
+            VALUES (?, ?, ?, ?, ?, 'pending')",
+            &payment.payment_id,
+            &payment.order_id,
+            payment.user_id,
+            payment.payment_method.as_str(),
+            payment.payment_amount
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let row = query_payment!("WHERE payment_id = ?", &payment.payment_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(CreatePaymentOutcome::Created(Box::new(row)))
+    }
+
+    /// 渠道已受理，等待支付结果
+    async fn mark_processing(&self, payment_id: &str, trade_no: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE payments SET payment_status = 'processing', thirdparty_trade_no = ? \
+            WHERE payment_id = ? AND payment_status = 'pending'",
+            trade_no,
+            payment_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 支付失败
+    async fn mark_failed(&self, payment_id: &str, trade_no: Option<&str>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE payments SET payment_status = 'failed', \
+            thirdparty_trade_no = COALESCE(?, thirdparty_trade_no) \
+            WHERE payment_id = ? AND payment_status IN ('pending', 'processing')",
+            trade_no,
+            payment_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 支付成功结算
+    /// 在同一事务中把支付记录置为 success、订单支付状态置为 paid，
+    /// 任一更新未命中 (支付已结算或订单已支付/取消) 时回滚并返回 false
+    async fn settle_success(&self, payment_id: &str, order_id: &str, trade_no: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let payment = sqlx::query!(
+            "UPDATE payments SET payment_status = 'success', thirdparty_trade_no = ?, payment_time = NOW() \
+            WHERE payment_id = ? AND payment_status IN ('pending', 'processing')",
+            trade_no,
+            payment_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if payment.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        let order = sqlx::query!(
+            "UPDATE orders SET payment_status = 'paid' \
+            WHERE order_id = ? AND payment_status = 'pending' AND order_status <> 'cancelled'",
+            order_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if order.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// 标记渠道支付已退款
+    /// 发起渠道退款前调用，订单和支付记录在同一事务中置为 refunded 防止重复退款，
+    /// 订单已退款时返回 false
+    async fn mark_refunded(&self, payment: &Payment) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if !set_refunded(&mut tx, payment).await? {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// 渠道退款失败，把订单和支付记录恢复为已支付
+    async fn revert_refund(&self, payment: &Payment) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE orders SET payment_status = 'paid' WHERE order_id = ? AND payment_status = 'refunded'",
+            &payment.order_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE payments SET payment_status = 'success' WHERE payment_id = ? AND payment_status = 'refunded'",
+            &payment.payment_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// 渠道退款成功，记录退款单号
+    async fn record_refund_no(&self, payment_id: &str, refund_no: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE payments SET refund_no = ? WHERE payment_id = ? AND payment_status = 'refunded'",
+            refund_no,
+            payment_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// 渠道已扣款但订单无法结算，退款后记录
+    async fn refund_unsettled(&self, payment_id: &str, trade_no: &str, refund_no: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE payments SET payment_status = 'refunded', thirdparty_trade_no = ?, refund_no = ?, \
+            payment_time = COALESCE(payment_time, NOW()) \
+            WHERE payment_id = ? AND payment_status IN ('pending', 'processing')",
+            trade_no,
+            refund_no,
+            payment_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 零元订单直接结算
+    /// 优惠后应付金额为 0 的订单不经过支付渠道，写入成功的支付记录并把订单置为已支付
+    async fn settle_zero_amount(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(outcome) = check_payable(&mut tx, &payment.order_id).await? {
+            tx.rollback().await?;
+            return Ok(outcome);
+        }
+
+        let row = insert_settled(&mut tx, payment).await?;
+        tx.commit().await?;
+        Ok(CreatePaymentOutcome::Created(Box::new(row)))
+    }
+
+    /// 余额支付
+    /// 在同一事务中锁定订单和用户余额，写入成功的支付记录、扣减余额并记流水、
+    /// 把订单支付状态置为 paid，余额不足时整体回滚
+    async fn pay_with_balance(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(outcome) = check_payable(&mut tx, &payment.order_id).await? {
+            tx.rollback().await?;
+            return Ok(outcome);
+        }
+
+        let row = insert_settled(&mut tx, payment).await?;
+
+        let entry = LedgerEntry {
+            user_id: payment.user_id,
+            transaction_type: TransactionType::Payment,
+            amount: -payment.payment_amount,
+            reference: TransactionReference::Payment(payment.payment_id.clone()),
+            operator_id: None,
+            remark: Some(format!("支付订单 {}", payment.order_id)),
+        };
+        if apply_entry(&mut tx, &entry).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(CreatePaymentOutcome::InsufficientBalance);
+        }
+
+        tx.commit().await?;
+        Ok(CreatePaymentOutcome::Created(Box::new(row)))
+    }
+
+    /// 余额支付的订单退款到余额
+    /// 订单和支付记录置为 refunded 与余额入账在同一事务中完成，订单已退款时返回 false
+    async fn refund_to_balance(&self, payment: &Payment) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        if !set_refunded(&mut tx, payment).await? {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        let entry = LedgerEntry {
+            user_id: payment.user_id,
+            transaction_type: TransactionType::Refund,
+            amount: payment.payment_amount,
+            reference: TransactionReference::Order(payment.order_id.clone()),
+            operator_id: None,
+            remark: Some(format!("订单 {} 退款", payment.order_id)),
+        };
+        // 入账不会使余额为负，apply_entry 一定成功
+        apply_entry(&mut tx, &entry).await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+/// 内存中的支付存储，仅用于测试和本地调试
+/// 与内存订单存储共享订单，结算和退款时同步订单的支付状态；
+/// 不维护用户余额，余额支付一律按余额不足处理
+pub struct InMemoryPaymentStore {
+    orders: Arc<InMemoryOrderStore>,
+    payments: RwLock<Vec<Payment>>,
+}
+
+impl InMemoryPaymentStore {
+    pub fn new(orders: Arc<InMemoryOrderStore>) -> Self {
+        Self { orders, payments: RwLock::new(Vec::new()) }
+    }
+
+    /// 订单待支付且未取消，且没有进行中的支付时返回 None
+    async fn check_payable(&self, payments: &[Payment], order_id: &str) -> Option<CreatePaymentOutcome> {
+        let payable = matches!(
+            self.orders.find_by_id(order_id.to_string()).await,
+            Ok(order) if order.payment_status == OrderPaymentStatus::Pending && order.order_status != OrderStatus::Cancelled
+        );
+        if !payable {
+            return Some(CreatePaymentOutcome::OrderNotPayable);
+        }
+
+        let window_start = chrono::Local::now().naive_local() - chrono::Duration::minutes(IN_PROGRESS_WINDOW_MINUTES);
+        let in_progress = payments.iter().any(|p| {
+            p.order_id == order_id
+                && p.payment_status.is_in_progress()
+                && p.created_at > window_start
+        });
+        in_progress.then_some(CreatePaymentOutcome::PaymentInProgress)
+    }
+
+    /// 订单置为已退款，同时把支付记录由 success 改为 refunded
+    async fn set_refunded(&self, payment: &Payment) -> bool {
+        let mut payments = self.payments.write().await;
+        if !self.orders.set_payment_status(&payment.order_id, OrderPaymentStatus::Paid, OrderPaymentStatus::Refunded).await {
+            return false;
+        }
+        if let Some(p) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment.payment_id && p.payment_status == PaymentStatus::Success)
+        {
+            p.payment_status = PaymentStatus::Refunded;
+        }
+        true
+    }
+}
+
+fn new_payment_row(payment: &NewPayment, status: PaymentStatus) -> Payment {
+    let now = chrono::Local::now().naive_local();
+    Payment {
+        payment_id: payment.payment_id.clone(),
+        order_id: payment.order_id.clone(),
+        user_id: payment.user_id,
+        payment_method: payment.payment_method,
+        payment_amount: payment.payment_amount,
+        payment_status: status,
+        thirdparty_trade_no: None,
+        refund_no: None,
+        payment_time: (status == PaymentStatus::Success).then_some(now),
+        created_at: now,
+    }
+}
+
+#[async_trait]
+impl PaymentStore for InMemoryPaymentStore {
+    async fn find_by_id(&self, payment_id: &str) -> Result<Payment, sqlx::Error> {
+        let payments = self.payments.read().await;
+        payments.iter().find(|p| p.payment_id == payment_id).cloned().ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn list_by_order(&self, order_id: &str) -> Result<Vec<Payment>, sqlx::Error> {
+        let payments = self.payments.read().await;
+        Ok(payments.iter().rev().filter(|p| p.order_id == order_id).cloned().collect())
+    }
+
+    async fn find_success_by_order(&self, order_id: &str) -> Result<Option<Payment>, sqlx::Error> {
+        let payments = self.payments.read().await;
+        Ok(payments.iter().find(|p| p.order_id == order_id && p.payment_status == PaymentStatus::Success).cloned())
+    }
+
+    async fn create_pending(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        if let Some(outcome) = self.check_payable(&payments, &payment.order_id).await {
+            return Ok(outcome);
+        }
+
+        let row = new_payment_row(payment, PaymentStatus::Pending);
+        payments.push(row.clone());
+        Ok(CreatePaymentOutcome::Created(Box::new(row)))
+    }
+
+    async fn mark_processing(&self, payment_id: &str, trade_no: &str) -> Result<bool, sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        let Some(payment) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment_id && p.payment_status == PaymentStatus::Pending)
+        else {
+            return Ok(false);
+        };
+        payment.payment_status = PaymentStatus::Processing;
+        payment.thirdparty_trade_no = Some(trade_no.to_string());
+        Ok(true)
+    }
+
+    async fn mark_failed(&self, payment_id: &str, trade_no: Option<&str>) -> Result<bool, sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        let Some(payment) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment_id && p.payment_status.is_in_progress())
+        else {
+            return Ok(false);
+        };
+        payment.payment_status = PaymentStatus::Failed;
+        if let Some(trade_no) = trade_no {
+            payment.thirdparty_trade_no = Some(trade_no.to_string());
+        }
+        Ok(true)
+    }
+
+    async fn settle_success(&self, payment_id: &str, order_id: &str, trade_no: &str) -> Result<bool, sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        let Some(payment) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment_id && p.payment_status.is_in_progress())
+        else {
+            return Ok(false);
+        };
+
+        let active = matches!(
+            self.orders.find_by_id(order_id.to_string()).await,
+            Ok(order) if order.order_status != OrderStatus::Cancelled
+        );
+        if !active || !self.orders.set_payment_status(order_id, OrderPaymentStatus::Pending, OrderPaymentStatus::Paid).await {
+            return Ok(false);
+        }
+
+        payment.payment_status = PaymentStatus::Success;
+        payment.thirdparty_trade_no = Some(trade_no.to_string());
+        payment.payment_time = Some(chrono::Local::now().naive_local());
+        Ok(true)
+    }
+
+    async fn mark_refunded(&self, payment: &Payment) -> Result<bool, sqlx::Error> {
+        Ok(self.set_refunded(payment).await)
+    }
+
+    async fn revert_refund(&self, payment: &Payment) -> Result<(), sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        self.orders.set_payment_status(&payment.order_id, OrderPaymentStatus::Refunded, OrderPaymentStatus::Paid).await;
+        if let Some(p) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment.payment_id && p.payment_status == PaymentStatus::Refunded)
+        {
+            p.payment_status = PaymentStatus::Success;
+        }
+        Ok(())
+    }
+
+    async fn record_refund_no(&self, payment_id: &str, refund_no: &str) -> Result<(), sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        if let Some(p) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment_id && p.payment_status == PaymentStatus::Refunded)
+        {
+            p.refund_no = Some(refund_no.to_string());
+        }
+        Ok(())
+    }
+
+    async fn refund_unsettled(&self, payment_id: &str, trade_no: &str, refund_no: &str) -> Result<bool, sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        let Some(payment) = payments
+            .iter_mut()
+            .find(|p| p.payment_id == payment_id && p.payment_status.is_in_progress())
+        else {
+            return Ok(false);
+        };
+        payment.payment_status = PaymentStatus::Refunded;
+        payment.thirdparty_trade_no = Some(trade_no.to_string());
+        payment.refund_no = Some(refund_no.to_string());
+        payment.payment_time.get_or_insert_with(|| chrono::Local::now().naive_local());
+        Ok(true)
+    }
+
+    async fn settle_zero_amount(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error> {
+        let mut payments = self.payments.write().await;
+        if let Some(outcome) = self.check_payable(&payments, &payment.order_id).await {
+            return Ok(outcome);
+        }
+
+        self.orders.set_payment_status(&payment.order_id, OrderPaymentStatus::Pending, OrderPaymentStatus::Paid).await;
+        let row = new_payment_row(payment, PaymentStatus::Success);
+        payments.push(row.clone());
+        Ok(CreatePaymentOutcome::Created(Box::new(row)))
+    }
+
+    async fn pay_with_balance(&self, payment: &NewPayment) -> Result<CreatePaymentOutcome, sqlx::Error> {
+        let payments = self.payments.read().await;
+        if let Some(outcome) = self.check_payable(&payments, &payment.order_id).await {
+            return Ok(outcome);
+        }
+        Ok(CreatePaymentOutcome::InsufficientBalance)
+    }
+
+    async fn refund_to_balance(&self, payment: &Payment) -> Result<bool, sqlx::Error> {
+        Ok(self.set_refunded(payment).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::models::order::Order;
+    use crate::models::payment::PaymentMethod;
+    use crate::models::worker::TimeSlot;
+    use crate::models::Money;
+
+    fn order(order_id: &str, order_status: OrderStatus) -> Order {
+        let now = chrono::Local::now().naive_local();
+        Order {
+            order_id: order_id.to_string(),
+            customer_id: 1,
+            worker_id: None,
+            address_id: 1,
+            service_id: 1,
+            coupon_id: None,
+            service_date: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+            time_slot: TimeSlot::Morning,
+            duration: 2.0,
+            unit_price: Money::from_yuan(50),
+            subtotal: Money::from_yuan(100),
+            discount_amount: Money::ZERO,
+            total_amount: Money::from_yuan(100),
+            payment_status: OrderPaymentStatus::Pending,
+            order_status,
+            special_instructions: None,
+            cancellation_reason: None,
+            scheduled_start_time: None,
+            actual_start_time: None,
+            actual_end_time: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn new_payment(payment_id: &str, order_id: &str) -> NewPayment {
+        NewPayment {
+            payment_id: payment_id.to_string(),
+            order_id: order_id.to_string(),
+            user_id: 1,
+            payment_method: PaymentMethod::Wechat,
+            payment_amount: Money::from_yuan(100),
+        }
+    }
+
+    fn store(orders: Vec<Order>) -> (Arc<InMemoryOrderStore>, InMemoryPaymentStore) {
+        let orders = Arc::new(InMemoryOrderStore::with_orders(orders));
+        (orders.clone(), InMemoryPaymentStore::new(orders))
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_settle_and_refund_update_order() {
+        let (orders, store) = store(vec![order("O1", OrderStatus::Pending)]);
+        assert!(matches!(store.create_pending(&new_payment("P1", "O1")).await.unwrap(), CreatePaymentOutcome::Created(_)));
+        assert!(matches!(
+            store.create_pending(&new_payment("P2", "O1")).await.unwrap(),
+            CreatePaymentOutcome::PaymentInProgress
+        ));
+
+        assert!(store.settle_success("P1", "O1", "T1").await.unwrap());
+        assert_eq!(orders.find_by_id("O1".to_string()).await.unwrap().payment_status, OrderPaymentStatus::Paid);
+        assert!(!store.settle_success("P1", "O1", "T1").await.unwrap());
+
+        let payment = store.find_success_by_order("O1").await.unwrap().unwrap();
+        assert!(store.mark_refunded(&payment).await.unwrap());
+        assert!(!store.mark_refunded(&payment).await.unwrap());
+        assert_eq!(store.find_by_id("P1").await.unwrap().payment_status, PaymentStatus::Refunded);
+
+        store.revert_refund(&payment).await.unwrap();
+        assert_eq!(orders.find_by_id("O1".to_string()).await.unwrap().payment_status, OrderPaymentStatus::Paid);
+        assert_eq!(store.find_by_id("P1").await.unwrap().payment_status, PaymentStatus::Success);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_cancelled_order_not_payable() {
+        let (_, store) = store(vec![order("O1", OrderStatus::Cancelled)]);
+        assert!(matches!(
+            store.create_pending(&new_payment("P1", "O1")).await.unwrap(),
+            CreatePaymentOutcome::OrderNotPayable
+        ));
+        assert!(matches!(
+            store.create_pending(&new_payment("P1", "O2")).await.unwrap(),
+            CreatePaymentOutcome::OrderNotPayable
+        ));
+    }
+}
@@ -0,0 +1,204 @@
+//! 刷新令牌数据访问层
+//!
+//! `RefreshTokenStore` 抽象了刷新令牌的持久化，生产环境使用 MySQL 实现，
+//! 测试中使用内存实现
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{mysql::MySqlPool, MySqlConnection};
+use tokio::sync::RwLock;
+
+use crate::models::refresh_token::{NewRefreshToken, RefreshToken};
+
+#[async_trait]
+pub trait RefreshTokenStore: Send + Sync {
+    /// 保存新签发的令牌
+    async fn insert(&self, token: NewRefreshToken) -> Result<RefreshToken, sqlx::Error>;
+
+    /// 根据令牌哈希查找
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, sqlx::Error>;
+
+    /// 轮换令牌: 在同一事务中撤销旧令牌并保存新令牌
+    /// 只有旧令牌此前处于有效状态时才保存新令牌，否则返回 None，
+    /// 用于在并发刷新时保证只有一个请求轮换成功
+    async fn rotate(&self, token_id: i64, replacement: NewRefreshToken) -> Result<Option<RefreshToken>, sqlx::Error>;
+
+    /// 撤销整个令牌族，返回本次撤销的数量
+    async fn revoke_family(&self, family_id: &str) -> Result<u64, sqlx::Error>;
+}
+
+/// 查询完整的令牌行，参数为 `FROM refresh_tokens` 之后的条件
+macro_rules! query_refresh_token {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            RefreshToken,
+            "SELECT token_id, token_hash, user_id, family_id, parent_id, device_info, expires_at, \
+            revoked AS `revoked!: bool`, created_at AS `created_at!: NaiveDateTime` FROM refresh_tokens " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 基于 MySQL 的刷新令牌存储
+pub struct RefreshTokenRepository {
+    pool: MySqlPool,
+}
+
+impl RefreshTokenRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+async fn insert_token(conn: &mut MySqlConnection, token: &NewRefreshToken) -> Result<RefreshToken, sqlx::Error> {
+    let result = sqlx::query!(
+        "INSERT INTO refresh_tokens (token_hash, user_id, family_id, parent_id, device_info, expires_at, revoked) \
+            VALUES (?, ?, ?, ?, ?, ?, FALSE)",
+        &token.token_hash,
+        token.user_id,
+        &token.family_id,
+        token.parent_id,
+        &token.device_info,
+        token.expires_at
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    query_refresh_token!("WHERE token_id = ?", result.last_insert_id() as i64)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+#[async_trait]
+impl RefreshTokenStore for RefreshTokenRepository {
+    async fn insert(&self, token: NewRefreshToken) -> Result<RefreshToken, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        insert_token(&mut conn, &token).await
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, sqlx::Error> {
+        query_refresh_token!("WHERE token_hash = ?", token_hash)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn rotate(&self, token_id: i64, replacement: NewRefreshToken) -> Result<Option<RefreshToken>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked = TRUE WHERE token_id = ? AND revoked = FALSE",
+            token_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() != 1 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let created = insert_token(&mut tx, &replacement).await?;
+        tx.commit().await?;
+        Ok(Some(created))
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked = TRUE WHERE family_id = ? AND revoked = FALSE",
+            family_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// 内存中的刷新令牌存储，仅用于测试和本地调试
+#[derive(Default)]
+pub struct InMemoryRefreshTokenStore {
+    tokens: RwLock<Vec<RefreshToken>>,
+}
+
+impl InMemoryRefreshTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn push_token(tokens: &mut Vec<RefreshToken>, token: NewRefreshToken) -> RefreshToken {
+    let record = RefreshToken {
+        token_id: tokens.len() as i64 + 1,
+        token_hash: token.token_hash,
+        user_id: token.user_id,
+        family_id: token.family_id,
+        parent_id: token.parent_id,
+        device_info: token.device_info,
+        expires_at: token.expires_at,
+        revoked: false,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+    tokens.push(record.clone());
+    record
+}
+
+#[async_trait]
+impl RefreshTokenStore for InMemoryRefreshTokenStore {
+    async fn insert(&self, token: NewRefreshToken) -> Result<RefreshToken, sqlx::Error> {
+        let mut tokens = self.tokens.write().await;
+        Ok(push_token(&mut tokens, token))
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, sqlx::Error> {
+        let tokens = self.tokens.read().await;
+        Ok(tokens.iter().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn rotate(&self, token_id: i64, replacement: NewRefreshToken) -> Result<Option<RefreshToken>, sqlx::Error> {
+        let mut tokens = self.tokens.write().await;
+        match tokens.iter_mut().find(|t| t.token_id == token_id && !t.revoked) {
+            Some(token) => token.revoked = true,
+            None => return Ok(None),
+        }
+        Ok(Some(push_token(&mut tokens, replacement)))
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tokens = self.tokens.write().await;
+        let mut count = 0;
+        for token in tokens.iter_mut().filter(|t| t.family_id == family_id && !t.revoked) {
+            token.revoked = true;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_token(token_hash: &str, parent_id: Option<i64>) -> NewRefreshToken {
+        NewRefreshToken {
+            token_hash: token_hash.to_string(),
+            user_id: 1,
+            family_id: "family".to_string(),
+            parent_id,
+            device_info: None,
+            expires_at: chrono::Utc::now().naive_utc() + chrono::Duration::days(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_rotate_requires_active_token() {
+        let store = InMemoryRefreshTokenStore::new();
+        let first = store.insert(new_token("first", None)).await.unwrap();
+
+        let second = store.rotate(first.token_id, new_token("second", Some(first.token_id))).await.unwrap().unwrap();
+        assert_eq!(second.parent_id, Some(first.token_id));
+        assert!(store.find_by_hash("first").await.unwrap().unwrap().revoked);
+
+        // 已撤销的令牌不能再次轮换，也不会保存新令牌
+        let replayed = store.rotate(first.token_id, new_token("third", Some(first.token_id))).await.unwrap();
+        assert!(replayed.is_none());
+        assert!(store.find_by_hash("third").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! 评价数据访问层
+//!
+//! 负责 reviews 表的读写。写入评价时在同一事务中重新计算服务人员的平均评分
+
+use chrono::NaiveDateTime;
+use sqlx::mysql::MySqlPool;
+use crate::models::pagination::PageQuery;
+use crate::models::review::{CreateReviewRequest, Review};
+
+/// 查询完整的评价行，参数为 `FROM reviews` 之后的条件
+macro_rules! query_review {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            Review,
+            "SELECT review_id, order_id, customer_id, worker_id, rating, service_rating, punctuality_rating, \
+            review_text, is_anonymous AS `is_anonymous!: bool`, created_at AS `created_at!: NaiveDateTime` \
+            FROM reviews " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+pub struct ReviewRepository {
+    pool: MySqlPool,
+}
+
+impl ReviewRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 写入评价并重新计算服务人员平均评分
+    /// 锁定订单行使同一订单的评价请求串行执行，订单已评价时返回 None
+    pub async fn create(
+        &self,
+        order_id: &str,
+        customer_id: i32,
+        worker_id: i32,
+        review: &CreateReviewRequest,
+    ) -> Result<Option<Review>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("SELECT order_id FROM orders WHERE order_id = ? FOR UPDATE", order_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let reviewed: i64 = sqlx::query_scalar!("SELECT COUNT(*) FROM reviews WHERE order_id = ?", order_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if reviewed > 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let result = sqlx::query!(
+            "INSERT INTO reviews (order_id, customer_id, worker_id, rating, service_rating, \
+            punctuality_rating, review_text, is_anonymous) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            order_id,
+            customer_id,
+            worker_id,
+            review.rating,
+            review.service_rating,
+            review.punctuality_rating,
+            review.review_text.as_deref(),
+            review.is_anonymous
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE worker_profiles SET avg_rating = \
+            (SELECT COALESCE(ROUND(AVG(rating), 2), 0) FROM reviews WHERE worker_id = ?) \
+            WHERE worker_id = ?",
+            worker_id,
+            worker_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let row = query_review!("WHERE review_id = ?", result.last_insert_id())
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Some(row))
+    }
+
+    /// 分页获取服务人员收到的评价及评价人用户名，按时间倒序，返回 (评价, 总条数)
+    pub async fn list_by_worker(
+        &self,
+        worker_id: i32,
+        page: &PageQuery,
+    ) -> Result<(Vec<(Review, String)>, i64), sqlx::Error> {
+        let total: i64 = sqlx::query_scalar!("SELECT COUNT(*) FROM reviews WHERE worker_id = ?", worker_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let reviews = sqlx::query!(
+            "SELECT r.review_id, r.order_id, r.customer_id, r.worker_id, r.rating, r.service_rating, \
+            r.punctuality_rating, r.review_text, r.is_anonymous AS `is_anonymous!: bool`, \
+            r.created_at AS `created_at!: NaiveDateTime`, u.username \
+            FROM reviews r JOIN users u ON u.user_id = r.customer_id \
+            WHERE r.worker_id = ? ORDER BY r.review_id DESC LIMIT ? OFFSET ?",
+            worker_id,
+            page.page_size(),
+            page.offset()
+        )
+        .map(|row| {
+            let review = Review {
+                review_id: row.review_id,
+                order_id: row.order_id,
+                customer_id: row.customer_id,
+                worker_id: row.worker_id,
+                rating: row.rating,
+                service_rating: row.service_rating,
+                punctuality_rating: row.punctuality_rating,
+                review_text: row.review_text,
+                is_anonymous: row.is_anonymous,
+                created_at: row.created_at,
+            };
+            (review, row.username)
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((reviews, total))
+    }
+}
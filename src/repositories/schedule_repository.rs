@@ -0,0 +1,134 @@
+//! 服务人员日程数据访问层
+//!
+//! 负责 worker_schedules 表的读写。
+//! 同一服务人员同一天的日程时间段互不重叠，写入时锁定当天的全部日程行，
+//! 与派单时的日程预订互斥
+
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::mysql::MySqlPool;
+
+use crate::models::worker::{ScheduleConflict, ScheduleStatus, ScheduleUpdateResult, TimeSlot, WorkerSchedule};
+
+pub struct ScheduleRepository {
+    pool: MySqlPool,
+}
+
+impl ScheduleRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取服务人员在日期范围内的日程
+    pub async fn list(&self, worker_id: i32, start: NaiveDate, end: NaiveDate) -> Result<Vec<WorkerSchedule>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkerSchedule,
+            "SELECT schedule_id, worker_id, schedule_date, time_slot AS `time_slot: TimeSlot`, \
+            status AS `status!: ScheduleStatus`, order_id, created_at AS `created_at!: NaiveDateTime` \
+            FROM worker_schedules WHERE worker_id = ? AND schedule_date BETWEEN ? AND ? \
+            ORDER BY schedule_date, FIELD(time_slot, 'morning', 'afternoon', 'evening', 'full_day')",
+            worker_id,
+            start,
+            end
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// 批量设置日程状态 (可用 / 不可用)
+    /// 与目标时间段重叠的未预订日程会被替换；重叠的日程已被预订时跳过并记录冲突
+    pub async fn set_slots(
+        &self,
+        worker_id: i32,
+        entries: &[(NaiveDate, TimeSlot)],
+        status: ScheduleStatus,
+    ) -> Result<ScheduleUpdateResult, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut result = ScheduleUpdateResult::default();
+
+        for &(date, slot) in entries {
+            // 锁定当天全部日程行 (含间隙)，避免与派单或其他设置请求并发写入
+            let rows = sqlx::query!(
+                "SELECT schedule_id, time_slot AS `time_slot: TimeSlot`, status AS `status!: ScheduleStatus` \
+                FROM worker_schedules WHERE worker_id = ? AND schedule_date = ? FOR UPDATE",
+                worker_id,
+                date
+            )
+            .map(|row| (row.schedule_id, row.time_slot, row.status))
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let mut overlapping = Vec::new();
+            let mut booked = false;
+            for (schedule_id, existing, existing_status) in rows {
+                if !existing.overlaps(slot) {
+                    continue;
+                }
+                if existing_status == ScheduleStatus::Booked {
+                    booked = true;
+                    break;
+                }
+                overlapping.push(schedule_id);
+            }
+
+            if booked {
+                result.conflicts.push(ScheduleConflict {
+                    schedule_date: date,
+                    time_slot: slot,
+                    reason: "该时间段已有预订".to_string(),
+                });
+                continue;
+            }
+
+            for schedule_id in overlapping {
+                sqlx::query!("DELETE FROM worker_schedules WHERE schedule_id = ?", schedule_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            sqlx::query!(
+                "INSERT INTO worker_schedules (worker_id, schedule_date, time_slot, status) VALUES (?, ?, ?, ?)",
+                worker_id,
+                date,
+                slot,
+                status.as_str()
+            )
+            .execute(&mut *tx)
+            .await?;
+            result.updated += 1;
+        }
+
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    /// 统计某服务在某天各时间段有空闲日程的服务人员数
+    /// 返回 (时间段, 人数)，条件与派单候选人一致 (见 `WorkerRepository::find_dispatch_candidates`):
+    /// 服务分类匹配、可接单、账号正常、当日订单数未达到上限；
+    /// 指定 district 时还要求服务区域包含该区县
+    pub async fn count_available_workers(
+        &self,
+        service_id: i32,
+        date: NaiveDate,
+        district: Option<&str>,
+    ) -> Result<Vec<(TimeSlot, i64)>, sqlx::Error> {
+        sqlx::query!(
+            "SELECT ws.time_slot AS `time_slot: TimeSlot`, COUNT(DISTINCT ws.worker_id) AS workers \
+            FROM worker_schedules ws \
+            JOIN worker_profiles wp ON wp.worker_id = ws.worker_id AND wp.is_available = TRUE \
+                AND (? IS NULL OR JSON_CONTAINS(wp.service_area, JSON_QUOTE(?))) \
+            JOIN users u ON u.user_id = wp.worker_id AND u.user_type = 'worker' AND u.status = 'active' \
+            JOIN services s ON s.category_id = wp.service_category_id \
+            WHERE s.service_id = ? AND ws.schedule_date = ? AND ws.status = 'available' \
+            AND (SELECT COUNT(*) FROM orders d WHERE d.worker_id = wp.worker_id AND d.service_date = ws.schedule_date \
+                AND d.order_status IN ('assigned', 'ongoing', 'completed')) < wp.max_daily_orders \
+            GROUP BY ws.time_slot",
+            district,
+            district,
+            service_id,
+            date
+        )
+        .map(|row| (row.time_slot, row.workers))
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -1,79 +1,620 @@
 //! 服务数据访问层
 //!
-//! 负责服务相关的数据库操作
+//! 负责服务分类、服务和附加项相关的数据库操作。
+//! 下单计价需要的查询由 `ServiceStore` 抽象，测试中可使用内存实现
 
-use sqlx::{mysql::MySqlPool, Row};
-use crate::models::service::Service;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{mysql::{MySql, MySqlPool}, MySqlConnection, QueryBuilder};
+use crate::models::audit::{AuditAction, AuditEntity, NewAuditLog};
+use crate::models::pagination::PageQuery;
+use crate::models::service::{
+    creates_cycle, AddonRequest, CategoryRequest, Service, ServiceAddon, ServiceCategory, ServiceFilter,
+    ServiceRequest, ServiceSort, ServiceUnit,
+};
+use crate::models::money::Money;
+use crate::repositories::audit_repository::record_audit;
+use tokio::sync::RwLock;
+
+/// 服务搜索的筛选条件是动态拼接的，这部分查询仍在运行时映射
+const SERVICE_COLUMNS: &str = "service_id, category_id, service_name, description, base_price, unit, \
+    min_duration, max_duration, is_active, created_at";
+
+/// 查询完整的分类行，参数为 `FROM service_categories` 之后的条件
+macro_rules! query_category {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            ServiceCategory,
+            "SELECT category_id, category_name, parent_id, icon_url, is_active AS `is_active!: bool`, \
+            sort_order AS `sort_order!`, created_at AS `created_at!: NaiveDateTime` \
+            FROM service_categories " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 查询完整的服务行，参数为 `FROM services` 之后的条件，列与 `SERVICE_COLUMNS` 一致
+macro_rules! query_service {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            Service,
+            "SELECT service_id, category_id, service_name, description, base_price AS `base_price: Money`, \
+            unit AS `unit: ServiceUnit`, min_duration AS `min_duration!`, max_duration AS `max_duration!`, \
+            is_active AS `is_active!: bool`, created_at AS `created_at!: NaiveDateTime` FROM services " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 查询完整的附加项行，参数为 `FROM service_addons` 之后的条件
+macro_rules! query_addon {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            ServiceAddon,
+            "SELECT addon_id, service_id, addon_name, addon_price AS `addon_price: Money`, \
+            is_active AS `is_active!: bool`, created_at AS `created_at!: NaiveDateTime` FROM service_addons " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 管理员写目录数据的结果
+#[derive(Debug)]
+pub enum CatalogWriteOutcome<T> {
+    Saved(T),
+    NotFound,
+    /// 父分类或服务所属分类不存在
+    ParentNotFound,
+    /// 修改父分类会使分类树形成环
+    Cycle,
+}
+
+/// 管理员停用目录数据的结果
+#[derive(Debug, PartialEq, Eq)]
+pub enum CatalogDeleteOutcome {
+    Deleted,
+    NotFound,
+    /// 分类下仍有启用的子分类或服务
+    HasChildren,
+    /// 仍被未结束的订单引用
+    InUse,
+}
+
+async fn fetch_category(conn: &mut MySqlConnection, category_id: i32) -> Result<Option<ServiceCategory>, sqlx::Error> {
+    query_category!("WHERE category_id = ?", category_id)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+async fn fetch_service(conn: &mut MySqlConnection, service_id: i32) -> Result<Option<Service>, sqlx::Error> {
+    query_service!("WHERE service_id = ? FOR UPDATE", service_id)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+async fn fetch_addon(conn: &mut MySqlConnection, service_id: i32, addon_id: i32) -> Result<Option<ServiceAddon>, sqlx::Error> {
+    query_addon!("WHERE addon_id = ? AND service_id = ? FOR UPDATE", addon_id, service_id)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+/// 锁住全部分类，返回 分类ID -> 父分类ID 映射
+/// 修改分类层级的事务互相串行，避免并发修改拼出环
+async fn lock_category_parents(conn: &mut MySqlConnection) -> Result<HashMap<i32, Option<i32>>, sqlx::Error> {
+    let rows = sqlx::query!("SELECT category_id, parent_id FROM service_categories FOR UPDATE")
+        .map(|row| (row.category_id, row.parent_id))
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// 追加服务筛选条件
+fn push_filters<'a>(builder: &mut QueryBuilder<'a, MySql>, filter: &'a ServiceFilter) {
+    builder.push(" WHERE 1 = 1");
+    if let Some(category_ids) = &filter.category_ids {
+        if category_ids.is_empty() {
+            builder.push(" AND FALSE");
+        } else {
+            builder.push(" AND category_id IN (");
+            let mut separated = builder.separated(", ");
+            for category_id in category_ids {
+                separated.push_bind(*category_id);
+            }
+            separated.push_unseparated(")");
+        }
+    }
+    if let Some(pattern) = &filter.keyword_pattern {
+        builder
+            .push(" AND (service_name LIKE ")
+            .push_bind(pattern)
+            .push(" OR description LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+    if let Some(min_price) = filter.min_price {
+        builder.push(" AND base_price >= CAST(").push_bind(min_price).push(" AS DECIMAL(8, 2))");
+    }
+    if let Some(max_price) = filter.max_price {
+        builder.push(" AND base_price <= CAST(").push_bind(max_price).push(" AS DECIMAL(8, 2))");
+    }
+    if let Some(unit) = filter.unit {
+        builder.push(" AND unit = ").push_bind(unit.as_str());
+    }
+    if filter.active_only {
+        builder.push(" AND is_active = TRUE");
+    }
+}
+
+fn order_by(sort: ServiceSort) -> &'static str {
+    match sort {
+        ServiceSort::Default => " ORDER BY service_id",
+        ServiceSort::PriceAsc => " ORDER BY base_price, service_id",
+        ServiceSort::PriceDesc => " ORDER BY base_price DESC, service_id",
+        ServiceSort::Newest => " ORDER BY created_at DESC, service_id DESC",
+    }
+}
+
+#[async_trait]
+pub trait ServiceStore: Send + Sync {
+    /// 根据ID查找服务，不存在时返回 `RowNotFound`
+    async fn find_by_id(&self, id: i32) -> Result<Service, sqlx::Error>;
+
+    /// 获取服务下所有启用的附加项
+    async fn find_active_addons(&self, service_id: i32) -> Result<Vec<ServiceAddon>, sqlx::Error>;
+}
 
 pub struct ServiceRepository {
     pool: MySqlPool,
 }
 
+#[async_trait]
+impl ServiceStore for ServiceRepository {
+    async fn find_by_id(&self, id: i32) -> Result<Service, sqlx::Error> {
+        query_service!("WHERE service_id = ?", id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn find_active_addons(&self, service_id: i32) -> Result<Vec<ServiceAddon>, sqlx::Error> {
+        let rows = query_addon!("WHERE service_id = ? AND is_active = TRUE", service_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+}
+
 impl ServiceRepository {
     pub fn new(pool: MySqlPool) -> Self {
         Self { pool }
     }
-    
-    /// 获取所有服务列表
-    pub async fn list_services(&self) -> Result<Vec<Service>, sqlx::Error> {
-        let services_result = sqlx::query(
-            "SELECT service_id, category_id, service_name, description, base_price, unit, \
-            min_duration, max_duration, is_active, created_at \
-            FROM services"
+
+    /// 获取服务分类，按 sort_order 排列
+    pub async fn list_categories(&self, active_only: bool) -> Result<Vec<ServiceCategory>, sqlx::Error> {
+        let rows = query_category!(
+            "WHERE (? = FALSE OR is_active = TRUE) ORDER BY sort_order, category_id",
+            active_only
         )
         .fetch_all(&self.pool)
-        .await;
-
-        match services_result {
-            Ok(rows) => {
-                let services: Vec<Service> = rows.into_iter().map(|row| {
-                    Service {
-                        service_id: row.get("service_id"),
-                        category_id: row.get("category_id"),
-                        service_name: row.get("service_name"),
-                        description: row.get("description"),
-                        base_price: row.get("base_price"),
-                        unit: row.get("unit"),
-                        min_duration: row.get("min_duration"),
-                        max_duration: row.get("max_duration"),
-                        is_active: row.get("is_active"),
-                        created_at: row.get("created_at"),
-                    }
-                }).collect();
-                Ok(services)
-            }
-            Err(e) => Err(e),
-        }
-    }
-    
-    /// 根据ID查找服务
-    pub async fn find_by_id(&self, id: i32) -> Result<Service, sqlx::Error> {
-        let service_result = sqlx::query(
-            "SELECT service_id, category_id, service_name, description, base_price, unit, \
-            min_duration, max_duration, is_active, created_at \
-            FROM services WHERE service_id = ?"
-        )
-        .bind(id)
-        .fetch_one(&self.pool)
-        .await;
-
-        match service_result {
-            Ok(row) => {
-                let service = Service {
-                    service_id: row.get("service_id"),
-                    category_id: row.get("category_id"),
-                    service_name: row.get("service_name"),
-                    description: row.get("description"),
-                    base_price: row.get("base_price"),
-                    unit: row.get("unit"),
-                    min_duration: row.get("min_duration"),
-                    max_duration: row.get("max_duration"),
-                    is_active: row.get("is_active"),
-                    created_at: row.get("created_at"),
-                };
-                Ok(service)
-            }
-            Err(e) => Err(e),
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 按条件分页查询服务，返回 (服务, 总条数)
+    pub async fn search_services(
+        &self,
+        filter: &ServiceFilter,
+        page: &PageQuery,
+    ) -> Result<(Vec<Service>, i64), sqlx::Error> {
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM services");
+        push_filters(&mut count, filter);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut select = QueryBuilder::new(format!("SELECT {} FROM services", SERVICE_COLUMNS));
+        push_filters(&mut select, filter);
+        select
+            .push(order_by(filter.sort))
+            .push(" LIMIT ")
+            .push_bind(page.page_size())
+            .push(" OFFSET ")
+            .push_bind(page.offset());
+        let rows = select.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok((rows, total))
+    }
+
+    /// 新增分类并记录审计日志
+    pub async fn create_category(
+        &self,
+        category: &CategoryRequest,
+        operator_id: i32,
+    ) -> Result<CatalogWriteOutcome<ServiceCategory>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let parents = lock_category_parents(&mut tx).await?;
+        if let Some(parent_id) = category.parent_id
+            && !parents.contains_key(&parent_id)
+        {
+            tx.rollback().await?;
+            return Ok(CatalogWriteOutcome::ParentNotFound);
         }
+
+        let result = sqlx::query!(
+            "INSERT INTO service_categories (category_name, parent_id, icon_url, is_active, sort_order) \
+            VALUES (?, ?, ?, ?, ?)",
+            &category.category_name,
+            category.parent_id,
+            &category.icon_url,
+            category.is_active.unwrap_or(true),
+            category.sort_order
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let category_id = result.last_insert_id() as i32;
+        let created = fetch_category(&mut tx, category_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Category, category_id, AuditAction::Create, &created),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogWriteOutcome::Saved(created))
     }
-}
\ No newline at end of file
+
+    /// 修改分类并记录审计日志，不允许把分类移到自己的子树下
+    pub async fn update_category(
+        &self,
+        category_id: i32,
+        category: &CategoryRequest,
+        operator_id: i32,
+    ) -> Result<CatalogWriteOutcome<ServiceCategory>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let parents = lock_category_parents(&mut tx).await?;
+        let outcome = if !parents.contains_key(&category_id) {
+            Some(CatalogWriteOutcome::NotFound)
+        } else if category.parent_id.is_some_and(|parent_id| !parents.contains_key(&parent_id)) {
+            Some(CatalogWriteOutcome::ParentNotFound)
+        } else if creates_cycle(&parents, category_id, category.parent_id) {
+            Some(CatalogWriteOutcome::Cycle)
+        } else {
+            None
+        };
+        if let Some(outcome) = outcome {
+            tx.rollback().await?;
+            return Ok(outcome);
+        }
+
+        sqlx::query!(
+            "UPDATE service_categories SET category_name = ?, parent_id = ?, icon_url = ?, sort_order = ?, \
+            is_active = COALESCE(?, is_active) WHERE category_id = ?",
+            &category.category_name,
+            category.parent_id,
+            &category.icon_url,
+            category.sort_order,
+            category.is_active,
+            category_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let updated = fetch_category(&mut tx, category_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Category, category_id, AuditAction::Update, &updated),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogWriteOutcome::Saved(updated))
+    }
+
+    /// 停用分类并记录审计日志
+    /// 分类下仍有启用的子分类、服务或未结束的订单时拒绝
+    pub async fn delete_category(&self, category_id: i32, operator_id: i32) -> Result<CatalogDeleteOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let parents = lock_category_parents(&mut tx).await?;
+        if !parents.contains_key(&category_id) {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::NotFound);
+        }
+
+        let active_children: i64 = sqlx::query_scalar!(
+            "SELECT (SELECT COUNT(*) FROM service_categories WHERE parent_id = ? AND is_active = TRUE) \
+            + (SELECT COUNT(*) FROM services WHERE category_id = ? AND is_active = TRUE) AS `active_children!`",
+            category_id,
+            category_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if active_children > 0 {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::HasChildren);
+        }
+
+        // 被未结束的订单引用时不能停用
+        let active_orders: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM orders o JOIN services s ON s.service_id = o.service_id \
+            WHERE s.category_id = ? AND o.order_status IN ('pending', 'confirmed', 'assigned', 'ongoing')",
+            category_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if active_orders > 0 {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::InUse);
+        }
+
+        sqlx::query!("UPDATE service_categories SET is_active = FALSE WHERE category_id = ?", category_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let deleted = fetch_category(&mut tx, category_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Category, category_id, AuditAction::Delete, &deleted),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogDeleteOutcome::Deleted)
+    }
+
+    /// 新增服务并记录审计日志
+    pub async fn create_service(
+        &self,
+        service: &ServiceRequest,
+        operator_id: i32,
+    ) -> Result<CatalogWriteOutcome<Service>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if fetch_category(&mut tx, service.category_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(CatalogWriteOutcome::ParentNotFound);
+        }
+
+        let result = sqlx::query!(
+            "INSERT INTO services (category_id, service_name, description, base_price, unit, \
+            min_duration, max_duration, is_active) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            service.category_id,
+            &service.service_name,
+            &service.description,
+            service.base_price,
+            service.unit.as_str(),
+            service.min_duration,
+            service.max_duration,
+            service.is_active.unwrap_or(true)
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let service_id = result.last_insert_id() as i32;
+        let created = fetch_service(&mut tx, service_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Service, service_id, AuditAction::Create, &created),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogWriteOutcome::Saved(created))
+    }
+
+    /// 修改服务并记录审计日志
+    pub async fn update_service(
+        &self,
+        service_id: i32,
+        service: &ServiceRequest,
+        operator_id: i32,
+    ) -> Result<CatalogWriteOutcome<Service>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if fetch_service(&mut tx, service_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(CatalogWriteOutcome::NotFound);
+        }
+        if fetch_category(&mut tx, service.category_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(CatalogWriteOutcome::ParentNotFound);
+        }
+
+        sqlx::query!(
+            "UPDATE services SET category_id = ?, service_name = ?, description = ?, base_price = ?, unit = ?, \
+            min_duration = ?, max_duration = ?, is_active = COALESCE(?, is_active) WHERE service_id = ?",
+            service.category_id,
+            &service.service_name,
+            &service.description,
+            service.base_price,
+            service.unit.as_str(),
+            service.min_duration,
+            service.max_duration,
+            service.is_active,
+            service_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let updated = fetch_service(&mut tx, service_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Service, service_id, AuditAction::Update, &updated),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogWriteOutcome::Saved(updated))
+    }
+
+    /// 下架服务并记录审计日志，被未结束的订单使用时拒绝
+    pub async fn delete_service(&self, service_id: i32, operator_id: i32) -> Result<CatalogDeleteOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if fetch_service(&mut tx, service_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::NotFound);
+        }
+
+        // 被未结束的订单引用时不能停用
+        let active_orders: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM orders WHERE service_id = ? AND order_status IN ('pending', 'confirmed', 'assigned', 'ongoing')",
+            service_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if active_orders > 0 {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::InUse);
+        }
+
+        sqlx::query!("UPDATE services SET is_active = FALSE WHERE service_id = ?", service_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let deleted = fetch_service(&mut tx, service_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Service, service_id, AuditAction::Delete, &deleted),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogDeleteOutcome::Deleted)
+    }
+
+    /// 为服务新增附加项并记录审计日志，服务不存在时返回 None
+    pub async fn create_addon(
+        &self,
+        service_id: i32,
+        addon: &AddonRequest,
+        operator_id: i32,
+    ) -> Result<Option<ServiceAddon>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if fetch_service(&mut tx, service_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let result = sqlx::query!(
+            "INSERT INTO service_addons (service_id, addon_name, addon_price, is_active) VALUES (?, ?, ?, ?)",
+            service_id,
+            &addon.addon_name,
+            addon.addon_price,
+            addon.is_active.unwrap_or(true)
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let addon_id = result.last_insert_id() as i32;
+        let created = fetch_addon(&mut tx, service_id, addon_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Addon, addon_id, AuditAction::Create, &created),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Some(created))
+    }
+
+    /// 修改附加项并记录审计日志，附加项不存在或不属于该服务时返回 None
+    pub async fn update_addon(
+        &self,
+        service_id: i32,
+        addon_id: i32,
+        addon: &AddonRequest,
+        operator_id: i32,
+    ) -> Result<Option<ServiceAddon>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if fetch_addon(&mut tx, service_id, addon_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE service_addons SET addon_name = ?, addon_price = ?, is_active = COALESCE(?, is_active) \
+            WHERE addon_id = ?",
+            &addon.addon_name,
+            addon.addon_price,
+            addon.is_active,
+            addon_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let updated = fetch_addon(&mut tx, service_id, addon_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Addon, addon_id, AuditAction::Update, &updated),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Some(updated))
+    }
+
+    /// 停用附加项并记录审计日志，被未结束的订单选购时拒绝
+    pub async fn delete_addon(
+        &self,
+        service_id: i32,
+        addon_id: i32,
+        operator_id: i32,
+    ) -> Result<CatalogDeleteOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if fetch_addon(&mut tx, service_id, addon_id).await?.is_none() {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::NotFound);
+        }
+
+        // 被未结束的订单引用时不能停用
+        let active_orders: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM order_addons oa JOIN orders o ON o.order_id = oa.order_id \
+            WHERE oa.addon_id = ? AND o.order_status IN ('pending', 'confirmed', 'assigned', 'ongoing')",
+            addon_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if active_orders > 0 {
+            tx.rollback().await?;
+            return Ok(CatalogDeleteOutcome::InUse);
+        }
+
+        sqlx::query!("UPDATE service_addons SET is_active = FALSE WHERE addon_id = ?", addon_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let deleted = fetch_addon(&mut tx, service_id, addon_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        record_audit(
+            &mut tx,
+            &NewAuditLog::new(operator_id, AuditEntity::Addon, addon_id, AuditAction::Delete, &deleted),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(CatalogDeleteOutcome::Deleted)
+    }
+}
+
+/// 内存中的服务存储，仅用于测试和本地调试
+#[derive(Default)]
+pub struct InMemoryServiceStore {
+    services: RwLock<Vec<Service>>,
+    addons: RwLock<Vec<ServiceAddon>>,
+}
+
+impl InMemoryServiceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用已有的服务和附加项初始化
+    pub fn with_catalog(services: Vec<Service>, addons: Vec<ServiceAddon>) -> Self {
+        Self {
+            services: RwLock::new(services),
+            addons: RwLock::new(addons),
+        }
+    }
+}
+
+#[async_trait]
+impl ServiceStore for InMemoryServiceStore {
+    async fn find_by_id(&self, id: i32) -> Result<Service, sqlx::Error> {
+        let services = self.services.read().await;
+        services.iter().find(|s| s.service_id == id).cloned().ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn find_active_addons(&self, service_id: i32) -> Result<Vec<ServiceAddon>, sqlx::Error> {
+        let addons = self.addons.read().await;
+        Ok(addons.iter().filter(|a| a.service_id == service_id && a.is_active).cloned().collect())
+    }
+}
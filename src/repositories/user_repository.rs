@@ -1,10 +1,52 @@
 //! 用户数据访问层
 //!
-//! 负责用户相关的数据库操作
+//! `UserStore` 抽象了用户的持久化，生产环境使用 MySQL 实现，
+//! 测试中使用内存实现
 
-use sqlx::{mysql::MySqlPool, Row};
-use crate::models::user::User;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::mysql::MySqlPool;
+use tokio::sync::RwLock;
 
+use crate::models::money::Money;
+use crate::models::user::{User, UserStatus, UserType};
+
+/// 查询完整的用户行，参数为 `FROM users` 之后的条件
+macro_rules! query_user {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            User,
+            "SELECT user_id, username, password_hash, email, phone, user_type AS `user_type!: UserType`, \
+            avatar_url, real_name, is_verified AS `is_verified!: bool`, balance AS `balance!: Money`, \
+            status AS `status!: UserStatus`, created_at AS `created_at!: NaiveDateTime`, \
+            updated_at AS `updated_at!: NaiveDateTime` FROM users " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+#[async_trait]
+pub trait UserStore: Send + Sync {
+    /// 获取所有用户列表
+    async fn list_users(&self) -> Result<Vec<User>, sqlx::Error>;
+
+    /// 根据ID查找用户，不存在时返回 `RowNotFound`
+    async fn find_by_id(&self, id: i32) -> Result<User, sqlx::Error>;
+
+    /// 根据用户名或邮箱查找用户
+    async fn find_by_identifier(&self, identifier: &str) -> Result<Option<User>, sqlx::Error>;
+
+    /// 检查用户名、邮箱或手机号是否已被使用
+    async fn check_user_exists(&self, username: &str, email: &str, phone: &str) -> Result<bool, sqlx::Error>;
+
+    /// 创建新用户，新用户均为客户
+    async fn create_user(&self, username: &str, hashed_password: &str, email: &str, phone: &str) -> Result<(), sqlx::Error>;
+
+    /// 根据用户名查找用户
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, sqlx::Error>;
+}
+
+/// 基于 MySQL 的用户存储
 pub struct UserRepository {
     pool: MySqlPool,
 }
@@ -13,179 +55,131 @@ impl UserRepository {
     pub fn new(pool: MySqlPool) -> Self {
         Self { pool }
     }
+}
 
+#[async_trait]
+impl UserStore for UserRepository {
     /// 获取所有用户列表
-    pub async fn list_users(&self) -> Result<Vec<User>, sqlx::Error> {
-        let users_result = sqlx::query(
-            "SELECT user_id, username, password_hash, email, phone, user_type, avatar_url, \
-            real_name, is_verified, balance, status, created_at, updated_at \
-            FROM users"
-        )
-        .fetch_all(&self.pool)
-        .await;
-
-        match users_result {
-            Ok(rows) => {
-                let users: Vec<User> = rows.into_iter().map(|row| {
-                    User {
-                        user_id: row.get("user_id"),
-                        username: row.get("username"),
-                        password_hash: row.get("password_hash"),
-                        email: row.get("email"),
-                        phone: row.get("phone"),
-                        user_type: row.get("user_type"),
-                        avatar_url: row.get("avatar_url"),
-                        real_name: row.get("real_name"),
-                        is_verified: row.get("is_verified"),
-                        balance: row.get("balance"),
-                        status: row.get("status"),
-                        created_at: row.get("created_at"),
-                        updated_at: row.get("updated_at"),
-                    }
-                }).collect();
-                Ok(users)
-            }
-            Err(e) => Err(e),
-        }
+    async fn list_users(&self) -> Result<Vec<User>, sqlx::Error> {
+        query_user!("")
+            .fetch_all(&self.pool)
+            .await
     }
 
     /// 根据ID查找用户
-    pub async fn find_by_id(&self, id: i32) -> Result<User, sqlx::Error> {
-        let user_result = sqlx::query(
-            "SELECT user_id, username, password_hash, email, phone, user_type, avatar_url, \
-            real_name, is_verified, balance, status, created_at, updated_at \
-            FROM users WHERE user_id = ?"
-        )
-        .bind(id)
-        .fetch_one(&self.pool)
-        .await;
-
-        match user_result {
-            Ok(row) => {
-                let user = User {
-                    user_id: row.get("user_id"),
-                    username: row.get("username"),
-                    password_hash: row.get("password_hash"),
-                    email: row.get("email"),
-                    phone: row.get("phone"),
-                    user_type: row.get("user_type"),
-                    avatar_url: row.get("avatar_url"),
-                    real_name: row.get("real_name"),
-                    is_verified: row.get("is_verified"),
-                    balance: row.get("balance"),
-                    status: row.get("status"),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                };
-                Ok(user)
-            }
-            Err(e) => Err(e),
-        }
+    async fn find_by_id(&self, id: i32) -> Result<User, sqlx::Error> {
+        query_user!("WHERE user_id = ?", id)
+            .fetch_one(&self.pool)
+            .await
     }
 
     /// 根据用户名或邮箱查找用户
-    pub async fn find_by_identifier(&self, identifier: &str) -> Result<Option<User>, sqlx::Error> {
-        let user_result = sqlx::query(
-            "SELECT user_id, username, password_hash, email, phone, user_type, avatar_url, \
-            real_name, is_verified, balance, status, created_at, updated_at \
-            FROM users WHERE username = ? OR email = ? LIMIT 1"
-        )
-        .bind(identifier)
-        .bind(identifier)
-        .fetch_optional(&self.pool)
-        .await;
-
-        match user_result {
-            Ok(Some(row)) => {
-                let user = User {
-                    user_id: row.get("user_id"),
-                    username: row.get("username"),
-                    password_hash: row.get("password_hash"),
-                    email: row.get("email"),
-                    phone: row.get("phone"),
-                    user_type: row.get("user_type"),
-                    avatar_url: row.get("avatar_url"),
-                    real_name: row.get("real_name"),
-                    is_verified: row.get("is_verified"),
-                    balance: row.get("balance"),
-                    status: row.get("status"),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                };
-                Ok(Some(user))
-            }
-            Ok(None) => Ok(None),
-            Err(e) => Err(e),
-        }
+    async fn find_by_identifier(&self, identifier: &str) -> Result<Option<User>, sqlx::Error> {
+        query_user!("WHERE username = ? OR email = ? LIMIT 1", identifier, identifier)
+            .fetch_optional(&self.pool)
+            .await
     }
 
     /// 检查用户是否已存在
-    pub async fn check_user_exists(&self, username: &str, email: &str, phone: &str) -> Result<bool, sqlx::Error> {
-        let exists_result = sqlx::query(
-            "SELECT COUNT(*) as count FROM users WHERE username = ? OR email = ? OR phone = ?"
+    async fn check_user_exists(&self, username: &str, email: &str, phone: &str) -> Result<bool, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM users WHERE username = ? OR email = ? OR phone = ?",
+            username,
+            email,
+            phone
         )
-        .bind(username)
-        .bind(email)
-        .bind(phone)
         .fetch_one(&self.pool)
-        .await;
-
-        match exists_result {
-            Ok(row) => {
-                let count: i64 = row.get("count");
-                Ok(count > 0)
-            }
-            Err(e) => Err(e),
-        }
+        .await?;
+        Ok(count > 0)
     }
 
     /// 创建新用户
-    pub async fn create_user(&self, username: &str, hashed_password: &str, email: &str, phone: &str) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    async fn create_user(&self, username: &str, hashed_password: &str, email: &str, phone: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
             "INSERT INTO users (username, password_hash, email, phone, user_type, status, created_at, updated_at) \
-            VALUES (?, ?, ?, ?, 'customer', 'active', NOW(), NOW())"
+            VALUES (?, ?, ?, ?, ?, ?, NOW(), NOW())",
+            username,
+            hashed_password,
+            email,
+            phone,
+            UserType::Customer.as_str(),
+            UserStatus::Active.as_str()
         )
-        .bind(username)
-        .bind(hashed_password)
-        .bind(email)
-        .bind(phone)
         .execute(&self.pool)
         .await
         .map(|_| ())
     }
 
     /// 根据用户名查找用户
-    pub async fn find_by_username(&self, username: &str) -> Result<Option<User>, sqlx::Error> {
-        let user_result = sqlx::query(
-            "SELECT user_id, username, password_hash, email, phone, user_type, avatar_url, \
-            real_name, is_verified, balance, status, created_at, updated_at \
-            FROM users WHERE username = ?"
-        )
-        .bind(username)
-        .fetch_optional(&self.pool)
-        .await;
-
-        match user_result {
-            Ok(Some(row)) => {
-                let user = User {
-                    user_id: row.get("user_id"),
-                    username: row.get("username"),
-                    password_hash: row.get("password_hash"),
-                    email: row.get("email"),
-                    phone: row.get("phone"),
-                    user_type: row.get("user_type"),
-                    avatar_url: row.get("avatar_url"),
-                    real_name: row.get("real_name"),
-                    is_verified: row.get("is_verified"),
-                    balance: row.get("balance"),
-                    status: row.get("status"),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                };
-                Ok(Some(user))
-            }
-            Ok(None) => Ok(None),
-            Err(e) => Err(e),
-        }
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, sqlx::Error> {
+        query_user!("WHERE username = ?", username)
+            .fetch_optional(&self.pool)
+            .await
     }
-}
\ No newline at end of file
+}
+
+/// 内存中的用户存储，仅用于测试和本地调试
+#[derive(Default)]
+pub struct InMemoryUserStore {
+    users: RwLock<Vec<User>>,
+}
+
+impl InMemoryUserStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用已有用户初始化，新用户的ID从最大ID之后开始分配
+    pub fn with_users(users: Vec<User>) -> Self {
+        Self { users: RwLock::new(users) }
+    }
+}
+
+#[async_trait]
+impl UserStore for InMemoryUserStore {
+    async fn list_users(&self) -> Result<Vec<User>, sqlx::Error> {
+        Ok(self.users.read().await.clone())
+    }
+
+    async fn find_by_id(&self, id: i32) -> Result<User, sqlx::Error> {
+        let users = self.users.read().await;
+        users.iter().find(|u| u.user_id == id).cloned().ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn find_by_identifier(&self, identifier: &str) -> Result<Option<User>, sqlx::Error> {
+        let users = self.users.read().await;
+        Ok(users.iter().find(|u| u.username == identifier || u.email == identifier).cloned())
+    }
+
+    async fn check_user_exists(&self, username: &str, email: &str, phone: &str) -> Result<bool, sqlx::Error> {
+        let users = self.users.read().await;
+        Ok(users.iter().any(|u| u.username == username || u.email == email || u.phone == phone))
+    }
+
+    async fn create_user(&self, username: &str, hashed_password: &str, email: &str, phone: &str) -> Result<(), sqlx::Error> {
+        let mut users = self.users.write().await;
+        let now = chrono::Utc::now().naive_utc();
+        let user_id = users.iter().map(|u| u.user_id).max().unwrap_or(0) + 1;
+        users.push(User {
+            user_id,
+            username: username.to_string(),
+            password_hash: hashed_password.to_string(),
+            email: email.to_string(),
+            phone: phone.to_string(),
+            user_type: UserType::Customer,
+            avatar_url: None,
+            real_name: None,
+            is_verified: false,
+            balance: Money::ZERO,
+            status: UserStatus::Active,
+            created_at: now,
+            updated_at: now,
+        });
+        Ok(())
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, sqlx::Error> {
+        let users = self.users.read().await;
+        Ok(users.iter().find(|u| u.username == username).cloned())
+    }
+}
@@ -0,0 +1,255 @@
+//! 钱包数据访问层
+//!
+//! 负责 users.balance 的变动、balance_transactions 流水和 wallet_topups 充值记录的读写。
+//! 余额变动必须通过 `apply_entry` 完成，它锁定用户行、拒绝会导致余额为负的扣款，
+//! 并在同一事务中写入流水和平台科目上的对应分录
+
+use chrono::NaiveDateTime;
+use sqlx::{mysql::MySqlPool, MySqlConnection};
+use crate::models::money::Money;
+use crate::models::pagination::PageQuery;
+use crate::models::payment::PaymentMethod;
+use crate::models::wallet::{
+    BalanceTransaction, LedgerEntry, NewTopup, ReferenceType, TopupStatus, TransactionType, WalletTopup,
+};
+
+/// 查询完整的余额流水行，参数为 `FROM balance_transactions` 之后的条件
+macro_rules! query_transaction {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            BalanceTransaction,
+            "SELECT transaction_id, user_id, transaction_type AS `transaction_type: TransactionType`, \
+            amount AS `amount: Money`, balance_before AS `balance_before: Money`, \
+            balance_after AS `balance_after: Money`, reference_type AS `reference_type: ReferenceType`, \
+            reference_id, operator_id, remark, created_at AS `created_at!: NaiveDateTime` \
+            FROM balance_transactions " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 查询完整的充值记录行，参数为 `FROM wallet_topups` 之后的条件
+macro_rules! query_topup {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            WalletTopup,
+            "SELECT topup_id, user_id, payment_method AS `payment_method: PaymentMethod`, amount AS `amount: Money`, \
+            topup_status AS `topup_status: TopupStatus`, thirdparty_trade_no, transaction_id, settled_at, \
+            created_at AS `created_at!: NaiveDateTime` FROM wallet_topups " + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// 在调用方的事务中记账
+/// 锁定用户行后按金额增减余额，扣款后余额为负时不做任何修改并返回 None；
+/// 写入流水后在平台科目上记一笔金额相反的分录
+pub(crate) async fn apply_entry(
+    conn: &mut MySqlConnection,
+    entry: &LedgerEntry,
+) -> Result<Option<BalanceTransaction>, sqlx::Error> {
+    sqlx::query!("SELECT user_id FROM users WHERE user_id = ? FOR UPDATE", entry.user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    // 金额参数以字符串绑定，需显式转换为 DECIMAL，否则 MySQL 会按 DOUBLE 计算
+    let updated = sqlx::query!(
+        "UPDATE users SET balance = balance + CAST(? AS DECIMAL(10, 2)) \
+        WHERE user_id = ? AND balance + CAST(? AS DECIMAL(10, 2)) >= 0",
+        entry.amount,
+        entry.user_id,
+        entry.amount
+    )
+    .execute(&mut *conn)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    let inserted = sqlx::query!(
+        "INSERT INTO balance_transactions \
+        (user_id, transaction_type, amount, balance_before, balance_after, reference_type, reference_id, operator_id, remark) \
+        SELECT user_id, ?, ?, balance - CAST(? AS DECIMAL(10, 2)), balance, ?, ?, ?, ? FROM users WHERE user_id = ?",
+        entry.transaction_type.as_str(),
+        entry.amount,
+        entry.amount,
+        entry.reference.reference_type().as_str(),
+        entry.reference.reference_id(),
+        entry.operator_id,
+        entry.remark.as_deref(),
+        entry.user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO platform_ledger (account, transaction_id, amount) \
+        VALUES (?, ?, -CAST(? AS DECIMAL(10, 2)))",
+        entry.transaction_type.platform_account().as_str(),
+        inserted.last_insert_id(),
+        entry.amount
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let row = query_transaction!("WHERE transaction_id = ?", inserted.last_insert_id())
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(Some(row))
+}
+
+pub struct WalletRepository {
+    pool: MySqlPool,
+}
+
+impl WalletRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取用户当前余额
+    pub async fn balance(&self, user_id: i32) -> Result<Money, sqlx::Error> {
+        sqlx::query_scalar!("SELECT balance AS `balance!: Money` FROM users WHERE user_id = ?", user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// 分页获取用户余额流水，按时间倒序，返回 (流水, 总条数)
+    pub async fn list_transactions(
+        &self,
+        user_id: i32,
+        page: &PageQuery,
+    ) -> Result<(Vec<BalanceTransaction>, i64), sqlx::Error> {
+        let total: i64 = sqlx::query_scalar!("SELECT COUNT(*) FROM balance_transactions WHERE user_id = ?", user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let rows = query_transaction!(
+            "WHERE user_id = ? \
+            ORDER BY transaction_id DESC LIMIT ? OFFSET ?",
+            user_id,
+            page.page_size(),
+            page.offset()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((rows, total))
+    }
+
+    /// 单独记一笔账 (充值、管理员调整)
+    /// 余额不足以扣减时返回 None
+    pub async fn record(&self, entry: &LedgerEntry) -> Result<Option<BalanceTransaction>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        match apply_entry(&mut tx, entry).await? {
+            Some(transaction) => {
+                tx.commit().await?;
+                Ok(Some(transaction))
+            }
+            None => {
+                tx.rollback().await?;
+                Ok(None)
+            }
+        }
+    }
+
+    pub async fn find_topup(&self, topup_id: &str) -> Result<WalletTopup, sqlx::Error> {
+        query_topup!("WHERE topup_id = ?", topup_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// 获取用户等待渠道结果的充值记录
+    pub async fn list_processing_topups(&self, user_id: i32) -> Result<Vec<WalletTopup>, sqlx::Error> {
+        query_topup!("WHERE user_id = ? AND topup_status = 'processing' ORDER BY created_at", user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// 创建待支付的充值记录，向渠道下单前调用
+    pub async fn create_topup(&self, topup: &NewTopup) -> Result<WalletTopup, sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO wallet_topups (topup_id, user_id, payment_method, amount, topup_status) \
+            VALUES (?, ?, ?, ?, 'pending')",
+            &topup.topup_id,
+            topup.user_id,
+            topup.payment_method.as_str(),
+            topup.amount
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.find_topup(&topup.topup_id).await
+    }
+
+    /// 渠道已受理，等待支付结果
+    pub async fn mark_topup_processing(&self, topup_id: &str, trade_no: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE wallet_topups SET topup_status = 'processing', thirdparty_trade_no = ? \
+            WHERE topup_id = ? AND topup_status = 'pending'",
+            trade_no,
+            topup_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 充值失败
+    pub async fn mark_topup_failed(&self, topup_id: &str, trade_no: Option<&str>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE wallet_topups SET topup_status = 'failed', \
+            thirdparty_trade_no = COALESCE(?, thirdparty_trade_no) \
+            WHERE topup_id = ? AND topup_status IN ('pending', 'processing')",
+            trade_no,
+            topup_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 充值成功入账
+    /// 锁定充值记录，在同一事务中记账并把充值记录置为 success，
+    /// 充值已入账或已失败时返回 None，保证同一笔充值只入账一次
+    pub async fn settle_topup(
+        &self,
+        topup_id: &str,
+        trade_no: &str,
+        entry: &LedgerEntry,
+    ) -> Result<Option<BalanceTransaction>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let open = sqlx::query!(
+            "SELECT topup_id FROM wallet_topups \
+            WHERE topup_id = ? AND topup_status IN ('pending', 'processing') FOR UPDATE",
+            topup_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if open.is_none() {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        // 入账不会使余额为负，apply_entry 一定成功
+        let Some(transaction) = apply_entry(&mut tx, entry).await? else {
+            tx.rollback().await?;
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            "UPDATE wallet_topups SET topup_status = 'success', thirdparty_trade_no = ?, \
+            transaction_id = ?, settled_at = NOW() WHERE topup_id = ?",
+            trade_no,
+            transaction.transaction_id,
+            topup_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(transaction))
+    }
+}
@@ -0,0 +1,248 @@
+//! 入驻申请数据访问层
+//!
+//! 负责 worker_applications 表的读写，审核通过时在同一事务中开通服务人员身份
+
+use chrono::NaiveDateTime;
+use sqlx::{mysql::MySqlPool, types::Json, MySqlConnection};
+use crate::models::money::Money;
+use crate::models::pagination::PageQuery;
+use crate::models::user::UserType;
+use crate::models::worker::{ApplicationStatus, WorkerApplication, WorkerApplicationRequest};
+use crate::repositories::worker_repository::json_list;
+
+/// 查询完整的申请行并组装为模型，参数为 `FROM worker_applications` 之后的条件
+/// JSON 列以 `Json<Vec<String>>` 解码后取出内部列表
+macro_rules! query_application {
+    ($tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query!(
+            "SELECT application_id, user_id, service_category_id, real_name, hourly_rate AS `hourly_rate: Money`, \
+            bio, skills AS `skills: Json<Vec<String>>`, service_area AS `service_area: Json<Vec<String>>`, \
+            status AS `status!: ApplicationStatus`, reviewer_id, review_remark, \
+            reviewed_at AS `reviewed_at: NaiveDateTime`, created_at AS `created_at!: NaiveDateTime` \
+            FROM worker_applications " + $tail
+            $(, $arg)*
+        )
+        .map(|row| WorkerApplication {
+            application_id: row.application_id,
+            user_id: row.user_id,
+            service_category_id: row.service_category_id,
+            real_name: row.real_name,
+            hourly_rate: row.hourly_rate,
+            bio: row.bio,
+            skills: row.skills.map(|list| list.0),
+            service_area: Some(row.service_area.0),
+            status: row.status,
+            reviewer_id: row.reviewer_id,
+            review_remark: row.review_remark,
+            reviewed_at: row.reviewed_at,
+            created_at: row.created_at,
+        })
+    };
+}
+
+/// 提交入驻申请的结果
+#[derive(Debug)]
+pub enum CreateApplicationOutcome {
+    Created(Box<WorkerApplication>),
+    /// 用户已有待审核的申请
+    PendingExists,
+    /// 用户不是客户 (已经是服务人员或管理员)
+    NotCustomer,
+    /// 申请的服务分类不存在或已停用
+    CategoryNotFound,
+}
+
+/// 审核入驻申请的结果
+#[derive(Debug)]
+pub enum ReviewApplicationOutcome {
+    Reviewed(Box<WorkerApplication>),
+    NotFound,
+    /// 申请已被审核过
+    AlreadyReviewed,
+    /// 申请人已不是客户，无法开通服务人员身份
+    NotCustomer,
+}
+
+async fn fetch_application(conn: &mut MySqlConnection, application_id: i32) -> Result<Option<WorkerApplication>, sqlx::Error> {
+    query_application!("WHERE application_id = ? FOR UPDATE", application_id)
+        .fetch_optional(&mut *conn)
+        .await
+}
+
+/// 锁住用户行并返回用户类型，串行化同一用户的申请和审核
+async fn lock_user_type(conn: &mut MySqlConnection, user_id: i32) -> Result<UserType, sqlx::Error> {
+    sqlx::query_scalar!("SELECT user_type AS `user_type!: UserType` FROM users WHERE user_id = ? FOR UPDATE", user_id)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+pub struct WorkerApplicationRepository {
+    pool: MySqlPool,
+}
+
+impl WorkerApplicationRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 根据ID查找申请
+    pub async fn find_by_id(&self, application_id: i32) -> Result<Option<WorkerApplication>, sqlx::Error> {
+        query_application!("WHERE application_id = ?", application_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// 用户自己的申请，最新的在前
+    pub async fn list_by_user(&self, user_id: i32) -> Result<Vec<WorkerApplication>, sqlx::Error> {
+        query_application!("WHERE user_id = ? ORDER BY application_id DESC", user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// 申请列表，可按状态筛选，先提交的在前，返回 (申请, 总条数)
+    pub async fn list(
+        &self,
+        status: Option<ApplicationStatus>,
+        page: &PageQuery,
+    ) -> Result<(Vec<WorkerApplication>, i64), sqlx::Error> {
+        let status = status.map(|s| s.as_str());
+        let total: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM worker_applications WHERE (? IS NULL OR status = ?)",
+            status,
+            status
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = query_application!(
+            "WHERE (? IS NULL OR status = ?) \
+            ORDER BY application_id LIMIT ? OFFSET ?",
+            status,
+            status,
+            page.page_size(),
+            page.offset()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((rows, total))
+    }
+
+    /// 提交入驻申请，每个用户同时只能有一条待审核的申请
+    pub async fn create(
+        &self,
+        user_id: i32,
+        application: &WorkerApplicationRequest,
+    ) -> Result<CreateApplicationOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if lock_user_type(&mut tx, user_id).await? != UserType::Customer {
+            tx.rollback().await?;
+            return Ok(CreateApplicationOutcome::NotCustomer);
+        }
+
+        let pending: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM worker_applications WHERE user_id = ? AND status = 'pending'",
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if pending > 0 {
+            tx.rollback().await?;
+            return Ok(CreateApplicationOutcome::PendingExists);
+        }
+
+        let category_exists: i64 = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM service_categories WHERE category_id = ? AND is_active = TRUE",
+            application.service_category_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if category_exists == 0 {
+            tx.rollback().await?;
+            return Ok(CreateApplicationOutcome::CategoryNotFound);
+        }
+
+        let result = sqlx::query!(
+            "INSERT INTO worker_applications (user_id, service_category_id, real_name, hourly_rate, bio, \
+            skills, service_area) VALUES (?, ?, ?, ?, ?, CAST(? AS JSON), CAST(? AS JSON))",
+            user_id,
+            application.service_category_id,
+            &application.real_name,
+            application.hourly_rate,
+            &application.bio,
+            json_list(&application.skills),
+            json_list(&application.service_area)
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let created = fetch_application(&mut tx, result.last_insert_id() as i32).await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        tx.commit().await?;
+        Ok(CreateApplicationOutcome::Created(Box::new(created)))
+    }
+
+    /// 审核入驻申请
+    /// 通过时将申请人设为已实名的服务人员，并按申请内容创建服务人员资料
+    pub async fn review(
+        &self,
+        application_id: i32,
+        reviewer_id: i32,
+        decision: ApplicationStatus,
+        remark: Option<&str>,
+    ) -> Result<ReviewApplicationOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let Some(application) = fetch_application(&mut tx, application_id).await? else {
+            tx.rollback().await?;
+            return Ok(ReviewApplicationOutcome::NotFound);
+        };
+        if application.status != ApplicationStatus::Pending {
+            tx.rollback().await?;
+            return Ok(ReviewApplicationOutcome::AlreadyReviewed);
+        }
+
+        if decision == ApplicationStatus::Approved {
+            if lock_user_type(&mut tx, application.user_id).await? != UserType::Customer {
+                tx.rollback().await?;
+                return Ok(ReviewApplicationOutcome::NotCustomer);
+            }
+
+            sqlx::query!(
+                "UPDATE users SET user_type = ?, is_verified = TRUE, real_name = ? WHERE user_id = ?",
+                UserType::Worker.as_str(),
+                &application.real_name,
+                application.user_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            // 曾经是服务人员的用户保留原有的评分，其余资料以本次申请为准
+            sqlx::query!(
+                "INSERT INTO worker_profiles (worker_id, service_category_id, hourly_rate, bio, skills, service_area) \
+                SELECT user_id, service_category_id, hourly_rate, bio, skills, service_area \
+                FROM worker_applications WHERE application_id = ? \
+                ON DUPLICATE KEY UPDATE service_category_id = VALUES(service_category_id), \
+                hourly_rate = VALUES(hourly_rate), bio = VALUES(bio), skills = VALUES(skills), \
+                service_area = VALUES(service_area), is_available = TRUE",
+                application_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            "UPDATE worker_applications SET status = ?, reviewer_id = ?, review_remark = ?, reviewed_at = NOW() \
+            WHERE application_id = ?",
+            decision.as_str(),
+            reviewer_id,
+            remark,
+            application_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let reviewed = fetch_application(&mut tx, application_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        tx.commit().await?;
+        Ok(ReviewApplicationOutcome::Reviewed(Box::new(reviewed)))
+    }
+}
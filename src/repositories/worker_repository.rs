@@ -0,0 +1,241 @@
+//! 服务人员数据访问层
+//!
+//! 负责服务人员资料和日程相关的数据库操作
+
+use chrono::NaiveDateTime;
+use sqlx::{mysql::{MySql, MySqlPool}, types::Json, QueryBuilder};
+use crate::models::money::Money;
+use crate::models::pagination::PageQuery;
+use crate::models::worker::{
+    UpdateWorkerProfileRequest, WorkerCandidate, WorkerProfile, WorkerPublicProfile,
+    WorkerSearchQuery,
+};
+
+/// 订单和评价统计实时从 orders、reviews 表计算
+/// 以下两段用于动态拼接的搜索查询，`find_profile` 和 `find_public_profile` 中的静态查询写有相同的列，修改时需同步
+const WORKER_STATS: &str = "\
+    (SELECT COUNT(*) FROM orders o WHERE o.worker_id = wp.worker_id AND o.order_status <> 'cancelled') AS total_orders, \
+    (SELECT COUNT(*) FROM orders o WHERE o.worker_id = wp.worker_id AND o.order_status = 'completed') AS completed_orders";
+
+const PROFILE_COLUMNS: &str = "wp.worker_id, wp.service_category_id, wp.hourly_rate, wp.bio, \
+    CAST(wp.skills AS CHAR) AS skills, CAST(wp.service_area AS CHAR) AS service_area, \
+    CAST(wp.avg_rating AS DOUBLE) AS avg_rating, wp.is_available, wp.max_daily_orders, wp.created_at, wp.updated_at";
+
+/// JSON 数组列的写入值
+pub(crate) fn json_list(values: &[String]) -> String {
+    serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// 订单统计超出 i32 时按解码错误处理，与 `WorkerProfile` 的 `try_from = "i64"` 映射一致
+fn count_to_i32(count: i64) -> Result<i32, sqlx::Error> {
+    i32::try_from(count).map_err(|e| sqlx::Error::Decode(e.into()))
+}
+
+/// 公开资料的查询，只包含状态正常的服务人员账号
+fn public_profile_query<'a>() -> QueryBuilder<'a, MySql> {
+    QueryBuilder::new(format!(
+        "SELECT {}, {}, u.username, u.avatar_url, c.category_name, \
+        (SELECT COUNT(*) FROM reviews r WHERE r.worker_id = wp.worker_id) AS review_count \
+        FROM worker_profiles wp \
+        JOIN users u ON u.user_id = wp.worker_id AND u.user_type = 'worker' AND u.status = 'active' \
+        JOIN service_categories c ON c.category_id = wp.service_category_id",
+        PROFILE_COLUMNS, WORKER_STATS
+    ))
+}
+
+/// 追加服务人员搜索条件
+fn push_search_filters<'a>(builder: &mut QueryBuilder<'a, MySql>, query: &'a WorkerSearchQuery) {
+    builder.push(" WHERE 1 = 1");
+    if let Some(category_id) = query.category_id {
+        builder.push(" AND wp.service_category_id = ").push_bind(category_id);
+    }
+    if let Some(area) = &query.area {
+        builder.push(" AND JSON_CONTAINS(wp.service_area, JSON_QUOTE(").push_bind(area).push("))");
+    }
+    if let Some(min_rating) = query.min_rating {
+        builder.push(" AND wp.avg_rating >= ").push_bind(min_rating);
+    }
+    if query.available_only {
+        builder.push(" AND wp.is_available = TRUE");
+    }
+}
+
+pub struct WorkerRepository {
+    pool: MySqlPool,
+}
+
+impl WorkerRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 查找可以接该订单的服务人员
+    /// 条件: 服务分类一致、服务区域包含订单地址所在区县、可接单、
+    /// 服务日期对应时间段 (或全天) 有空闲日程、当日订单数未达到上限
+    /// 可预约时间段的统计 (`ScheduleRepository::count_available_workers`) 使用相同的条件，修改时需同步
+    /// 返回结果未排序，score 为 0，由派单服务计算
+    pub async fn find_dispatch_candidates(&self, order_id: &str) -> Result<Vec<WorkerCandidate>, sqlx::Error> {
+        sqlx::query!(
+            "SELECT wp.worker_id, u.username, CAST(wp.avg_rating AS DOUBLE) AS `avg_rating!: f64`, \
+            wp.max_daily_orders AS `max_daily_orders!`, ws.schedule_id, \
+            (SELECT COUNT(*) FROM orders d WHERE d.worker_id = wp.worker_id AND d.service_date = o.service_date \
+                AND d.order_status IN ('assigned', 'ongoing', 'completed')) AS `daily_orders!: i64` \
+            FROM orders o \
+            JOIN services s ON s.service_id = o.service_id \
+            JOIN user_addresses a ON a.address_id = o.address_id \
+            JOIN worker_profiles wp ON wp.service_category_id = s.category_id \
+                AND wp.is_available = TRUE \
+                AND JSON_CONTAINS(wp.service_area, JSON_QUOTE(a.district)) \
+            JOIN users u ON u.user_id = wp.worker_id AND u.user_type = 'worker' AND u.status = 'active' \
+            JOIN worker_schedules ws ON ws.worker_id = wp.worker_id \
+                AND ws.schedule_date = o.service_date \
+                AND (ws.time_slot = o.time_slot OR ws.time_slot = 'full_day') \
+                AND ws.status = 'available' \
+            WHERE o.order_id = ? \
+            AND (SELECT COUNT(*) FROM orders d WHERE d.worker_id = wp.worker_id AND d.service_date = o.service_date \
+                AND d.order_status IN ('assigned', 'ongoing', 'completed')) < wp.max_daily_orders",
+            order_id
+        )
+        .map(|row| WorkerCandidate {
+            worker_id: row.worker_id,
+            username: row.username,
+            avg_rating: row.avg_rating,
+            daily_orders: row.daily_orders,
+            max_daily_orders: row.max_daily_orders,
+            schedule_id: row.schedule_id,
+            score: 0.0,
+        })
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// 获取服务人员自己的资料
+    pub async fn find_profile(&self, worker_id: i32) -> Result<Option<WorkerProfile>, sqlx::Error> {
+        sqlx::query!(
+            "SELECT wp.worker_id, wp.service_category_id, wp.hourly_rate AS `hourly_rate: Money`, wp.bio, \
+            wp.skills AS `skills: Json<Vec<String>>`, wp.service_area AS `service_area: Json<Vec<String>>`, \
+            CAST(wp.avg_rating AS DOUBLE) AS `avg_rating!: f64`, wp.is_available AS `is_available!: bool`, \
+            wp.max_daily_orders AS `max_daily_orders!`, wp.created_at AS `created_at!: NaiveDateTime`, \
+            wp.updated_at AS `updated_at!: NaiveDateTime`, \
+            (SELECT COUNT(*) FROM orders o WHERE o.worker_id = wp.worker_id AND o.order_status <> 'cancelled') \
+                AS `total_orders!: i64`, \
+            (SELECT COUNT(*) FROM orders o WHERE o.worker_id = wp.worker_id AND o.order_status = 'completed') \
+                AS `completed_orders!: i64` \
+            FROM worker_profiles wp WHERE wp.worker_id = ?",
+            worker_id
+        )
+        .try_map(|row| {
+            Ok(WorkerProfile {
+                worker_id: row.worker_id,
+                service_category_id: row.service_category_id,
+                hourly_rate: row.hourly_rate,
+                bio: row.bio,
+                skills: row.skills.map(|list| list.0),
+                service_area: row.service_area.map(|list| list.0),
+                total_orders: count_to_i32(row.total_orders)?,
+                completed_orders: count_to_i32(row.completed_orders)?,
+                avg_rating: row.avg_rating,
+                is_available: row.is_available,
+                max_daily_orders: row.max_daily_orders,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            })
+        })
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// 修改服务人员资料，资料不存在时返回 None
+    pub async fn update_profile(
+        &self,
+        worker_id: i32,
+        profile: &UpdateWorkerProfileRequest,
+    ) -> Result<Option<WorkerProfile>, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE worker_profiles SET hourly_rate = ?, bio = ?, skills = CAST(? AS JSON), \
+            service_area = CAST(? AS JSON), max_daily_orders = ? WHERE worker_id = ?",
+            profile.hourly_rate,
+            &profile.bio,
+            json_list(&profile.skills),
+            json_list(&profile.service_area),
+            profile.max_daily_orders,
+            worker_id
+        )
+        .execute(&self.pool)
+        .await?;
+        self.find_profile(worker_id).await
+    }
+
+    /// 切换是否接单，资料不存在时返回 None
+    pub async fn set_available(&self, worker_id: i32, is_available: bool) -> Result<Option<WorkerProfile>, sqlx::Error> {
+        sqlx::query!("UPDATE worker_profiles SET is_available = ? WHERE worker_id = ?", is_available, worker_id)
+            .execute(&self.pool)
+            .await?;
+        self.find_profile(worker_id).await
+    }
+
+    /// 按分类、服务区域和评分搜索服务人员，评分高的在前，返回 (资料, 总条数)
+    pub async fn search(
+        &self,
+        query: &WorkerSearchQuery,
+        page: &PageQuery,
+    ) -> Result<(Vec<WorkerPublicProfile>, i64), sqlx::Error> {
+        let mut count = QueryBuilder::new(
+            "SELECT COUNT(*) FROM worker_profiles wp \
+            JOIN users u ON u.user_id = wp.worker_id AND u.user_type = 'worker' AND u.status = 'active'"
+        );
+        push_search_filters(&mut count, query);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut select = public_profile_query();
+        push_search_filters(&mut select, query);
+        select
+            .push(" ORDER BY wp.avg_rating DESC, wp.worker_id LIMIT ")
+            .push_bind(page.page_size())
+            .push(" OFFSET ")
+            .push_bind(page.offset());
+        let rows: Vec<WorkerPublicProfile> = select.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok((rows.into_iter().map(WorkerPublicProfile::with_completion_rate).collect(), total))
+    }
+
+    /// 服务人员公开资料
+    pub async fn find_public_profile(&self, worker_id: i32) -> Result<Option<WorkerPublicProfile>, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT wp.worker_id, wp.service_category_id, wp.hourly_rate AS `hourly_rate: Money`, wp.bio, \
+            wp.skills AS `skills: Json<Vec<String>>`, wp.service_area AS `service_area: Json<Vec<String>>`, \
+            CAST(wp.avg_rating AS DOUBLE) AS `avg_rating!: f64`, wp.is_available AS `is_available!: bool`, \
+            (SELECT COUNT(*) FROM orders o WHERE o.worker_id = wp.worker_id AND o.order_status <> 'cancelled') \
+                AS `total_orders!: i64`, \
+            (SELECT COUNT(*) FROM orders o WHERE o.worker_id = wp.worker_id AND o.order_status = 'completed') \
+                AS `completed_orders!: i64`, \
+            u.username, u.avatar_url, c.category_name, \
+            (SELECT COUNT(*) FROM reviews r WHERE r.worker_id = wp.worker_id) AS `review_count!: i64` \
+            FROM worker_profiles wp \
+            JOIN users u ON u.user_id = wp.worker_id AND u.user_type = 'worker' AND u.status = 'active' \
+            JOIN service_categories c ON c.category_id = wp.service_category_id \
+            WHERE wp.worker_id = ?",
+            worker_id
+        )
+        .map(|row| WorkerPublicProfile {
+            worker_id: row.worker_id,
+            username: row.username,
+            avatar_url: row.avatar_url,
+            service_category_id: row.service_category_id,
+            category_name: row.category_name,
+            hourly_rate: row.hourly_rate,
+            bio: row.bio,
+            skills: row.skills.map(|list| list.0),
+            service_area: row.service_area.map(|list| list.0),
+            is_available: row.is_available,
+            avg_rating: row.avg_rating,
+            review_count: row.review_count,
+            total_orders: row.total_orders,
+            completed_orders: row.completed_orders,
+            completion_rate: 0.0,
+        })
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(WorkerPublicProfile::with_completion_rate))
+    }
+}
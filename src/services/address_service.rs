@@ -0,0 +1,167 @@
+//! 地址业务逻辑层
+//!
+//! 用户管理自己的服务地址，每个有地址的用户恰好有一个默认地址
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::models::user::{AddressRequest, UserAddress};
+use crate::repositories::{AddressStore, DeleteAddressOutcome};
+
+/// 每个用户最多保存的地址数
+const MAX_ADDRESSES_PER_USER: i64 = 20;
+
+#[derive(Debug)]
+pub enum AddressServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Conflict(String),
+}
+
+impl fmt::Display for AddressServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            AddressServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            AddressServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            AddressServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AddressServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        AddressServiceError::DatabaseError(error)
+    }
+}
+
+/// 去掉各字段首尾空白，并按 user_addresses 的列长度校验
+fn normalize_address(payload: &AddressRequest) -> Result<AddressRequest, String> {
+    let fields = [
+        ("联系人", payload.contact_name.trim(), 50),
+        ("联系电话", payload.contact_phone.trim(), 20),
+        ("省份", payload.province.trim(), 50),
+        ("城市", payload.city.trim(), 50),
+        ("区县", payload.district.trim(), 50),
+        ("详细地址", payload.street_address.trim(), 200),
+    ];
+    for (name, value, max_chars) in fields {
+        if value.is_empty() {
+            return Err(format!("{}不能为空", name));
+        }
+        if value.chars().count() > max_chars {
+            return Err(format!("{}不能超过{}个字符", name, max_chars));
+        }
+    }
+
+    let phone = payload.contact_phone.trim();
+    let digits = phone.strip_prefix('+').unwrap_or(phone);
+    if digits.len() < 5 || !digits.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err("联系电话格式不正确".to_string());
+    }
+
+    Ok(AddressRequest {
+        contact_name: payload.contact_name.trim().to_string(),
+        contact_phone: phone.to_string(),
+        province: payload.province.trim().to_string(),
+        city: payload.city.trim().to_string(),
+        district: payload.district.trim().to_string(),
+        street_address: payload.street_address.trim().to_string(),
+        is_default: payload.is_default,
+    })
+}
+
+pub struct AddressService {
+    address_repo: Arc<dyn AddressStore>,
+}
+
+impl AddressService {
+    pub fn new(address_store: Arc<dyn AddressStore>) -> Self {
+        Self {
+            address_repo: address_store,
+        }
+    }
+
+    pub async fn list_addresses(&self, user_id: i32) -> Result<Vec<UserAddress>, AddressServiceError> {
+        Ok(self.address_repo.list_by_user(user_id).await?)
+    }
+
+    pub async fn create_address(
+        &self,
+        user_id: i32,
+        payload: &AddressRequest,
+    ) -> Result<UserAddress, AddressServiceError> {
+        let address = normalize_address(payload).map_err(AddressServiceError::ValidationError)?;
+        self.address_repo.create(user_id, &address, MAX_ADDRESSES_PER_USER).await?
+            .ok_or_else(|| AddressServiceError::Conflict(format!("最多只能保存{}个地址", MAX_ADDRESSES_PER_USER)))
+    }
+
+    /// 修改地址，默认地址只能通过将其他地址设为默认来替换，不能直接取消
+    pub async fn update_address(
+        &self,
+        user_id: i32,
+        address_id: i32,
+        payload: &AddressRequest,
+    ) -> Result<UserAddress, AddressServiceError> {
+        let address = normalize_address(payload).map_err(AddressServiceError::ValidationError)?;
+        let current = self.address_repo.find_by_user(user_id, address_id).await?
+            .ok_or_else(|| AddressServiceError::NotFound("地址不存在".to_string()))?;
+        if current.is_default && address.is_default == Some(false) {
+            return Err(AddressServiceError::ValidationError(
+                "不能取消默认地址，请将其他地址设为默认".to_string(),
+            ));
+        }
+
+        self.address_repo.update(user_id, address_id, &address).await?
+            .ok_or_else(|| AddressServiceError::NotFound("地址不存在".to_string()))
+    }
+
+    pub async fn delete_address(&self, user_id: i32, address_id: i32) -> Result<(), AddressServiceError> {
+        match self.address_repo.delete(user_id, address_id).await? {
+            DeleteAddressOutcome::Deleted => Ok(()),
+            DeleteAddressOutcome::NotFound => Err(AddressServiceError::NotFound("地址不存在".to_string())),
+            DeleteAddressOutcome::InUse => {
+                Err(AddressServiceError::Conflict("该地址有进行中的订单，暂不能删除".to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> AddressRequest {
+        AddressRequest {
+            contact_name: " 张三 ".to_string(),
+            contact_phone: "138-0000-0000".to_string(),
+            province: "广东省".to_string(),
+            city: "深圳市".to_string(),
+            district: "南山区".to_string(),
+            street_address: "科技园路1号".to_string(),
+            is_default: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_address() {
+        let address = normalize_address(&request()).unwrap();
+        assert_eq!(address.contact_name, "张三");
+
+        let mut payload = request();
+        payload.contact_phone = "+8613800000000".to_string();
+        assert!(normalize_address(&payload).is_ok());
+
+        payload.contact_phone = "phone".to_string();
+        assert!(normalize_address(&payload).is_err());
+
+        let mut payload = request();
+        payload.district = "  ".to_string();
+        assert!(normalize_address(&payload).is_err());
+
+        let mut payload = request();
+        payload.street_address = "路".repeat(201);
+        assert!(normalize_address(&payload).is_err());
+    }
+}
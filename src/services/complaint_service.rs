@@ -0,0 +1,412 @@
+//! 投诉业务逻辑层
+//!
+//! 客户对订单的服务人员提交投诉，管理员分配处理人、添加内部备注并给出处理结果。
+//! 处理结果可附带退款或优惠券补偿，状态每次变更都会通知投诉人和被投诉人
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::models::order::Order;
+use crate::models::pagination::{Page, PageQuery};
+use crate::models::user::UserType;
+use crate::models::review::{
+    AssignComplaintRequest, Compensation, Complaint, ComplaintNote, ComplaintStatus, CreateComplaintRequest,
+    NewComplaint, ResolveComplaintRequest,
+};
+use crate::repositories::{ComplaintStore, OrderStore, ResolveOutcome, UserStore};
+use crate::services::notifier::Notifier;
+use crate::services::payment_service::{PaymentService, PaymentServiceError};
+
+/// 投诉内容最大字数
+const MAX_COMPLAINT_TEXT_CHARS: usize = 1000;
+
+/// 证据说明、处理结果和内部备注的最大字数
+const MAX_DETAIL_TEXT_CHARS: usize = 2000;
+
+#[derive(Debug)]
+pub enum ComplaintServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Forbidden(String),
+    Conflict(String),
+}
+
+impl fmt::Display for ComplaintServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComplaintServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            ComplaintServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            ComplaintServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            ComplaintServiceError::Forbidden(msg) => write!(f, "无权操作: {}", msg),
+            ComplaintServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ComplaintServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => ComplaintServiceError::NotFound("投诉不存在".to_string()),
+            e => ComplaintServiceError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<PaymentServiceError> for ComplaintServiceError {
+    fn from(error: PaymentServiceError) -> Self {
+        match error {
+            PaymentServiceError::DatabaseError(e) => ComplaintServiceError::DatabaseError(e),
+            e => ComplaintServiceError::Conflict(format!("退款补偿失败: {}", e)),
+        }
+    }
+}
+
+/// 校验文本非空且不超过字数上限
+fn validate_text(name: &str, text: &str, max_chars: usize) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err(format!("{}不能为空", name));
+    }
+    if text.chars().count() > max_chars {
+        return Err(format!("{}不能超过{}字", name, max_chars));
+    }
+    Ok(())
+}
+
+/// 校验处理请求：只能以已解决或已驳回结束，只有已解决可以附带补偿
+fn validate_resolution(request: &ResolveComplaintRequest) -> Result<(), String> {
+    match request.status {
+        ComplaintStatus::Resolved => {}
+        ComplaintStatus::Rejected if request.compensation.is_none() => {}
+        ComplaintStatus::Rejected => return Err("驳回的投诉不能附带补偿".to_string()),
+        _ => return Err("处理结果只能为 resolved 或 rejected".to_string()),
+    }
+    validate_text("处理结果", &request.resolution, MAX_DETAIL_TEXT_CHARS)
+}
+
+pub struct ComplaintService {
+    complaint_repo: Arc<dyn ComplaintStore>,
+    order_repo: Arc<dyn OrderStore>,
+    user_repo: Arc<dyn UserStore>,
+    payment_service: Arc<PaymentService>,
+    notifier: Notifier,
+}
+
+impl ComplaintService {
+    /// 各存储均可替换，订单和用户存储与其他服务共享，测试中可使用内存实现
+    pub fn new(
+        complaint_store: Arc<dyn ComplaintStore>,
+        order_store: Arc<dyn OrderStore>,
+        user_store: Arc<dyn UserStore>,
+        payment_service: Arc<PaymentService>,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            complaint_repo: complaint_store,
+            order_repo: order_store,
+            user_repo: user_store,
+            payment_service,
+            notifier,
+        }
+    }
+
+    /// 客户投诉订单的服务人员
+    /// 同一订单有未结束的投诉时不能重复提交
+    pub async fn create_complaint(
+        &self,
+        customer_id: i32,
+        payload: &CreateComplaintRequest,
+    ) -> Result<Complaint, ComplaintServiceError> {
+        validate_text("投诉内容", &payload.complaint_text, MAX_COMPLAINT_TEXT_CHARS)
+            .map_err(ComplaintServiceError::ValidationError)?;
+        if let Some(evidence) = &payload.evidence_text {
+            validate_text("证据说明", evidence, MAX_DETAIL_TEXT_CHARS).map_err(ComplaintServiceError::ValidationError)?;
+        }
+
+        let order = self.find_order(&payload.order_id).await?;
+        if order.customer_id != customer_id {
+            return Err(ComplaintServiceError::Forbidden("只能投诉自己的订单".to_string()));
+        }
+        let Some(worker_id) = order.worker_id else {
+            return Err(ComplaintServiceError::Conflict("订单未分配服务人员".to_string()));
+        };
+
+        let complaint = NewComplaint {
+            order_id: order.order_id,
+            complainant_id: customer_id,
+            target_id: worker_id,
+            complaint_type: payload.complaint_type,
+            complaint_text: payload.complaint_text.trim().to_string(),
+            evidence_text: payload.evidence_text.as_deref().map(|text| text.trim().to_string()),
+        };
+        let created = self.complaint_repo.create(&complaint).await?
+            .ok_or_else(|| ComplaintServiceError::Conflict("该订单已有处理中的投诉".to_string()))?;
+        self.notifier.complaint_status_changed(&created).await;
+        Ok(created)
+    }
+
+    /// 投诉详情，投诉人、被投诉人和管理员可查看
+    pub async fn get_complaint(
+        &self,
+        complaint_id: i32,
+        user_id: i32,
+        is_admin: bool,
+    ) -> Result<Complaint, ComplaintServiceError> {
+        let complaint = self.complaint_repo.find_by_id(complaint_id).await?;
+        if !is_admin && complaint.complainant_id != user_id && complaint.target_id != user_id {
+            return Err(ComplaintServiceError::Forbidden("无权查看该投诉".to_string()));
+        }
+        Ok(complaint)
+    }
+
+    pub async fn list_my_complaints(&self, customer_id: i32) -> Result<Vec<Complaint>, ComplaintServiceError> {
+        Ok(self.complaint_repo.list_by_complainant(customer_id).await?)
+    }
+
+    /// 管理员投诉队列，可按状态筛选
+    pub async fn list_complaints(
+        &self,
+        status: Option<ComplaintStatus>,
+        query: &PageQuery,
+    ) -> Result<Page<Complaint>, ComplaintServiceError> {
+        let (complaints, total) = self.complaint_repo.list(status, query).await?;
+        Ok(Page::new(complaints, query, total))
+    }
+
+    /// 分配处理人，未指定时分配给当前管理员，处理人必须是管理员
+    /// 只有待处理的投诉进入调查中时才通知双方，重新分配处理人不通知
+    pub async fn assign(
+        &self,
+        complaint_id: i32,
+        admin_id: i32,
+        payload: &AssignComplaintRequest,
+    ) -> Result<Complaint, ComplaintServiceError> {
+        let handler_id = payload.handler_id.unwrap_or(admin_id);
+        if handler_id != admin_id {
+            let handler = match self.user_repo.find_by_id(handler_id).await {
+                Ok(user) => user,
+                Err(sqlx::Error::RowNotFound) => {
+                    return Err(ComplaintServiceError::ValidationError("处理人不存在".to_string()));
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if handler.user_type != UserType::Admin {
+                return Err(ComplaintServiceError::ValidationError("处理人必须是管理员".to_string()));
+            }
+        }
+
+        let (from, complaint) = self.complaint_repo.assign(complaint_id, handler_id).await?
+            .ok_or_else(|| ComplaintServiceError::Conflict("投诉已结束或正在结案".to_string()))?;
+        if from == ComplaintStatus::Pending {
+            self.notifier.complaint_status_changed(&complaint).await;
+        }
+        Ok(complaint)
+    }
+
+    pub async fn add_note(
+        &self,
+        complaint_id: i32,
+        admin_id: i32,
+        note: &str,
+    ) -> Result<ComplaintNote, ComplaintServiceError> {
+        validate_text("备注", note, MAX_DETAIL_TEXT_CHARS).map_err(ComplaintServiceError::ValidationError)?;
+        self.complaint_repo.find_by_id(complaint_id).await?;
+        Ok(self.complaint_repo.add_note(complaint_id, admin_id, note.trim()).await?)
+    }
+
+    pub async fn list_notes(&self, complaint_id: i32) -> Result<Vec<ComplaintNote>, ComplaintServiceError> {
+        self.complaint_repo.find_by_id(complaint_id).await?;
+        Ok(self.complaint_repo.list_notes(complaint_id).await?)
+    }
+
+    /// 结束投诉
+    /// 退款补偿先把投诉置为 resolving 占位再原路退款，退款成功后结案，失败时恢复原状态；
+    /// 优惠券补偿与状态更新在同一事务中发放
+    pub async fn resolve(
+        &self,
+        complaint_id: i32,
+        admin_id: i32,
+        payload: &ResolveComplaintRequest,
+    ) -> Result<Complaint, ComplaintServiceError> {
+        validate_resolution(payload).map_err(ComplaintServiceError::ValidationError)?;
+
+        let complaint = self.complaint_repo.find_by_id(complaint_id).await?;
+        let from = complaint.status;
+        if !from.can_transition_to(payload.status) {
+            return Err(ComplaintServiceError::Conflict(format!(
+                "投诉状态为 {}，不能变更为 {}",
+                from, payload.status
+            )));
+        }
+
+        // 退款补偿先占用投诉，只有占用成功的请求会发起退款
+        let refund = payload.compensation == Some(Compensation::Refund);
+        let from = if refund {
+            if !self.complaint_repo.claim_for_resolution(complaint_id, from).await? {
+                return Err(ComplaintServiceError::Conflict("投诉状态已变更，请刷新后重试".to_string()));
+            }
+            if let Err(e) = self.payment_service.refund_paid_order(&complaint.order_id, admin_id).await {
+                self.complaint_repo.release_claim(complaint_id, from).await?;
+                return Err(e.into());
+            }
+            ComplaintStatus::Resolving
+        } else {
+            from
+        };
+
+        let today = chrono::Local::now().date_naive();
+        match self.complaint_repo.resolve(complaint_id, from, admin_id, payload, today).await? {
+            ResolveOutcome::Resolved(complaint) => {
+                self.notifier.complaint_status_changed(&complaint).await;
+                Ok(*complaint)
+            }
+            ResolveOutcome::StatusChanged => {
+                if refund {
+                    tracing::error!("投诉 {} 已退款但状态已被修改，请人工核对", complaint_id);
+                }
+                Err(ComplaintServiceError::Conflict("投诉状态已变更，请刷新后重试".to_string()))
+            }
+            ResolveOutcome::CouponNotAvailable => {
+                Err(ComplaintServiceError::Conflict("补偿优惠券不可用".to_string()))
+            }
+        }
+    }
+
+    async fn find_order(&self, order_id: &str) -> Result<Order, ComplaintServiceError> {
+        match self.order_repo.find_by_id(order_id.to_string()).await {
+            Ok(order) => Ok(order),
+            Err(sqlx::Error::RowNotFound) => Err(ComplaintServiceError::NotFound("订单不存在".to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+    use sqlx::mysql::MySqlPoolOptions;
+
+    use crate::models::coupon::{Coupon, DiscountType};
+    use crate::models::review::{CompensationType, ComplaintType};
+    use crate::models::Money;
+    use crate::repositories::{
+        ClaimOutcome, CouponStore, InMemoryComplaintStore, InMemoryCouponStore, InMemoryOrderStore, InMemoryPaymentStore,
+        InMemoryUserStore,
+    };
+    use crate::services::event_hub::InMemoryEventHub;
+    use crate::services::payment_provider::MockPaymentProvider;
+
+    /// 使用上限 5 次，有效期为今天起 30 天
+    fn coupon(coupon_id: i32, used_count: i32, today: NaiveDate) -> Coupon {
+        Coupon {
+            coupon_id,
+            coupon_code: format!("COMP{}", coupon_id),
+            coupon_name: "投诉补偿券".to_string(),
+            discount_type: DiscountType::Fixed,
+            discount_value: Money::from_yuan(20),
+            min_order_amount: Money::ZERO,
+            applicable_services: None,
+            valid_from: today,
+            valid_until: today + chrono::Duration::days(30),
+            usage_limit: 5,
+            used_count,
+            is_active: true,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    fn complaint(status: ComplaintStatus) -> Complaint {
+        Complaint {
+            complaint_id: 12,
+            order_id: "20240101000001".to_string(),
+            complainant_id: 7,
+            target_id: 3,
+            complaint_type: ComplaintType::Punctuality,
+            complaint_text: "迟到一小时".to_string(),
+            status,
+            evidence_text: None,
+            handler_id: Some(1),
+            resolution: None,
+            compensation_type: CompensationType::None,
+            compensation_coupon_id: None,
+            resolved_at: None,
+            created_at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    fn service(coupons: Arc<InMemoryCouponStore>, complaints: Vec<Complaint>) -> ComplaintService {
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("mysql://jz:jz@127.0.0.1:1/jz_test")
+            .unwrap();
+        let notifier = Notifier::new(pool, Arc::new(InMemoryEventHub::default()));
+        let orders = Arc::new(InMemoryOrderStore::new());
+        let payment_service = PaymentService::new(
+            Arc::new(InMemoryPaymentStore::new(orders.clone())),
+            orders.clone(),
+            Arc::new(MockPaymentProvider::default()),
+            notifier.clone(),
+        );
+        ComplaintService::new(
+            Arc::new(InMemoryComplaintStore::with_complaints(coupons, complaints)),
+            orders,
+            Arc::new(InMemoryUserStore::new()),
+            Arc::new(payment_service),
+            notifier,
+        )
+    }
+
+    fn resolution(status: ComplaintStatus, compensation: Option<Compensation>) -> ResolveComplaintRequest {
+        ResolveComplaintRequest {
+            status,
+            resolution: "已与服务人员核实".to_string(),
+            compensation,
+        }
+    }
+
+    #[test]
+    fn test_validate_text() {
+        assert!(validate_text("投诉内容", "迟到", 10).is_ok());
+        assert!(validate_text("投诉内容", "   ", 10).is_err());
+        assert!(validate_text("投诉内容", &"字".repeat(11), 10).is_err());
+    }
+
+    #[test]
+    fn test_validate_resolution() {
+        let coupon = Some(Compensation::Coupon { coupon_id: 1 });
+        assert!(validate_resolution(&resolution(ComplaintStatus::Resolved, None)).is_ok());
+        assert!(validate_resolution(&resolution(ComplaintStatus::Resolved, coupon)).is_ok());
+        assert!(validate_resolution(&resolution(ComplaintStatus::Rejected, None)).is_ok());
+        assert!(validate_resolution(&resolution(ComplaintStatus::Rejected, Some(Compensation::Refund))).is_err());
+        assert!(validate_resolution(&resolution(ComplaintStatus::Investigating, None)).is_err());
+
+        let mut empty = resolution(ComplaintStatus::Resolved, None);
+        empty.resolution = String::new();
+        assert!(validate_resolution(&empty).is_err());
+    }
+
+    // 补偿优惠券与用户领取遵循相同的有效期和使用上限规则：已达上限时不发放，投诉保持原状态
+    #[tokio::test]
+    async fn test_coupon_compensation_at_usage_limit() {
+        let today = chrono::Local::now().date_naive();
+        let coupons = Arc::new(InMemoryCouponStore::with_coupons(vec![coupon(1, 5, today), coupon(2, 0, today)]));
+        let service = service(coupons.clone(), vec![complaint(ComplaintStatus::Investigating)]);
+
+        assert!(matches!(coupons.claim(7, 1, today).await.unwrap(), ClaimOutcome::NotAvailable));
+        let exhausted = resolution(ComplaintStatus::Resolved, Some(Compensation::Coupon { coupon_id: 1 }));
+        assert!(matches!(service.resolve(12, 1, &exhausted).await, Err(ComplaintServiceError::Conflict(_))));
+        assert_eq!(service.get_complaint(12, 1, true).await.unwrap().status, ComplaintStatus::Investigating);
+        assert!(coupons.list_user_coupons(7).await.unwrap().is_empty());
+
+        let available = resolution(ComplaintStatus::Resolved, Some(Compensation::Coupon { coupon_id: 2 }));
+        let resolved = service.resolve(12, 1, &available).await.unwrap();
+        assert_eq!(resolved.status, ComplaintStatus::Resolved);
+        assert_eq!(resolved.compensation_coupon_id, Some(2));
+        let granted = coupons.list_user_coupons(7).await.unwrap();
+        assert_eq!(granted.len(), 1);
+        assert_eq!(granted[0].expires_at, today + chrono::Duration::days(30));
+    }
+}
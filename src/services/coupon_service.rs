@@ -0,0 +1,69 @@
+//! 优惠券业务逻辑层
+//!
+//! 处理优惠券的查询和领取。
+//! 下单时的优惠券校验与核销由订单业务层完成，取消订单时优惠券随订单一起退还
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::models::coupon::{Coupon, UserCoupon, UserCouponDetail};
+use crate::repositories::{ClaimOutcome, CouponStore};
+
+#[derive(Debug)]
+pub enum CouponServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    Conflict(String),
+}
+
+impl fmt::Display for CouponServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CouponServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            CouponServiceError::NotFound(msg) => write!(f, "资源不存在: {}", msg),
+            CouponServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for CouponServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        CouponServiceError::DatabaseError(error)
+    }
+}
+
+pub struct CouponService {
+    coupon_repo: Arc<dyn CouponStore>,
+}
+
+impl CouponService {
+    pub fn new(coupon_store: Arc<dyn CouponStore>) -> Self {
+        Self {
+            coupon_repo: coupon_store,
+        }
+    }
+
+    /// 当前可领取的优惠券
+    pub async fn list_available(&self) -> Result<Vec<Coupon>, CouponServiceError> {
+        let today = chrono::Local::now().date_naive();
+        Ok(self.coupon_repo.list_available(today).await?)
+    }
+
+    /// 领取优惠券
+    pub async fn claim(&self, user_id: i32, coupon_id: i32) -> Result<UserCoupon, CouponServiceError> {
+        let today = chrono::Local::now().date_naive();
+        match self.coupon_repo.claim(user_id, coupon_id, today).await? {
+            ClaimOutcome::Claimed(user_coupon) => {
+                tracing::info!("用户 {} 领取优惠券 {}", user_id, coupon_id);
+                Ok(user_coupon)
+            }
+            ClaimOutcome::NotAvailable => Err(CouponServiceError::NotFound("优惠券不存在、已过期或已领完".to_string())),
+            ClaimOutcome::AlreadyClaimed => Err(CouponServiceError::Conflict("已领取过该优惠券".to_string())),
+        }
+    }
+
+    /// 我的优惠券
+    pub async fn list_mine(&self, user_id: i32) -> Result<Vec<UserCouponDetail>, CouponServiceError> {
+        Ok(self.coupon_repo.list_user_coupons(user_id).await?)
+    }
+}
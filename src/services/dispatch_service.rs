@@ -0,0 +1,171 @@
+//! 派单业务逻辑层
+//!
+//! 为订单筛选并排序候选服务人员，支持自动派单和管理员手动派单
+
+use std::sync::Arc;
+
+use sqlx::mysql::MySqlPool;
+
+use crate::models::{order::{Order, OrderStatus}, worker::WorkerCandidate};
+use crate::repositories::{AssignOutcome, OrderStore, WorkerRepository};
+use crate::services::notifier::Notifier;
+use crate::services::order_service::OrderServiceError;
+
+/// 评分在综合得分中的权重，其余权重给当日负载
+const RATING_WEIGHT: f64 = 0.7;
+const LOAD_WEIGHT: f64 = 1.0 - RATING_WEIGHT;
+
+/// 计算候选人综合得分
+/// 评分按 5 分制归一化，负载按当日剩余接单能力归一化
+pub fn candidate_score(candidate: &WorkerCandidate) -> f64 {
+    let rating = (candidate.avg_rating / 5.0).clamp(0.0, 1.0);
+    let load = if candidate.max_daily_orders > 0 {
+        1.0 - (candidate.daily_orders as f64 / candidate.max_daily_orders as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    RATING_WEIGHT * rating + LOAD_WEIGHT * load
+}
+
+/// 按综合得分从高到低排序，得分相同时当日订单少者优先
+pub fn rank_candidates(mut candidates: Vec<WorkerCandidate>) -> Vec<WorkerCandidate> {
+    for candidate in candidates.iter_mut() {
+        candidate.score = candidate_score(candidate);
+    }
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.daily_orders.cmp(&b.daily_orders))
+            .then(a.worker_id.cmp(&b.worker_id))
+    });
+    candidates
+}
+
+pub struct DispatchService {
+    order_repo: Arc<dyn OrderStore>,
+    worker_repo: WorkerRepository,
+    notifier: Notifier,
+}
+
+impl DispatchService {
+    /// 订单存储与其他服务共享，测试中可使用内存实现
+    pub fn new(pool: MySqlPool, order_store: Arc<dyn OrderStore>, notifier: Notifier) -> Self {
+        Self {
+            order_repo: order_store,
+            worker_repo: WorkerRepository::new(pool),
+            notifier,
+        }
+    }
+
+    /// 获取订单的候选服务人员，按优先级排序
+    pub async fn list_candidates(&self, order_id: &str) -> Result<Vec<WorkerCandidate>, OrderServiceError> {
+        self.ensure_assignable(order_id).await?;
+        let candidates = self.worker_repo.find_dispatch_candidates(order_id).await?;
+        Ok(rank_candidates(candidates))
+    }
+
+    /// 自动派单
+    /// 依次尝试排名靠前的候选人，候选人的日程被并发占用时顺延到下一位
+    pub async fn auto_assign(&self, order_id: &str, operator_id: i32) -> Result<Order, OrderServiceError> {
+        let candidates = self.list_candidates(order_id).await?;
+        for candidate in &candidates {
+            match self.try_assign(order_id, candidate, operator_id).await? {
+                Some(order) => return Ok(order),
+                None => continue,
+            }
+        }
+        Err(OrderServiceError::Conflict("暂无可派单的服务人员".to_string()))
+    }
+
+    /// 管理员手动派单，指定的服务人员必须满足派单条件
+    pub async fn assign(&self, order_id: &str, worker_id: i32, operator_id: i32) -> Result<Order, OrderServiceError> {
+        let candidates = self.list_candidates(order_id).await?;
+        let candidate = candidates
+            .iter()
+            .find(|c| c.worker_id == worker_id)
+            .ok_or_else(|| OrderServiceError::Conflict("该服务人员不满足派单条件或当前时段不可用".to_string()))?;
+
+        self.try_assign(order_id, candidate, operator_id)
+            .await?
+            .ok_or_else(|| OrderServiceError::Conflict("该服务人员当前时段已被预订".to_string()))
+    }
+
+    /// 尝试将订单指派给候选人，候选人不可用时返回 None
+    async fn try_assign(
+        &self,
+        order_id: &str,
+        candidate: &WorkerCandidate,
+        operator_id: i32,
+    ) -> Result<Option<Order>, OrderServiceError> {
+        let outcome = self.order_repo
+            .assign_worker(order_id, candidate.worker_id, candidate.schedule_id, operator_id)
+            .await?;
+        match outcome {
+            AssignOutcome::Assigned(order) => {
+                tracing::info!("订单 {} 已指派给服务人员 {} 操作人: {}", order_id, candidate.worker_id, operator_id);
+                self.notifier.order_status_changed(&order).await;
+                Ok(Some(*order))
+            }
+            AssignOutcome::OrderNotAssignable => {
+                Err(OrderServiceError::Conflict("订单状态已变更，无法派单".to_string()))
+            }
+            AssignOutcome::SlotUnavailable | AssignOutcome::DailyLimitReached => {
+                tracing::info!("服务人员 {} 不可用，订单 {} 尝试下一位候选人", candidate.worker_id, order_id);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn ensure_assignable(&self, order_id: &str) -> Result<(), OrderServiceError> {
+        let order = match self.order_repo.find_by_id(order_id.to_string()).await {
+            Ok(order) => order,
+            Err(sqlx::Error::RowNotFound) => return Err(OrderServiceError::NotFound("订单不存在".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+        if order.order_status != OrderStatus::Confirmed {
+            return Err(OrderServiceError::Conflict(format!(
+                "订单状态为 {}，只有已确认的订单可以派单",
+                order.order_status
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(worker_id: i32, avg_rating: f64, daily_orders: i64, max_daily_orders: i32) -> WorkerCandidate {
+        WorkerCandidate {
+            worker_id,
+            username: format!("worker{}", worker_id),
+            avg_rating,
+            daily_orders,
+            max_daily_orders,
+            schedule_id: worker_id * 10,
+            score: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_higher_rating_ranks_first() {
+        let ranked = rank_candidates(vec![candidate(1, 4.0, 0, 3), candidate(2, 4.9, 0, 3)]);
+        assert_eq!(ranked[0].worker_id, 2);
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[test]
+    fn test_load_breaks_close_ratings() {
+        // 评分接近时，当日负载低的服务人员优先
+        let ranked = rank_candidates(vec![candidate(1, 4.8, 2, 3), candidate(2, 4.7, 0, 3)]);
+        assert_eq!(ranked[0].worker_id, 2);
+    }
+
+    #[test]
+    fn test_equal_score_prefers_fewer_orders_then_id() {
+        let ranked = rank_candidates(vec![candidate(3, 4.5, 1, 2), candidate(1, 4.5, 2, 4), candidate(2, 4.5, 1, 2)]);
+        let ids: Vec<i32> = ranked.iter().map(|c| c.worker_id).collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+    }
+}
@@ -0,0 +1,252 @@
+//! 实时事件中心
+//!
+//! 业务代码通过 `EventHub` 向指定用户发布事件，SSE 连接订阅当前用户的事件流。
+//! `InMemoryEventHub` 是进程内实现，只能推送给连接到本进程的客户端；
+//! 多实例部署时可替换为基于共享消息代理的实现。
+//! 补发缓冲只为在线或最近断开的用户保留，断开超过 `IDLE_CHANNEL_TTL` 的用户通道会被清理
+
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::models::event::{EventPayload, RealtimeEvent};
+
+/// 每个用户保留的最近事件数，用于断线重连补发
+const REPLAY_BUFFER_SIZE: usize = 100;
+
+/// 每个用户广播通道的容量，订阅端积压超过该值时断开，由客户端重连补发
+const CHANNEL_CAPACITY: usize = 64;
+
+/// 用户全部连接断开后保留通道和补发缓冲的时长
+const IDLE_CHANNEL_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 清理空闲通道的最小间隔
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 某个用户的事件流
+pub type EventStream = Pin<Box<dyn Stream<Item = RealtimeEvent> + Send>>;
+
+/// 事件中心接口
+#[async_trait]
+pub trait EventHub: Send + Sync {
+    /// 向用户发布事件，用户最近断开时只保留在补发缓冲中，从未连接或断开已久时丢弃
+    async fn publish(&self, user_id: i32, payload: EventPayload);
+
+    /// 订阅用户的事件流
+    /// 传入 last_event_id 时先补发该ID之后仍在缓冲中的事件，再接收新事件
+    async fn subscribe(&self, user_id: i32, last_event_id: Option<u64>) -> EventStream;
+}
+
+struct UserChannel {
+    sender: broadcast::Sender<RealtimeEvent>,
+    recent: VecDeque<RealtimeEvent>,
+    /// 最近一次确认有订阅者的时间
+    last_seen: Instant,
+}
+
+impl UserChannel {
+    fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            recent: VecDeque::with_capacity(REPLAY_BUFFER_SIZE),
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// 有订阅者时刷新 last_seen，返回没有订阅者的时长是否已超过 ttl
+    fn is_idle(&mut self, now: Instant, ttl: Duration) -> bool {
+        if self.sender.receiver_count() > 0 {
+            self.last_seen = now;
+            return false;
+        }
+        now.duration_since(self.last_seen) >= ttl
+    }
+}
+
+struct HubState {
+    last_id: u64,
+    channels: HashMap<i32, UserChannel>,
+    last_sweep: Instant,
+}
+
+impl HubState {
+    /// 清理空闲超过 ttl 的通道，两次清理至少间隔 SWEEP_INTERVAL 与 ttl 中的较小值
+    fn sweep(&mut self, ttl: Duration) {
+        let now = Instant::now();
+        if now.duration_since(self.last_sweep) < SWEEP_INTERVAL.min(ttl) {
+            return;
+        }
+        self.last_sweep = now;
+        self.channels.retain(|_, channel| !channel.is_idle(now, ttl));
+    }
+}
+
+/// 进程内事件中心，按 user_id 分别广播
+/// 事件ID从进程启动时的微秒时间戳开始递增，重启后新事件的ID仍大于重启前的ID
+pub struct InMemoryEventHub {
+    state: Mutex<HubState>,
+    idle_ttl: Duration,
+}
+
+impl Default for InMemoryEventHub {
+    fn default() -> Self {
+        let start = chrono::Utc::now().timestamp_micros().max(0) as u64;
+        Self::starting_at(start)
+    }
+}
+
+impl InMemoryEventHub {
+    /// 指定起始事件ID，第一个事件的ID为 last_id + 1
+    pub fn starting_at(last_id: u64) -> Self {
+        Self {
+            state: Mutex::new(HubState { last_id, channels: HashMap::new(), last_sweep: Instant::now() }),
+            idle_ttl: IDLE_CHANNEL_TTL,
+        }
+    }
+
+    /// 指定用户断开后保留补发缓冲的时长
+    pub fn with_idle_ttl(mut self, idle_ttl: Duration) -> Self {
+        self.idle_ttl = idle_ttl;
+        self
+    }
+
+    /// 当前保留的用户通道数
+    pub fn channel_count(&self) -> usize {
+        self.state.lock().unwrap().channels.len()
+    }
+}
+
+#[async_trait]
+impl EventHub for InMemoryEventHub {
+    async fn publish(&self, user_id: i32, payload: EventPayload) {
+        let mut state = self.state.lock().unwrap();
+        state.sweep(self.idle_ttl);
+        state.last_id += 1;
+        let event = RealtimeEvent { id: state.last_id, user_id, payload };
+
+        // 只为在线或最近断开的用户缓冲，其余用户上线后从通知列表获取
+        let Some(channel) = state.channels.get_mut(&user_id) else {
+            return;
+        };
+        if channel.recent.len() == REPLAY_BUFFER_SIZE {
+            channel.recent.pop_front();
+        }
+        channel.recent.push_back(event.clone());
+        // 没有在线的订阅者时发送失败，事件仍保留在补发缓冲中
+        let _ = channel.sender.send(event);
+    }
+
+    async fn subscribe(&self, user_id: i32, last_event_id: Option<u64>) -> EventStream {
+        // 在同一把锁内取补发事件和订阅通道，保证补发和实时事件之间不丢不重
+        let mut state = self.state.lock().unwrap();
+        state.sweep(self.idle_ttl);
+        let channel = state.channels.entry(user_id).or_insert_with(UserChannel::new);
+        channel.last_seen = Instant::now();
+        let missed: Vec<RealtimeEvent> = match last_event_id {
+            Some(last_id) => channel.recent.iter().filter(|e| e.id > last_id).cloned().collect(),
+            None => Vec::new(),
+        };
+        let live = BroadcastStream::new(channel.sender.subscribe()).map_while(Result::ok);
+        Box::pin(tokio_stream::iter(missed).chain(live))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::models::event::OrderStatusEvent;
+    use crate::models::order::OrderStatus;
+
+    fn order_event(order_id: &str) -> EventPayload {
+        EventPayload::OrderStatus(OrderStatusEvent {
+            order_id: order_id.to_string(),
+            order_status: OrderStatus::Assigned,
+            worker_id: Some(3),
+            updated_at: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(9, 0, 0).unwrap(),
+        })
+    }
+
+    fn order_id(event: &RealtimeEvent) -> &str {
+        match &event.payload {
+            EventPayload::OrderStatus(e) => &e.order_id,
+            EventPayload::Notification(_) => panic!("unexpected notification"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_events_are_delivered_per_user() {
+        let hub = InMemoryEventHub::starting_at(0);
+        let mut worker = hub.subscribe(3, None).await;
+        let mut customer = hub.subscribe(7, None).await;
+
+        hub.publish(3, order_event("A")).await;
+        hub.publish(7, order_event("B")).await;
+
+        let event = worker.next().await.unwrap();
+        assert_eq!((event.id, event.user_id, order_id(&event)), (1, 3, "A"));
+        let event = customer.next().await.unwrap();
+        assert_eq!((event.id, event.user_id, order_id(&event)), (2, 7, "B"));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_replays_missed_events() {
+        let hub = InMemoryEventHub::starting_at(0);
+        drop(hub.subscribe(3, None).await);
+        hub.publish(3, order_event("A")).await;
+        hub.publish(3, order_event("B")).await;
+        hub.publish(3, order_event("C")).await;
+
+        let mut stream = hub.subscribe(3, Some(1)).await;
+        hub.publish(3, order_event("D")).await;
+
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            received.push(stream.next().await.unwrap().id);
+        }
+        assert_eq!(received, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_buffer_is_bounded() {
+        let hub = InMemoryEventHub::starting_at(0);
+        drop(hub.subscribe(3, None).await);
+        for i in 0..REPLAY_BUFFER_SIZE + 5 {
+            hub.publish(3, order_event(&i.to_string())).await;
+        }
+
+        let mut stream = hub.subscribe(3, Some(0)).await;
+        assert_eq!(stream.next().await.unwrap().id, 6);
+    }
+
+    #[tokio::test]
+    async fn test_users_never_connected_are_not_buffered() {
+        let hub = InMemoryEventHub::starting_at(0);
+        hub.publish(3, order_event("A")).await;
+        assert_eq!(hub.channel_count(), 0);
+
+        let mut stream = hub.subscribe(3, Some(0)).await;
+        hub.publish(3, order_event("B")).await;
+        assert_eq!(order_id(&stream.next().await.unwrap()), "B");
+    }
+
+    #[tokio::test]
+    async fn test_idle_channels_are_evicted() {
+        let hub = InMemoryEventHub::starting_at(0).with_idle_ttl(Duration::ZERO);
+        let online = hub.subscribe(3, None).await;
+        drop(hub.subscribe(7, None).await);
+        assert_eq!(hub.channel_count(), 2);
+
+        hub.publish(3, order_event("A")).await;
+        assert_eq!(hub.channel_count(), 1);
+        drop(online);
+        hub.publish(3, order_event("B")).await;
+        assert_eq!(hub.channel_count(), 0);
+    }
+}
@@ -0,0 +1,73 @@
+//! 通知中心业务逻辑层
+//!
+//! 负责用户查看和标记自己的站内通知，通知的发送见 `Notifier`
+
+use std::fmt;
+
+use sqlx::mysql::MySqlPool;
+
+use crate::models::notification::{MarkAllReadResult, Notification, NotificationType, UnreadCount};
+use crate::models::pagination::{CursorPage, CursorQuery};
+use crate::repositories::NotificationRepository;
+
+#[derive(Debug)]
+pub enum NotificationServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+}
+
+impl fmt::Display for NotificationServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotificationServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            NotificationServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for NotificationServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        NotificationServiceError::DatabaseError(error)
+    }
+}
+
+pub struct NotificationService {
+    notification_repo: NotificationRepository,
+}
+
+impl NotificationService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            notification_repo: NotificationRepository::new(pool),
+        }
+    }
+
+    /// 用户的通知，按时间倒序游标分页，可按类型筛选
+    pub async fn list_notifications(
+        &self,
+        user_id: i32,
+        notification_type: Option<NotificationType>,
+        query: &CursorQuery,
+    ) -> Result<CursorPage<Notification>, NotificationServiceError> {
+        let notifications = self.notification_repo.list_by_user(user_id, notification_type, query).await?;
+        Ok(CursorPage::from_overfetch(notifications, query, |n| i64::from(n.notification_id)))
+    }
+
+    pub async fn unread_count(&self, user_id: i32) -> Result<UnreadCount, NotificationServiceError> {
+        let unread_count = self.notification_repo.count_unread(user_id).await?;
+        Ok(UnreadCount { unread_count })
+    }
+
+    pub async fn mark_read(&self, user_id: i32, notification_id: i32) -> Result<(), NotificationServiceError> {
+        if self.notification_repo.mark_read(user_id, notification_id).await? {
+            Ok(())
+        } else {
+            Err(NotificationServiceError::NotFound("通知不存在".to_string()))
+        }
+    }
+
+    pub async fn mark_all_read(&self, user_id: i32) -> Result<MarkAllReadResult, NotificationServiceError> {
+        let updated = self.notification_repo.mark_all_read(user_id).await?;
+        Ok(MarkAllReadResult { updated })
+    }
+}
@@ -0,0 +1,223 @@
+//! 站内通知发送
+//!
+//! 订单、支付和投诉的业务代码在状态变更成功后调用 `Notifier`。
+//! 通知写入数据库后同时通过 `EventHub` 实时推送给在线用户。
+//! 通知是尽力而为的：写入失败只记录日志，不影响已经完成的业务操作
+
+use std::sync::Arc;
+
+use sqlx::mysql::MySqlPool;
+
+use crate::models::event::{EventPayload, OrderStatusEvent};
+use crate::models::notification::{NewNotification, NotificationType};
+use crate::models::order::{Order, OrderStatus};
+use crate::models::payment::{Payment, PaymentMethod};
+use crate::models::review::Complaint;
+use crate::models::worker::WorkerApplication;
+use crate::repositories::NotificationRepository;
+use crate::services::event_hub::EventHub;
+
+fn order_notification(user_id: i32, order_id: &str, title: &str, content: String) -> NewNotification {
+    NewNotification {
+        user_id,
+        notification_type: NotificationType::Order,
+        title: title.to_string(),
+        content,
+        related_id: Some(order_id.to_string()),
+    }
+}
+
+/// 订单进入当前状态时需要发送的通知
+/// 客户在每次状态推进时收到通知，服务人员在被指派和订单取消时收到通知
+pub fn order_status_notifications(order: &Order) -> Vec<NewNotification> {
+    let status = order.order_status;
+    let id = &order.order_id;
+    let schedule = format!("{} {}", order.service_date, order.time_slot.label());
+
+    let mut notifications = Vec::new();
+    match status {
+        OrderStatus::Pending => {}
+        OrderStatus::Confirmed => {
+            notifications.push(order_notification(
+                order.customer_id, id, "订单已确认",
+                format!("您的订单 {} 已确认，平台正在为您安排服务人员", id),
+            ));
+        }
+        OrderStatus::Assigned => {
+            notifications.push(order_notification(
+                order.customer_id, id, "服务人员已接单",
+                format!("您的订单 {} 已安排服务人员，服务时间 {}", id, schedule),
+            ));
+            if let Some(worker_id) = order.worker_id {
+                notifications.push(order_notification(
+                    worker_id, id, "您有新的订单",
+                    format!("订单 {} 已指派给您，服务时间 {}", id, schedule),
+                ));
+            }
+        }
+        OrderStatus::Ongoing => {
+            notifications.push(order_notification(
+                order.customer_id, id, "服务已开始",
+                format!("您的订单 {} 服务已开始", id),
+            ));
+        }
+        OrderStatus::Completed => {
+            notifications.push(order_notification(
+                order.customer_id, id, "服务已完成",
+                format!("您的订单 {} 服务已完成，欢迎对本次服务进行评价", id),
+            ));
+        }
+        OrderStatus::Cancelled => {
+            let reason = order.cancellation_reason.as_deref().unwrap_or("无");
+            notifications.push(order_notification(
+                order.customer_id, id, "订单已取消",
+                format!("您的订单 {} 已取消，取消原因：{}", id, reason),
+            ));
+            if let Some(worker_id) = order.worker_id {
+                notifications.push(order_notification(
+                    worker_id, id, "订单已取消",
+                    format!("订单 {} ({}) 已取消，无需上门服务", id, schedule),
+                ));
+            }
+        }
+    }
+    notifications
+}
+
+#[derive(Clone)]
+pub struct Notifier {
+    notification_repo: NotificationRepository,
+    hub: Arc<dyn EventHub>,
+}
+
+impl Notifier {
+    /// 通知写入数据库，并通过事件中心推送给在线用户
+    pub fn new(pool: MySqlPool, hub: Arc<dyn EventHub>) -> Self {
+        Self {
+            notification_repo: NotificationRepository::new(pool),
+            hub,
+        }
+    }
+
+    /// 发送一批通知并推送给在线用户，失败时只记录日志
+    pub async fn send(&self, notifications: &[NewNotification]) {
+        if notifications.is_empty() {
+            return;
+        }
+        match self.notification_repo.create_many(notifications).await {
+            Ok(created) => {
+                for notification in created {
+                    self.hub.publish(notification.user_id, EventPayload::Notification(notification)).await;
+                }
+            }
+            Err(e) => tracing::error!("发送通知失败: {:?}", e),
+        }
+    }
+
+    /// 订单状态变更，向客户和服务人员推送状态事件并发送通知
+    pub async fn order_status_changed(&self, order: &Order) {
+        let event = OrderStatusEvent {
+            order_id: order.order_id.clone(),
+            order_status: order.order_status,
+            worker_id: order.worker_id,
+            updated_at: order.updated_at,
+        };
+        for user_id in std::iter::once(order.customer_id).chain(order.worker_id) {
+            self.hub.publish(user_id, EventPayload::OrderStatus(event.clone())).await;
+        }
+        self.send(&order_status_notifications(order)).await;
+    }
+
+    /// 订单支付成功
+    pub async fn payment_succeeded(&self, payment: &Payment) {
+        let notification = order_notification(
+            payment.user_id, &payment.order_id, "支付成功",
+            format!("订单 {} 已支付 {} 元", payment.order_id, payment.payment_amount),
+        );
+        self.send(&[notification]).await;
+    }
+
+    /// 订单退款成功
+    pub async fn payment_refunded(&self, payment: &Payment) {
+        let content = if payment.payment_method == PaymentMethod::Balance {
+            format!("订单 {} 已退款 {} 元，款项已退回账户余额", payment.order_id, payment.payment_amount)
+        } else {
+            format!("订单 {} 已退款 {} 元，款项将原路退回", payment.order_id, payment.payment_amount)
+        };
+        let notification = order_notification(payment.user_id, &payment.order_id, "退款成功", content);
+        self.send(&[notification]).await;
+    }
+
+    /// 投诉状态变更，通知投诉人和被投诉人
+    pub async fn complaint_status_changed(&self, complaint: &Complaint) {
+        self.send(&complaint.status_notifications()).await;
+    }
+
+    /// 入驻申请审核完成
+    pub async fn worker_application_reviewed(&self, application: &WorkerApplication) {
+        if let Some(notification) = application.review_notification() {
+            self.send(&[notification]).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::models::money::Money;
+    use crate::models::order::OrderPaymentStatus;
+    use crate::models::worker::TimeSlot;
+
+    fn order(status: OrderStatus, worker_id: Option<i32>) -> Order {
+        let now = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(8, 0, 0).unwrap();
+        Order {
+            order_id: "20240101000001".to_string(),
+            customer_id: 7,
+            worker_id,
+            address_id: 1,
+            service_id: 1,
+            coupon_id: None,
+            service_date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            time_slot: TimeSlot::Morning,
+            duration: 2.0,
+            unit_price: Money::from_yuan(50),
+            subtotal: Money::from_yuan(100),
+            discount_amount: Money::ZERO,
+            total_amount: Money::from_yuan(100),
+            payment_status: OrderPaymentStatus::Paid,
+            order_status: status,
+            special_instructions: None,
+            cancellation_reason: Some("行程有变".to_string()),
+            scheduled_start_time: None,
+            actual_start_time: None,
+            actual_end_time: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn recipients(notifications: &[NewNotification]) -> Vec<i32> {
+        notifications.iter().map(|n| n.user_id).collect()
+    }
+
+    #[test]
+    fn test_order_status_notifications() {
+        assert!(order_status_notifications(&order(OrderStatus::Pending, None)).is_empty());
+        assert_eq!(recipients(&order_status_notifications(&order(OrderStatus::Confirmed, None))), vec![7]);
+
+        let assigned = order_status_notifications(&order(OrderStatus::Assigned, Some(3)));
+        assert_eq!(recipients(&assigned), vec![7, 3]);
+        assert!(assigned[1].content.contains("2024-01-02 上午"));
+        assert!(assigned.iter().all(|n| n.notification_type == NotificationType::Order));
+        assert!(assigned.iter().all(|n| n.related_id.as_deref() == Some("20240101000001")));
+
+        assert_eq!(recipients(&order_status_notifications(&order(OrderStatus::Ongoing, Some(3)))), vec![7]);
+        assert_eq!(recipients(&order_status_notifications(&order(OrderStatus::Completed, Some(3)))), vec![7]);
+
+        let cancelled = order_status_notifications(&order(OrderStatus::Cancelled, Some(3)));
+        assert_eq!(recipients(&cancelled), vec![7, 3]);
+        assert!(cancelled[0].content.contains("行程有变"));
+        assert_eq!(recipients(&order_status_notifications(&order(OrderStatus::Cancelled, None))), vec![7]);
+    }
+}
@@ -2,18 +2,79 @@
 //!
 //! 处理订单相关的业务逻辑
 
-use sqlx::mysql::MySqlPool;
-use crate::repositories::OrderRepository;
-use crate::models::order::Order;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use crate::repositories::{CouponStore, CreateOrderOutcome, OrderStore, ServiceStore};
+use crate::models::coupon::{Coupon, CouponPreviewRequest, UserCoupon};
+use crate::models::order::{
+    CreateOrderRequest, NewOrder, NewOrderAddon, Order, OrderAddonRequest, OrderStatus, OrderStatusHistory,
+};
+use crate::models::service::{Service, ServiceAddon};
+use crate::models::worker::TimeSlot;
+use crate::services::notifier::Notifier;
+use crate::services::pricing::{self, PriceBreakdown};
+
+#[derive(Debug)]
+pub enum OrderServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    /// 无权操作该订单
+    Forbidden(String),
+    /// 订单当前状态不允许该操作
+    Conflict(String),
+}
+
+impl fmt::Display for OrderServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            OrderServiceError::NotFound(msg) => write!(f, "资源不存在: {}", msg),
+            OrderServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            OrderServiceError::Forbidden(msg) => write!(f, "权限不足: {}", msg),
+            OrderServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for OrderServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        OrderServiceError::DatabaseError(error)
+    }
+}
+
+/// 计价结果
+struct Quote {
+    service: Service,
+    addons: Vec<(ServiceAddon, i32)>,
+    /// 使用的优惠券及用户领取的那一张
+    coupon: Option<(Coupon, UserCoupon)>,
+    price: PriceBreakdown,
+}
 
 pub struct OrderService {
-    order_repo: OrderRepository,
+    order_repo: Arc<dyn OrderStore>,
+    service_repo: Arc<dyn ServiceStore>,
+    coupon_repo: Arc<dyn CouponStore>,
+    notifier: Notifier,
 }
 
 impl OrderService {
-    pub fn new(pool: MySqlPool) -> Self {
+    /// 各存储均可替换，测试中可使用内存实现
+    pub fn new(
+        order_store: Arc<dyn OrderStore>,
+        service_store: Arc<dyn ServiceStore>,
+        coupon_store: Arc<dyn CouponStore>,
+        notifier: Notifier,
+    ) -> Self {
         Self {
-            order_repo: OrderRepository::new(pool),
+            order_repo: order_store,
+            service_repo: service_store,
+            coupon_repo: coupon_store,
+            notifier,
         }
     }
     
@@ -21,9 +82,294 @@ impl OrderService {
     pub async fn list_orders(&self) -> Result<Vec<Order>, sqlx::Error> {
         self.order_repo.list_orders().await
     }
+
+    /// 获取客户的订单列表
+    pub async fn list_customer_orders(&self, customer_id: i32) -> Result<Vec<Order>, sqlx::Error> {
+        self.order_repo.list_by_customer(customer_id).await
+    }
+
+    /// 获取服务人员的订单列表
+    pub async fn list_worker_orders(&self, worker_id: i32) -> Result<Vec<Order>, sqlx::Error> {
+        self.order_repo.list_by_worker(worker_id).await
+    }
     
     /// 根据ID获取订单
     pub async fn get_order_by_id(&self, id: String) -> Result<Order, sqlx::Error> {
         self.order_repo.find_by_id(id).await
     }
-}
\ No newline at end of file
+
+    /// 预览订单金额和优惠，不写入数据库
+    pub async fn preview_price(
+        &self,
+        customer_id: i32,
+        payload: &CouponPreviewRequest,
+    ) -> Result<PriceBreakdown, OrderServiceError> {
+        let today = chrono::Local::now().date_naive();
+        let quote = self
+            .quote(customer_id, payload.service_id, payload.duration, &payload.addons, Some(&payload.coupon_code), today)
+            .await?;
+        Ok(quote.price)
+    }
+
+    /// 创建订单
+    /// 校验服务时长、附加项和优惠券后由服务端计算金额，服务地址在创建订单的事务中校验
+    pub async fn create_order(&self, customer_id: i32, payload: &CreateOrderRequest) -> Result<Order, OrderServiceError> {
+        let today = chrono::Local::now().date_naive();
+        let (time_slot, scheduled_start_time) = validate_schedule(payload.service_date, &payload.time_slot, today)?;
+
+        let quote = self
+            .quote(
+                customer_id,
+                payload.service_id,
+                payload.duration,
+                &payload.addons,
+                payload.coupon_code.as_deref(),
+                today,
+            )
+            .await?;
+        let price = &quote.price;
+
+        let new_order = NewOrder {
+            customer_id,
+            address_id: payload.address_id,
+            service_id: quote.service.service_id,
+            coupon_id: quote.coupon.as_ref().map(|(c, _)| c.coupon_id),
+            user_coupon_id: quote.coupon.as_ref().map(|(_, uc)| uc.user_coupon_id),
+            service_date: payload.service_date,
+            time_slot,
+            duration: payload.duration,
+            unit_price: price.unit_price,
+            subtotal: price.subtotal,
+            discount_amount: price.discount_amount,
+            total_amount: price.total_amount,
+            special_instructions: payload.special_instructions.clone(),
+            scheduled_start_time: Some(scheduled_start_time),
+        };
+        let new_addons = quote
+            .addons
+            .iter()
+            .map(|(addon, quantity)| NewOrderAddon {
+                addon_id: addon.addon_id,
+                quantity: *quantity,
+                unit_price: addon.addon_price,
+            })
+            .collect();
+
+        let order = match self.order_repo.create_order(new_order, new_addons).await? {
+            CreateOrderOutcome::Created(order) => *order,
+            CreateOrderOutcome::AddressNotFound => {
+                return Err(OrderServiceError::ValidationError("服务地址不存在".to_string()));
+            }
+            CreateOrderOutcome::CouponUnavailable => {
+                return Err(OrderServiceError::Conflict("优惠券已被使用或已领完".to_string()));
+            }
+        };
+        tracing::info!("订单创建成功: {} 客户ID: {} 金额: {}", order.order_id, customer_id, order.total_amount);
+        Ok(order)
+    }
+
+    /// 计价：校验服务、时长、附加项和优惠券，计算订单金额
+    /// 使用优惠券时要求用户已领取且尚未使用
+    async fn quote(
+        &self,
+        customer_id: i32,
+        service_id: i32,
+        duration: f64,
+        addon_items: &[OrderAddonRequest],
+        coupon_code: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<Quote, OrderServiceError> {
+        let service = match self.service_repo.find_by_id(service_id).await {
+            Ok(service) if service.is_active => service,
+            Ok(_) | Err(sqlx::Error::RowNotFound) => {
+                return Err(OrderServiceError::NotFound("服务不存在或已下架".to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        validate_duration(duration, service.min_duration, service.max_duration)?;
+
+        // 附加项必须属于该服务且处于启用状态
+        let available_addons = self.service_repo.find_active_addons(service.service_id).await?;
+        let mut seen = HashSet::new();
+        let mut addons = Vec::with_capacity(addon_items.len());
+        for item in addon_items {
+            if item.quantity < 1 {
+                return Err(OrderServiceError::ValidationError("附加服务数量必须大于0".to_string()));
+            }
+            if !seen.insert(item.addon_id) {
+                return Err(OrderServiceError::ValidationError("附加服务不能重复选择".to_string()));
+            }
+            let addon = available_addons
+                .iter()
+                .find(|a| a.addon_id == item.addon_id)
+                .ok_or_else(|| OrderServiceError::ValidationError(format!("附加服务 {} 不可用", item.addon_id)))?;
+            addons.push((addon.clone(), item.quantity));
+        }
+
+        let coupon = match coupon_code.map(str::trim).filter(|c| !c.is_empty()) {
+            Some(code) => {
+                let coupon = self.coupon_repo.find_by_code(code).await?
+                    .ok_or_else(|| OrderServiceError::ValidationError("优惠券不存在".to_string()))?;
+                let subtotal = pricing::calculate(&service, duration, &addons, None).subtotal;
+                pricing::check_coupon(&coupon, service.service_id, subtotal, today)
+                    .map_err(OrderServiceError::ValidationError)?;
+                let user_coupon = self.coupon_repo
+                    .find_usable_user_coupon(customer_id, coupon.coupon_id, today)
+                    .await?
+                    .ok_or_else(|| OrderServiceError::ValidationError("请先领取该优惠券，或优惠券已使用".to_string()))?;
+                Some((coupon, user_coupon))
+            }
+            None => None,
+        };
+
+        let price = pricing::calculate(&service, duration, &addons, coupon.as_ref().map(|(c, _)| c));
+        Ok(Quote { service, addons, coupon, price })
+    }
+
+    /// 确认订单 (管理员)
+    pub async fn confirm_order(&self, order_id: &str, operator_id: i32) -> Result<Order, OrderServiceError> {
+        let order = self.find_order(order_id).await?;
+        self.transition(&order, OrderStatus::Confirmed, operator_id, None).await
+    }
+
+    /// 开始服务，仅被指派的服务人员或管理员可操作
+    pub async fn start_order(&self, order_id: &str, actor_id: i32, is_admin: bool) -> Result<Order, OrderServiceError> {
+        let order = self.find_order(order_id).await?;
+        ensure_assigned_worker(&order, actor_id, is_admin)?;
+        self.transition(&order, OrderStatus::Ongoing, actor_id, None).await
+    }
+
+    /// 完成服务，仅被指派的服务人员或管理员可操作
+    pub async fn complete_order(&self, order_id: &str, actor_id: i32, is_admin: bool) -> Result<Order, OrderServiceError> {
+        let order = self.find_order(order_id).await?;
+        ensure_assigned_worker(&order, actor_id, is_admin)?;
+        self.transition(&order, OrderStatus::Completed, actor_id, None).await
+    }
+
+    /// 取消订单，仅下单客户或管理员可操作，必须填写取消原因
+    pub async fn cancel_order(
+        &self,
+        order_id: &str,
+        actor_id: i32,
+        is_admin: bool,
+        reason: &str,
+    ) -> Result<Order, OrderServiceError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(OrderServiceError::ValidationError("请填写取消原因".to_string()));
+        }
+        let order = self.find_order(order_id).await?;
+        if order.customer_id != actor_id && !is_admin {
+            return Err(OrderServiceError::Forbidden("只能取消自己的订单".to_string()));
+        }
+        self.transition(&order, OrderStatus::Cancelled, actor_id, Some(reason)).await
+    }
+
+    /// 获取订单状态变更历史
+    pub async fn list_status_history(&self, order_id: &str) -> Result<Vec<OrderStatusHistory>, OrderServiceError> {
+        Ok(self.order_repo.list_status_history(order_id).await?)
+    }
+
+    async fn find_order(&self, order_id: &str) -> Result<Order, OrderServiceError> {
+        match self.order_repo.find_by_id(order_id.to_string()).await {
+            Ok(order) => Ok(order),
+            Err(sqlx::Error::RowNotFound) => Err(OrderServiceError::NotFound("订单不存在".to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 按状态机校验并执行状态流转
+    async fn transition(
+        &self,
+        order: &Order,
+        to: OrderStatus,
+        actor_id: i32,
+        remark: Option<&str>,
+    ) -> Result<Order, OrderServiceError> {
+        let from = order.order_status;
+        if !from.can_transition_to(to) {
+            return Err(OrderServiceError::Conflict(format!(
+                "订单状态为 {}，不能变更为 {}",
+                from, to
+            )));
+        }
+
+        let updated = self.order_repo
+            .update_status(&order.order_id, from, to, actor_id, remark)
+            .await?
+            .ok_or_else(|| OrderServiceError::Conflict("订单状态已被修改，请刷新后重试".to_string()))?;
+
+        tracing::info!("订单 {} 状态变更: {} -> {} 操作人: {}", order.order_id, from, to, actor_id);
+        self.notifier.order_status_changed(&updated).await;
+        Ok(updated)
+    }
+}
+
+fn ensure_assigned_worker(order: &Order, actor_id: i32, is_admin: bool) -> Result<(), OrderServiceError> {
+    if is_admin || order.worker_id == Some(actor_id) {
+        Ok(())
+    } else {
+        Err(OrderServiceError::Forbidden("只有被指派的服务人员可以操作该订单".to_string()))
+    }
+}
+
+/// 校验服务日期和时间段，返回时间段和计划开始时间
+fn validate_schedule(
+    service_date: NaiveDate,
+    time_slot: &str,
+    today: NaiveDate,
+) -> Result<(TimeSlot, chrono::NaiveDateTime), OrderServiceError> {
+    let slot = time_slot
+        .parse::<TimeSlot>()
+        .map_err(|_| OrderServiceError::ValidationError("无效的时间段".to_string()))?;
+    if service_date < today {
+        return Err(OrderServiceError::ValidationError("服务日期不能早于今天".to_string()));
+    }
+    Ok((slot, service_date.and_time(slot.start_time())))
+}
+
+/// 校验服务时长在服务项目允许的范围内，且最多一位小数 (orders.duration 为 DECIMAL(5,1))
+fn validate_duration(duration: f64, min_duration: i32, max_duration: i32) -> Result<(), OrderServiceError> {
+    if !duration.is_finite() || duration <= 0.0 {
+        return Err(OrderServiceError::ValidationError("服务时长必须大于0".to_string()));
+    }
+    if ((duration * 10.0).round() - duration * 10.0).abs() > 1e-9 {
+        return Err(OrderServiceError::ValidationError("服务时长最多保留一位小数".to_string()));
+    }
+    if duration < min_duration as f64 || duration > max_duration as f64 {
+        return Err(OrderServiceError::ValidationError(format!(
+            "服务时长需在 {} 到 {} 之间",
+            min_duration, max_duration
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    #[test]
+    fn test_validate_duration() {
+        assert!(validate_duration(2.0, 2, 8).is_ok());
+        assert!(validate_duration(8.0, 2, 8).is_ok());
+        assert!(validate_duration(2.5, 2, 8).is_ok());
+        assert!(validate_duration(1.5, 2, 8).is_err());
+        assert!(validate_duration(8.5, 2, 8).is_err());
+        assert!(validate_duration(2.25, 2, 8).is_err());
+        assert!(validate_duration(0.0, 0, 8).is_err());
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let (slot, start) = validate_schedule(today, "afternoon", today).unwrap();
+        assert_eq!(slot, TimeSlot::Afternoon);
+        assert_eq!(start.time(), NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+
+        assert!(validate_schedule(today, "night", today).is_err());
+        assert!(validate_schedule(today.pred_opt().unwrap(), "morning", today).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! 支付渠道抽象
+//!
+//! 不同支付渠道 (微信、支付宝、银行卡) 实现 `PaymentProvider`，
+//! 支付业务层只依赖该接口。`MockPaymentProvider` 是进程内的模拟网关，
+//! 用于本地开发和测试，不会产生真实交易
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::config::{PaymentConfig, PaymentProviderKind};
+use crate::models::money::Money;
+use crate::models::payment::PaymentMethod;
+
+/// 渠道侧的支付状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderPaymentStatus {
+    /// 已受理，等待用户完成支付或渠道回调
+    Processing,
+    Success,
+    Failed,
+}
+
+/// 渠道返回的交易信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderPayment {
+    /// 第三方交易号
+    pub trade_no: String,
+    pub status: ProviderPaymentStatus,
+}
+
+/// 向渠道发起支付的参数
+#[derive(Debug, Clone)]
+pub struct PaymentIntent<'a> {
+    /// 本系统的支付ID，作为商户订单号传给渠道
+    pub payment_id: &'a str,
+    pub method: PaymentMethod,
+    pub amount: Money,
+    pub description: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaymentProviderError {
+    /// 渠道不支持该支付方式
+    UnsupportedMethod(PaymentMethod),
+    /// 交易不存在
+    TradeNotFound(String),
+    /// 渠道拒绝请求
+    Rejected(String),
+}
+
+impl fmt::Display for PaymentProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaymentProviderError::UnsupportedMethod(method) => write!(f, "不支持的支付方式: {}", method),
+            PaymentProviderError::TradeNotFound(trade_no) => write!(f, "交易不存在: {}", trade_no),
+            PaymentProviderError::Rejected(msg) => write!(f, "支付渠道拒绝: {}", msg),
+        }
+    }
+}
+
+/// 支付渠道接口
+#[async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// 渠道名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 创建支付，返回第三方交易号和初始状态
+    async fn create_intent(&self, intent: PaymentIntent<'_>) -> Result<ProviderPayment, PaymentProviderError>;
+
+    /// 查询交易状态
+    async fn query(&self, trade_no: &str) -> Result<ProviderPayment, PaymentProviderError>;
+
+    /// 对成功的交易全额或部分退款，返回渠道退款单号
+    async fn refund(&self, trade_no: &str, amount: Money) -> Result<String, PaymentProviderError>;
+}
+
+/// 模拟网关对新支付的处理结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MockOutcome {
+    /// 立即支付成功
+    #[default]
+    Succeed,
+    /// 先返回处理中，查询时变为成功，模拟异步回调
+    Deferred,
+    /// 支付失败
+    Fail,
+}
+
+#[derive(Debug, Clone)]
+struct MockTrade {
+    status: ProviderPaymentStatus,
+    amount: Money,
+    refunded: Money,
+}
+
+/// 进程内模拟支付网关
+pub struct MockPaymentProvider {
+    outcome: MockOutcome,
+    trades: RwLock<HashMap<String, MockTrade>>,
+}
+
+impl Default for MockPaymentProvider {
+    fn default() -> Self {
+        Self::new(MockOutcome::default())
+    }
+}
+
+impl MockPaymentProvider {
+    pub fn new(outcome: MockOutcome) -> Self {
+        Self {
+            outcome,
+            trades: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl PaymentProvider for MockPaymentProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn create_intent(&self, intent: PaymentIntent<'_>) -> Result<ProviderPayment, PaymentProviderError> {
+        if intent.method == PaymentMethod::Balance {
+            return Err(PaymentProviderError::UnsupportedMethod(intent.method));
+        }
+        if !intent.amount.is_positive() {
+            return Err(PaymentProviderError::Rejected("支付金额必须大于0".to_string()));
+        }
+
+        let trade_no = format!("MOCK{}", Uuid::new_v4().simple());
+        let status = match self.outcome {
+            MockOutcome::Succeed => ProviderPaymentStatus::Success,
+            MockOutcome::Deferred => ProviderPaymentStatus::Processing,
+            MockOutcome::Fail => ProviderPaymentStatus::Failed,
+        };
+        self.trades.write().unwrap().insert(
+            trade_no.clone(),
+            MockTrade { status, amount: intent.amount, refunded: Money::ZERO },
+        );
+        Ok(ProviderPayment { trade_no, status })
+    }
+
+    async fn query(&self, trade_no: &str) -> Result<ProviderPayment, PaymentProviderError> {
+        let mut trades = self.trades.write().unwrap();
+        let trade = trades
+            .get_mut(trade_no)
+            .ok_or_else(|| PaymentProviderError::TradeNotFound(trade_no.to_string()))?;
+        if trade.status == ProviderPaymentStatus::Processing {
+            trade.status = ProviderPaymentStatus::Success;
+        }
+        Ok(ProviderPayment { trade_no: trade_no.to_string(), status: trade.status })
+    }
+
+    async fn refund(&self, trade_no: &str, amount: Money) -> Result<String, PaymentProviderError> {
+        let mut trades = self.trades.write().unwrap();
+        let trade = trades
+            .get_mut(trade_no)
+            .ok_or_else(|| PaymentProviderError::TradeNotFound(trade_no.to_string()))?;
+        if trade.status != ProviderPaymentStatus::Success {
+            return Err(PaymentProviderError::Rejected("交易未支付成功".to_string()));
+        }
+        if !amount.is_positive() || trade.refunded + amount > trade.amount {
+            return Err(PaymentProviderError::Rejected("退款金额超过可退金额".to_string()));
+        }
+        trade.refunded += amount;
+        Ok(format!("MOCKR{}", Uuid::new_v4().simple()))
+    }
+}
+
+/// 按配置创建支付渠道
+pub fn provider_from_config(config: &PaymentConfig) -> Arc<dyn PaymentProvider> {
+    match config.provider {
+        PaymentProviderKind::Mock => Arc::new(MockPaymentProvider::new(config.mock_outcome)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(yuan: i64) -> PaymentIntent<'static> {
+        PaymentIntent {
+            payment_id: "P1",
+            method: PaymentMethod::Wechat,
+            amount: Money::from_yuan(yuan),
+            description: "测试订单",
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_succeed_and_refund() {
+        let provider = MockPaymentProvider::default();
+        let payment = provider.create_intent(intent(100)).await.unwrap();
+        assert_eq!(payment.status, ProviderPaymentStatus::Success);
+
+        assert!(provider.refund(&payment.trade_no, Money::from_yuan(60)).await.is_ok());
+        assert!(provider.refund(&payment.trade_no, Money::from_yuan(50)).await.is_err());
+        assert!(provider.refund(&payment.trade_no, Money::from_yuan(40)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_deferred_settles_on_query() {
+        let provider = MockPaymentProvider::new(MockOutcome::Deferred);
+        let payment = provider.create_intent(intent(10)).await.unwrap();
+        assert_eq!(payment.status, ProviderPaymentStatus::Processing);
+        assert!(provider.refund(&payment.trade_no, Money::from_yuan(10)).await.is_err());
+
+        let queried = provider.query(&payment.trade_no).await.unwrap();
+        assert_eq!(queried.status, ProviderPaymentStatus::Success);
+        assert!(provider.query("unknown").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_rejects_invalid_requests() {
+        let provider = MockPaymentProvider::new(MockOutcome::Fail);
+        assert_eq!(
+            provider.create_intent(intent(10)).await.unwrap().status,
+            ProviderPaymentStatus::Failed
+        );
+        assert!(provider.create_intent(intent(0)).await.is_err());
+
+        let balance = PaymentIntent { method: PaymentMethod::Balance, ..intent(10) };
+        assert_eq!(
+            provider.create_intent(balance).await,
+            Err(PaymentProviderError::UnsupportedMethod(PaymentMethod::Balance))
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! 支付业务逻辑层
+//!
+//! 负责订单支付的发起、结果同步和退款。
+//! 支付记录先以 pending 状态落库，再向支付渠道下单；
+//! 渠道返回成功后在同一事务中更新支付记录和订单的支付状态。
+//! 余额支付不经过支付渠道，扣款、记流水和订单结算在一个事务中完成
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::models::order::{Order, OrderStatus};
+use crate::models::payment::{NewPayment, Payment, PaymentMethod, PaymentStatus};
+use crate::repositories::{CreatePaymentOutcome, OrderStore, PaymentStore};
+use crate::services::notifier::Notifier;
+use crate::services::payment_provider::{
+    PaymentIntent, PaymentProvider, PaymentProviderError, ProviderPayment, ProviderPaymentStatus,
+};
+
+#[derive(Debug)]
+pub enum PaymentServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Forbidden(String),
+    Conflict(String),
+    ProviderError(PaymentProviderError),
+}
+
+impl fmt::Display for PaymentServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaymentServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            PaymentServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            PaymentServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            PaymentServiceError::Forbidden(msg) => write!(f, "无权操作: {}", msg),
+            PaymentServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+            PaymentServiceError::ProviderError(e) => write!(f, "支付渠道错误: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for PaymentServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        PaymentServiceError::DatabaseError(error)
+    }
+}
+
+impl From<PaymentProviderError> for PaymentServiceError {
+    fn from(error: PaymentProviderError) -> Self {
+        PaymentServiceError::ProviderError(error)
+    }
+}
+
+/// 取出新建的支付记录，其余结果转换为业务错误
+fn created_payment(outcome: CreatePaymentOutcome) -> Result<Payment, PaymentServiceError> {
+    match outcome {
+        CreatePaymentOutcome::Created(payment) => Ok(*payment),
+        CreatePaymentOutcome::OrderNotPayable => {
+            Err(PaymentServiceError::Conflict("订单已支付或已取消".to_string()))
+        }
+        CreatePaymentOutcome::PaymentInProgress => {
+            Err(PaymentServiceError::Conflict("订单有正在处理的支付，请稍后查询".to_string()))
+        }
+        CreatePaymentOutcome::InsufficientBalance => {
+            Err(PaymentServiceError::Conflict("余额不足".to_string()))
+        }
+    }
+}
+
+/// 生成支付ID: P + 时间戳 + 8位随机串
+fn generate_payment_id(now: NaiveDateTime) -> String {
+    let random = Uuid::new_v4().simple().to_string();
+    format!("P{}{}", now.format("%Y%m%d%H%M%S"), random[..8].to_uppercase())
+}
+
+pub struct PaymentService {
+    payment_repo: Arc<dyn PaymentStore>,
+    order_repo: Arc<dyn OrderStore>,
+    provider: Arc<dyn PaymentProvider>,
+    notifier: Notifier,
+}
+
+impl PaymentService {
+    /// 使用指定的支付存储、订单存储和支付渠道
+    pub fn new(
+        payment_store: Arc<dyn PaymentStore>,
+        order_store: Arc<dyn OrderStore>,
+        provider: Arc<dyn PaymentProvider>,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            payment_repo: payment_store,
+            order_repo: order_store,
+            provider,
+            notifier,
+        }
+    }
+
+    /// 客户支付订单
+    pub async fn pay_order(
+        &self,
+        order_id: &str,
+        user_id: i32,
+        method: PaymentMethod,
+    ) -> Result<Payment, PaymentServiceError> {
+        let order = self.find_order(order_id).await?;
+        if order.customer_id != user_id {
+            return Err(PaymentServiceError::Forbidden("只能支付自己的订单".to_string()));
+        }
+
+        let new_payment = NewPayment {
+            payment_id: generate_payment_id(chrono::Local::now().naive_local()),
+            order_id: order.order_id.clone(),
+            user_id,
+            payment_method: method,
+            payment_amount: order.total_amount,
+        };
+
+        if new_payment.payment_amount.is_zero() {
+            let payment = created_payment(self.payment_repo.settle_zero_amount(&new_payment).await?)?;
+            tracing::info!("订单 {} 应付金额为 0，直接结算: {}", payment.order_id, payment.payment_id);
+            self.notifier.payment_succeeded(&payment).await;
+            return Ok(payment);
+        }
+
+        if method == PaymentMethod::Balance {
+            let payment = created_payment(self.payment_repo.pay_with_balance(&new_payment).await?)?;
+            tracing::info!("订单 {} 余额支付成功: {}", payment.order_id, payment.payment_id);
+            self.notifier.payment_succeeded(&payment).await;
+            return Ok(payment);
+        }
+
+        let payment = created_payment(self.payment_repo.create_pending(&new_payment).await?)?;
+
+        let description = format!("家政服务订单 {}", order.order_id);
+        let intent = PaymentIntent {
+            payment_id: &payment.payment_id,
+            method,
+            amount: payment.payment_amount,
+            description: &description,
+        };
+        let result = match self.provider.create_intent(intent).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("支付 {} 下单失败 ({}): {}", payment.payment_id, self.provider.name(), e);
+                self.payment_repo.mark_failed(&payment.payment_id, None).await?;
+                return Err(e.into());
+            }
+        };
+
+        self.apply_provider_result(&payment, &result).await?;
+        Ok(self.payment_repo.find_by_id(&payment.payment_id).await?)
+    }
+
+    /// 获取订单的支付记录，处理中的支付会先向渠道查询最新结果
+    pub async fn list_payments(
+        &self,
+        order_id: &str,
+        actor_id: i32,
+        is_admin: bool,
+    ) -> Result<Vec<Payment>, PaymentServiceError> {
+        let order = self.find_order(order_id).await?;
+        if order.customer_id != actor_id && !is_admin {
+            return Err(PaymentServiceError::Forbidden("无权查看该订单的支付记录".to_string()));
+        }
+
+        let payments = self.payment_repo.list_by_order(order_id).await?;
+        let mut synced = false;
+        for payment in payments.iter().filter(|p| p.payment_status == PaymentStatus::Processing) {
+            let Some(trade_no) = payment.thirdparty_trade_no.as_deref() else {
+                continue;
+            };
+            match self.provider.query(trade_no).await {
+                Ok(result) => {
+                    self.apply_provider_result(payment, &result).await?;
+                    synced = true;
+                }
+                Err(e) => tracing::warn!("查询支付 {} 失败: {}", payment.payment_id, e),
+            }
+        }
+
+        if synced {
+            Ok(self.payment_repo.list_by_order(order_id).await?)
+        } else {
+            Ok(payments)
+        }
+    }
+
+    /// 已取消且已支付的订单原路退款
+    pub async fn refund_order(&self, order_id: &str, operator_id: i32) -> Result<Order, PaymentServiceError> {
+        let order = self.find_order(order_id).await?;
+        if order.order_status != OrderStatus::Cancelled {
+            return Err(PaymentServiceError::Conflict("只有已取消的订单可以退款".to_string()));
+        }
+        self.refund_paid_order(order_id, operator_id).await
+    }
+
+    /// 已支付订单原路退款，不检查订单状态，用于取消退款和投诉补偿
+    /// 余额支付退回余额；渠道支付先把订单和支付记录置为 refunded 防止重复退款，
+    /// 渠道退款失败时再改回；零元订单没有实际扣款，只变更状态
+    pub async fn refund_paid_order(&self, order_id: &str, operator_id: i32) -> Result<Order, PaymentServiceError> {
+        let payment = self
+            .payment_repo
+            .find_success_by_order(order_id)
+            .await?
+            .ok_or_else(|| PaymentServiceError::Conflict("订单没有成功的支付记录".to_string()))?;
+
+        if payment.payment_method == PaymentMethod::Balance && !payment.payment_amount.is_zero() {
+            if !self.payment_repo.refund_to_balance(&payment).await? {
+                return Err(PaymentServiceError::Conflict("订单未支付或已退款".to_string()));
+            }
+            tracing::info!("订单 {} 已退款到余额 {} 操作人: {}", order_id, payment.payment_amount, operator_id);
+            self.notifier.payment_refunded(&payment).await;
+            return self.find_order(order_id).await;
+        }
+
+        let trade_no = payment.thirdparty_trade_no.as_deref();
+        if trade_no.is_none() && !payment.payment_amount.is_zero() {
+            return Err(PaymentServiceError::Conflict("支付记录缺少第三方交易号".to_string()));
+        }
+
+        if !self.payment_repo.mark_refunded(&payment).await? {
+            return Err(PaymentServiceError::Conflict("订单未支付或已退款".to_string()));
+        }
+
+        if let Some(trade_no) = trade_no {
+            match self.provider.refund(trade_no, payment.payment_amount).await {
+                Ok(refund_no) => self.payment_repo.record_refund_no(&payment.payment_id, &refund_no).await?,
+                Err(e) => {
+                    tracing::error!("订单 {} 渠道退款失败: {}", order_id, e);
+                    self.payment_repo.revert_refund(&payment).await?;
+                    return Err(e.into());
+                }
+            }
+        }
+
+        tracing::info!("订单 {} 已退款 {} 操作人: {}", order_id, payment.payment_amount, operator_id);
+        self.notifier.payment_refunded(&payment).await;
+        self.find_order(order_id).await
+    }
+
+    /// 根据渠道返回的结果更新支付记录
+    async fn apply_provider_result(
+        &self,
+        payment: &Payment,
+        result: &ProviderPayment,
+    ) -> Result<(), PaymentServiceError> {
+        match result.status {
+            ProviderPaymentStatus::Success => {
+                let settled = self
+                    .payment_repo
+                    .settle_success(&payment.payment_id, &payment.order_id, &result.trade_no)
+                    .await?;
+                if settled {
+                    tracing::info!("订单 {} 支付成功: {}", payment.order_id, payment.payment_id);
+                    self.notifier.payment_succeeded(payment).await;
+                } else {
+                    // 订单在支付期间已被取消或由其他支付结清，退回本次扣款
+                    // 支付记录置为 refunded 并记录退款单号，对账时是一笔扣款后全额退回的交易
+                    tracing::warn!("支付 {} 无法结算，发起退款", payment.payment_id);
+                    match self.provider.refund(&result.trade_no, payment.payment_amount).await {
+                        Ok(refund_no) => {
+                            let refunded = self
+                                .payment_repo
+                                .refund_unsettled(&payment.payment_id, &result.trade_no, &refund_no)
+                                .await?;
+                            if refunded {
+                                self.notifier.payment_refunded(payment).await;
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("支付 {} 退款失败: {}", payment.payment_id, e);
+                            self.payment_repo.mark_failed(&payment.payment_id, Some(&result.trade_no)).await?;
+                        }
+                    }
+                }
+            }
+            ProviderPaymentStatus::Processing => {
+                self.payment_repo.mark_processing(&payment.payment_id, &result.trade_no).await?;
+            }
+            ProviderPaymentStatus::Failed => {
+                self.payment_repo.mark_failed(&payment.payment_id, Some(&result.trade_no)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn find_order(&self, order_id: &str) -> Result<Order, PaymentServiceError> {
+        match self.order_repo.find_by_id(order_id.to_string()).await {
+            Ok(order) => Ok(order),
+            Err(sqlx::Error::RowNotFound) => Err(PaymentServiceError::NotFound("订单不存在".to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+    use sqlx::mysql::MySqlPoolOptions;
+
+    use crate::models::order::OrderPaymentStatus;
+    use crate::models::worker::TimeSlot;
+    use crate::models::Money;
+    use crate::repositories::{InMemoryOrderStore, InMemoryPaymentStore};
+    use crate::services::event_hub::InMemoryEventHub;
+    use crate::services::payment_provider::{MockOutcome, MockPaymentProvider};
+
+    fn order(order_id: &str) -> Order {
+        let now = chrono::Local::now().naive_local();
+        Order {
+            order_id: order_id.to_string(),
+            customer_id: 1,
+            worker_id: None,
+            address_id: 1,
+            service_id: 1,
+            coupon_id: None,
+            service_date: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+            time_slot: TimeSlot::Morning,
+            duration: 2.0,
+            unit_price: Money::from_yuan(50),
+            subtotal: Money::from_yuan(100),
+            discount_amount: Money::ZERO,
+            total_amount: Money::from_yuan(100),
+            payment_status: OrderPaymentStatus::Pending,
+            order_status: OrderStatus::Pending,
+            special_instructions: None,
+            cancellation_reason: None,
+            scheduled_start_time: None,
+            actual_start_time: None,
+            actual_end_time: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_generate_payment_id() {
+        let now = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap().and_hms_opt(8, 30, 5).unwrap();
+        let id = generate_payment_id(now);
+        assert!(id.starts_with("P20250601083005"));
+        assert_eq!(id.len(), 23);
+        assert_ne!(id, generate_payment_id(now));
+    }
+
+    // 支付处理中订单被取消，渠道随后扣款成功：退回扣款，支付记录为已退款并带退款单号
+    #[tokio::test]
+    async fn test_unsettled_capture_is_recorded_as_refunded() {
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("mysql://jz:jz@127.0.0.1:1/jz_test")
+            .unwrap();
+        let orders = Arc::new(InMemoryOrderStore::with_orders(vec![order("O1")]));
+        let service = PaymentService::new(
+            Arc::new(InMemoryPaymentStore::new(orders.clone())),
+            orders.clone(),
+            Arc::new(MockPaymentProvider::new(MockOutcome::Deferred)),
+            Notifier::new(pool, Arc::new(InMemoryEventHub::default())),
+        );
+
+        let payment = service.pay_order("O1", 1, PaymentMethod::Wechat).await.unwrap();
+        assert_eq!(payment.payment_status, PaymentStatus::Processing);
+        orders.update_status("O1", OrderStatus::Pending, OrderStatus::Cancelled, 1, None).await.unwrap().unwrap();
+
+        let payments = service.list_payments("O1", 1, false).await.unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].payment_status, PaymentStatus::Refunded);
+        assert_eq!(payments[0].thirdparty_trade_no, payment.thirdparty_trade_no);
+        assert!(payments[0].refund_no.as_deref().is_some_and(|no| no.starts_with("MOCKR")));
+        let order = orders.find_by_id("O1".to_string()).await.unwrap();
+        assert_eq!(order.payment_status, OrderPaymentStatus::Pending);
+    }
+}
@@ -0,0 +1,231 @@
+//! 订单计价
+//!
+//! 根据服务项目、附加项和优惠券计算订单金额，不涉及数据库访问。
+//! 金额均为 `Money`，只有按时长和折扣比例计算时才会舍入到分
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::models::{coupon::{Coupon, DiscountType}, money::Money, service::{Service, ServiceAddon, ServiceUnit}};
+
+/// 订单金额明细
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceBreakdown {
+    /// 服务单价
+    pub unit_price: Money,
+    /// 服务费用 (不含附加项)
+    pub service_amount: Money,
+    /// 附加项费用
+    pub addon_amount: Money,
+    /// 小计 = 服务费用 + 附加项费用
+    pub subtotal: Money,
+    /// 优惠金额
+    pub discount_amount: Money,
+    /// 应付金额 = 小计 - 优惠金额
+    pub total_amount: Money,
+}
+
+/// 时长换算为 0.1 的整数倍 (orders.duration 为 DECIMAL(5,1))
+fn duration_tenths(duration: f64) -> i64 {
+    (duration * 10.0).round() as i64
+}
+
+/// 服务费用
+/// 固定价格的服务不随时长变化，其余计价单位按 单价 × 时长/数量 计算
+pub fn service_amount(service: &Service, duration: f64) -> Money {
+    match service.unit {
+        ServiceUnit::Fixed => service.base_price,
+        _ => service.base_price.mul_ratio(duration_tenths(duration), 10),
+    }
+}
+
+/// 检查优惠券是否可用于当前订单
+pub fn check_coupon(
+    coupon: &Coupon,
+    service_id: i32,
+    subtotal: Money,
+    today: NaiveDate,
+) -> Result<(), String> {
+    if !coupon.is_active {
+        return Err("优惠券已停用".to_string());
+    }
+    if today < coupon.valid_from || today > coupon.valid_until {
+        return Err("优惠券不在有效期内".to_string());
+    }
+    if coupon.used_count >= coupon.usage_limit {
+        return Err("优惠券已被领完".to_string());
+    }
+    if subtotal < coupon.min_order_amount {
+        return Err(format!("订单金额未达到优惠券最低使用金额 {}", coupon.min_order_amount));
+    }
+    if let Some(services) = &coupon.applicable_services
+        && !services.is_empty()
+        && !services.contains(&service_id)
+    {
+        return Err("优惠券不适用于该服务".to_string());
+    }
+    Ok(())
+}
+
+/// 计算优惠金额，不超过可优惠的金额
+/// - percentage: discount_value 为折扣百分比，如 20 表示减免 20%
+/// - fixed: 整单立减 discount_value
+/// - service: 仅对服务费用立减 discount_value，附加项不参与优惠
+pub fn coupon_discount(coupon: &Coupon, service_amount: Money, subtotal: Money) -> Money {
+    let discount = match coupon.discount_type {
+        DiscountType::Percentage => subtotal.percent(coupon.discount_value.clamp(Money::ZERO, Money::from_yuan(100))),
+        DiscountType::Fixed => coupon.discount_value.min(subtotal),
+        DiscountType::Service => coupon.discount_value.min(service_amount),
+    };
+    discount.max(Money::ZERO)
+}
+
+/// 计算订单金额
+/// addons 为 (附加项, 数量)，调用方需保证附加项属于该服务且处于启用状态
+pub fn calculate(
+    service: &Service,
+    duration: f64,
+    addons: &[(ServiceAddon, i32)],
+    coupon: Option<&Coupon>,
+) -> PriceBreakdown {
+    let service_amount = service_amount(service, duration);
+    let addon_amount: Money = addons
+        .iter()
+        .map(|(addon, quantity)| addon.addon_price.times(i64::from(*quantity)))
+        .sum();
+    let subtotal = service_amount + addon_amount;
+    let discount_amount = coupon
+        .map(|c| coupon_discount(c, service_amount, subtotal))
+        .unwrap_or(Money::ZERO);
+
+    PriceBreakdown {
+        unit_price: service.base_price,
+        service_amount,
+        addon_amount,
+        subtotal,
+        discount_amount,
+        total_amount: subtotal - discount_amount,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn m(s: &str) -> Money {
+        s.parse().unwrap()
+    }
+
+    fn service(unit: ServiceUnit, base_price: &str) -> Service {
+        Service {
+            service_id: 1,
+            category_id: 1,
+            service_name: "日常保洁".to_string(),
+            description: None,
+            base_price: m(base_price),
+            unit,
+            min_duration: 2,
+            max_duration: 8,
+            is_active: true,
+            created_at: DateTime::from_timestamp(1609459200, 0).unwrap().naive_utc(),
+        }
+    }
+
+    fn addon(price: &str) -> ServiceAddon {
+        ServiceAddon {
+            addon_id: 1,
+            service_id: 1,
+            addon_name: "擦玻璃".to_string(),
+            addon_price: m(price),
+            is_active: true,
+            created_at: DateTime::from_timestamp(1609459200, 0).unwrap().naive_utc(),
+        }
+    }
+
+    fn coupon(discount_type: DiscountType, value: &str, min_amount: &str) -> Coupon {
+        Coupon {
+            coupon_id: 1,
+            coupon_code: "NEW10".to_string(),
+            coupon_name: "新人券".to_string(),
+            discount_type,
+            discount_value: m(value),
+            min_order_amount: m(min_amount),
+            applicable_services: None,
+            valid_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            valid_until: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+            usage_limit: 10,
+            used_count: 0,
+            is_active: true,
+            created_at: DateTime::from_timestamp(1609459200, 0).unwrap().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_hourly_service_with_addons() {
+        let price = calculate(&service(ServiceUnit::Hour, "45.5"), 3.0, &[(addon("20"), 2)], None);
+        assert_eq!(price.service_amount, m("136.5"));
+        assert_eq!(price.addon_amount, m("40"));
+        assert_eq!(price.subtotal, m("176.5"));
+        assert_eq!(price.discount_amount, Money::ZERO);
+        assert_eq!(price.total_amount, m("176.5"));
+    }
+
+    #[test]
+    fn test_fractional_duration_rounds_to_fen() {
+        // 33.33 × 1.5 = 49.995 → 50.00
+        let price = calculate(&service(ServiceUnit::Hour, "33.33"), 1.5, &[], None);
+        assert_eq!(price.service_amount, m("50.00"));
+    }
+
+    #[test]
+    fn test_fixed_service_ignores_duration() {
+        let price = calculate(&service(ServiceUnit::Fixed, "199"), 5.0, &[], None);
+        assert_eq!(price.subtotal, m("199"));
+    }
+
+    #[test]
+    fn test_coupon_discounts() {
+        let svc = service(ServiceUnit::Hour, "50");
+        let addons = [(addon("30"), 1)];
+
+        let price = calculate(&svc, 2.0, &addons, Some(&coupon(DiscountType::Percentage, "20", "0")));
+        assert_eq!(price.discount_amount, m("26"));
+        assert_eq!(price.total_amount, m("104"));
+
+        let price = calculate(&svc, 2.0, &addons, Some(&coupon(DiscountType::Fixed, "500", "0")));
+        assert_eq!(price.discount_amount, m("130"));
+        assert_eq!(price.total_amount, Money::ZERO);
+
+        let price = calculate(&svc, 2.0, &addons, Some(&coupon(DiscountType::Service, "120", "0")));
+        assert_eq!(price.discount_amount, m("100"));
+        assert_eq!(price.total_amount, m("30"));
+
+        // 99.99 打 85 折: 优惠 14.9985 → 15.00，应付 84.99
+        let price = calculate(&service(ServiceUnit::Fixed, "99.99"), 1.0, &[], Some(&coupon(DiscountType::Percentage, "15", "0")));
+        assert_eq!(price.discount_amount, m("15.00"));
+        assert_eq!(price.total_amount, m("84.99"));
+    }
+
+    #[test]
+    fn test_check_coupon() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        assert!(check_coupon(&coupon(DiscountType::Fixed, "10", "100"), 1, m("120"), today).is_ok());
+        assert!(check_coupon(&coupon(DiscountType::Fixed, "10", "100"), 1, m("99.99"), today).is_err());
+
+        let expired = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        assert!(check_coupon(&coupon(DiscountType::Fixed, "10", "0"), 1, m("120"), expired).is_err());
+
+        let mut limited = coupon(DiscountType::Fixed, "10", "0");
+        limited.applicable_services = Some(vec![2, 3]);
+        assert!(check_coupon(&limited, 1, m("120"), today).is_err());
+        assert!(check_coupon(&limited, 2, m("120"), today).is_ok());
+
+        limited.used_count = limited.usage_limit;
+        assert!(check_coupon(&limited, 2, m("120"), today).is_err());
+
+        let mut inactive = coupon(DiscountType::Fixed, "10", "0");
+        inactive.is_active = false;
+        assert!(check_coupon(&inactive, 1, m("120"), today).is_err());
+    }
+}
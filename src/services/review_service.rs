@@ -0,0 +1,200 @@
+//! 评价业务逻辑层
+//!
+//! 客户在订单完成后可对服务人员评价一次，评价写入后同步更新服务人员的平均评分
+
+use std::fmt;
+use std::sync::Arc;
+
+use sqlx::mysql::MySqlPool;
+
+use crate::models::order::OrderStatus;
+use crate::models::pagination::{Page, PageQuery};
+use crate::models::review::{CreateReviewRequest, Review, WorkerReview};
+use crate::repositories::{OrderStore, ReviewRepository};
+
+/// 评价内容最大字数
+const MAX_REVIEW_TEXT_CHARS: usize = 500;
+
+/// 匿名评价展示的评价人名称
+const ANONYMOUS_REVIEWER: &str = "匿名用户";
+
+#[derive(Debug)]
+pub enum ReviewServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Forbidden(String),
+    Conflict(String),
+}
+
+impl fmt::Display for ReviewServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReviewServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            ReviewServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            ReviewServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            ReviewServiceError::Forbidden(msg) => write!(f, "无权操作: {}", msg),
+            ReviewServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ReviewServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        ReviewServiceError::DatabaseError(error)
+    }
+}
+
+/// 校验评分在 1-5 分之间、评价内容不超过字数上限
+fn validate_review(review: &CreateReviewRequest) -> Result<(), String> {
+    let ratings = [
+        ("总评分", Some(review.rating)),
+        ("服务评分", review.service_rating),
+        ("守时评分", review.punctuality_rating),
+    ];
+    for (name, rating) in ratings {
+        if let Some(rating) = rating && !(1..=5).contains(&rating) {
+            return Err(format!("{}必须在1-5分之间", name));
+        }
+    }
+    if let Some(text) = &review.review_text && text.chars().count() > MAX_REVIEW_TEXT_CHARS {
+        return Err(format!("评价内容不能超过{}字", MAX_REVIEW_TEXT_CHARS));
+    }
+    Ok(())
+}
+
+/// 转换为对外展示的评价，匿名评价隐藏评价人身份
+fn to_worker_review(review: Review, username: String) -> WorkerReview {
+    let (reviewer_id, reviewer_name) = if review.is_anonymous {
+        (None, ANONYMOUS_REVIEWER.to_string())
+    } else {
+        (Some(review.customer_id), username)
+    };
+    WorkerReview {
+        review_id: review.review_id,
+        reviewer_id,
+        reviewer_name,
+        rating: review.rating,
+        service_rating: review.service_rating,
+        punctuality_rating: review.punctuality_rating,
+        review_text: review.review_text,
+        created_at: review.created_at,
+    }
+}
+
+pub struct ReviewService {
+    review_repo: ReviewRepository,
+    order_repo: Arc<dyn OrderStore>,
+}
+
+impl ReviewService {
+    pub fn new(pool: MySqlPool, order_store: Arc<dyn OrderStore>) -> Self {
+        Self {
+            review_repo: ReviewRepository::new(pool),
+            order_repo: order_store,
+        }
+    }
+
+    /// 客户评价已完成的订单，每个订单只能评价一次
+    pub async fn create_review(
+        &self,
+        customer_id: i32,
+        order_id: &str,
+        payload: &CreateReviewRequest,
+    ) -> Result<Review, ReviewServiceError> {
+        validate_review(payload).map_err(ReviewServiceError::ValidationError)?;
+
+        let order = match self.order_repo.find_by_id(order_id.to_string()).await {
+            Ok(order) => order,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(ReviewServiceError::NotFound("订单不存在".to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if order.customer_id != customer_id {
+            return Err(ReviewServiceError::Forbidden("只能评价自己的订单".to_string()));
+        }
+        if order.order_status != OrderStatus::Completed {
+            return Err(ReviewServiceError::Conflict("订单完成后才能评价".to_string()));
+        }
+        let Some(worker_id) = order.worker_id else {
+            return Err(ReviewServiceError::Conflict("订单未分配服务人员".to_string()));
+        };
+
+        self.review_repo.create(order_id, customer_id, worker_id, payload).await?
+            .ok_or_else(|| ReviewServiceError::Conflict("订单已评价".to_string()))
+    }
+
+    /// 分页获取服务人员收到的评价
+    pub async fn list_worker_reviews(
+        &self,
+        worker_id: i32,
+        query: &PageQuery,
+    ) -> Result<Page<WorkerReview>, ReviewServiceError> {
+        let (reviews, total) = self.review_repo.list_by_worker(worker_id, query).await?;
+        let items = reviews.into_iter()
+            .map(|(review, username)| to_worker_review(review, username))
+            .collect();
+        Ok(Page::new(items, query, total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(rating: i8) -> CreateReviewRequest {
+        CreateReviewRequest {
+            rating,
+            service_rating: None,
+            punctuality_rating: None,
+            review_text: None,
+            is_anonymous: false,
+        }
+    }
+
+    fn review(is_anonymous: bool) -> Review {
+        Review {
+            review_id: 1,
+            order_id: "20240101000001".to_string(),
+            customer_id: 7,
+            worker_id: 3,
+            rating: 5,
+            service_rating: Some(4),
+            punctuality_rating: None,
+            review_text: Some("很满意".to_string()),
+            is_anonymous,
+            created_at: chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap().and_hms_opt(10, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_validate_review() {
+        assert!(validate_review(&request(1)).is_ok());
+        assert!(validate_review(&request(5)).is_ok());
+        assert!(validate_review(&request(0)).is_err());
+        assert!(validate_review(&request(6)).is_err());
+
+        let mut payload = request(5);
+        payload.punctuality_rating = Some(-1);
+        assert!(validate_review(&payload).is_err());
+
+        payload.punctuality_rating = Some(3);
+        payload.review_text = Some("好".repeat(MAX_REVIEW_TEXT_CHARS));
+        assert!(validate_review(&payload).is_ok());
+        payload.review_text = Some("好".repeat(MAX_REVIEW_TEXT_CHARS + 1));
+        assert!(validate_review(&payload).is_err());
+    }
+
+    #[test]
+    fn test_anonymous_reviewer_is_masked() {
+        let public = to_worker_review(review(false), "alice".to_string());
+        assert_eq!(public.reviewer_id, Some(7));
+        assert_eq!(public.reviewer_name, "alice");
+
+        let anonymous = to_worker_review(review(true), "alice".to_string());
+        assert_eq!(anonymous.reviewer_id, None);
+        assert_eq!(anonymous.reviewer_name, ANONYMOUS_REVIEWER);
+        assert_eq!(anonymous.review_text.as_deref(), Some("很满意"));
+    }
+}
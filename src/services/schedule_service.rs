@@ -0,0 +1,231 @@
+//! 服务人员日程业务逻辑层
+//!
+//! 处理服务人员发布可预约时间、标记不可用日期以及可预约时间段查询
+
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate};
+use sqlx::mysql::MySqlPool;
+
+use crate::models::worker::{
+    MarkUnavailableRequest, ScheduleStatus, ScheduleUpdateResult, SetAvailabilityRequest, SlotAvailability, TimeSlot, WorkerSchedule,
+};
+use crate::repositories::ScheduleRepository;
+
+/// 单次设置的最大天数
+pub const MAX_SCHEDULE_DAYS: i64 = 62;
+
+#[derive(Debug)]
+pub enum ScheduleServiceError {
+    DatabaseError(sqlx::Error),
+    ValidationError(String),
+}
+
+impl fmt::Display for ScheduleServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScheduleServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            ScheduleServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ScheduleServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        ScheduleServiceError::DatabaseError(error)
+    }
+}
+
+/// 展开日期范围，weekdays 指定时只保留这些星期 (1 = 周一 ... 7 = 周日)
+pub fn expand_dates(
+    start: NaiveDate,
+    end: NaiveDate,
+    weekdays: Option<&[u32]>,
+    today: NaiveDate,
+) -> Result<Vec<NaiveDate>, String> {
+    if end < start {
+        return Err("结束日期不能早于开始日期".to_string());
+    }
+    if start < today {
+        return Err("不能设置今天之前的日程".to_string());
+    }
+    if (end - start).num_days() >= MAX_SCHEDULE_DAYS {
+        return Err(format!("单次最多设置 {} 天", MAX_SCHEDULE_DAYS));
+    }
+    if let Some(days) = weekdays
+        && days.iter().any(|d| !(1..=7).contains(d))
+    {
+        return Err("星期取值范围为 1-7".to_string());
+    }
+
+    Ok(start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| weekdays.is_none_or(|days| days.contains(&d.weekday().number_from_monday())))
+        .collect())
+}
+
+/// 同一次设置中的时间段不能互相重叠
+pub fn validate_slots(slots: &[TimeSlot]) -> Result<(), String> {
+    if slots.is_empty() {
+        return Err("请至少选择一个时间段".to_string());
+    }
+    for (i, a) in slots.iter().enumerate() {
+        if slots[i + 1..].iter().any(|b| a.overlaps(*b)) {
+            return Err(format!("时间段 {} 与其他时间段重叠", a));
+        }
+    }
+    Ok(())
+}
+
+/// 汇总各时间段的可预约情况
+/// 全天空闲的服务人员可以承接任意时间段，全天订单只能由全天空闲的服务人员承接
+pub fn summarize_slots(counts: &[(TimeSlot, i64)]) -> Vec<SlotAvailability> {
+    let count_of = |slot: TimeSlot| {
+        counts
+            .iter()
+            .filter(|(s, _)| s.can_serve(slot))
+            .map(|(_, n)| *n)
+            .sum::<i64>()
+    };
+    TimeSlot::ALL
+        .iter()
+        .map(|&slot| {
+            let available_workers = count_of(slot);
+            SlotAvailability {
+                time_slot: slot,
+                available_workers,
+                bookable: available_workers > 0,
+            }
+        })
+        .collect()
+}
+
+pub struct ScheduleService {
+    schedule_repo: ScheduleRepository,
+}
+
+impl ScheduleService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            schedule_repo: ScheduleRepository::new(pool),
+        }
+    }
+
+    /// 获取服务人员在日期范围内的日程
+    pub async fn list_schedules(
+        &self,
+        worker_id: i32,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<WorkerSchedule>, ScheduleServiceError> {
+        if end < start || (end - start).num_days() >= MAX_SCHEDULE_DAYS {
+            return Err(ScheduleServiceError::ValidationError("日期范围无效".to_string()));
+        }
+        Ok(self.schedule_repo.list(worker_id, start, end).await?)
+    }
+
+    /// 批量设置可预约时间
+    pub async fn set_availability(
+        &self,
+        worker_id: i32,
+        payload: &SetAvailabilityRequest,
+    ) -> Result<ScheduleUpdateResult, ScheduleServiceError> {
+        validate_slots(&payload.time_slots).map_err(ScheduleServiceError::ValidationError)?;
+        let today = chrono::Local::now().date_naive();
+        let dates = expand_dates(payload.start_date, payload.end_date, payload.weekdays.as_deref(), today)
+            .map_err(ScheduleServiceError::ValidationError)?;
+
+        let entries: Vec<(NaiveDate, TimeSlot)> = dates
+            .iter()
+            .flat_map(|date| payload.time_slots.iter().map(move |slot| (*date, *slot)))
+            .collect();
+
+        let result = self.schedule_repo.set_slots(worker_id, &entries, ScheduleStatus::Available).await?;
+        tracing::info!("服务人员 {} 设置可预约时间: 成功 {} 冲突 {}", worker_id, result.updated, result.conflicts.len());
+        Ok(result)
+    }
+
+    /// 将日期整天标记为不可用
+    pub async fn mark_unavailable(
+        &self,
+        worker_id: i32,
+        payload: &MarkUnavailableRequest,
+    ) -> Result<ScheduleUpdateResult, ScheduleServiceError> {
+        if payload.dates.is_empty() {
+            return Err(ScheduleServiceError::ValidationError("请至少选择一个日期".to_string()));
+        }
+        let today = chrono::Local::now().date_naive();
+        if payload.dates.iter().any(|d| *d < today) {
+            return Err(ScheduleServiceError::ValidationError("不能设置今天之前的日程".to_string()));
+        }
+        let mut dates = payload.dates.clone();
+        dates.sort();
+        dates.dedup();
+
+        let entries: Vec<(NaiveDate, TimeSlot)> = dates.into_iter().map(|d| (d, TimeSlot::FullDay)).collect();
+        Ok(self.schedule_repo.set_slots(worker_id, &entries, ScheduleStatus::Unavailable).await?)
+    }
+
+    /// 查询某服务在某天各时间段是否可预约
+    pub async fn bookable_slots(
+        &self,
+        service_id: i32,
+        date: NaiveDate,
+        district: Option<&str>,
+    ) -> Result<Vec<SlotAvailability>, ScheduleServiceError> {
+        let counts = self.schedule_repo.count_available_workers(service_id, date, district).await?;
+        Ok(summarize_slots(&counts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_expand_dates_with_weekly_template() {
+        let today = date(2025, 6, 1);
+        // 2025-06-02 是周一
+        let dates = expand_dates(date(2025, 6, 2), date(2025, 6, 15), Some(&[1, 3]), today).unwrap();
+        assert_eq!(dates, vec![date(2025, 6, 2), date(2025, 6, 4), date(2025, 6, 9), date(2025, 6, 11)]);
+
+        let dates = expand_dates(date(2025, 6, 2), date(2025, 6, 4), None, today).unwrap();
+        assert_eq!(dates.len(), 3);
+    }
+
+    #[test]
+    fn test_expand_dates_rejects_invalid_ranges() {
+        let today = date(2025, 6, 1);
+        assert!(expand_dates(date(2025, 6, 5), date(2025, 6, 4), None, today).is_err());
+        assert!(expand_dates(date(2025, 5, 30), date(2025, 6, 4), None, today).is_err());
+        assert!(expand_dates(date(2025, 6, 1), date(2025, 9, 1), None, today).is_err());
+        assert!(expand_dates(date(2025, 6, 1), date(2025, 6, 2), Some(&[0]), today).is_err());
+    }
+
+    #[test]
+    fn test_validate_slots() {
+        assert!(validate_slots(&[TimeSlot::Morning, TimeSlot::Evening]).is_ok());
+        assert!(validate_slots(&[TimeSlot::Morning, TimeSlot::FullDay]).is_err());
+        assert!(validate_slots(&[TimeSlot::Morning, TimeSlot::Morning]).is_err());
+        assert!(validate_slots(&[]).is_err());
+    }
+
+    #[test]
+    fn test_summarize_slots() {
+        let counts = vec![(TimeSlot::Morning, 2), (TimeSlot::FullDay, 1)];
+        let slots = summarize_slots(&counts);
+        let find = |slot: TimeSlot| slots.iter().find(|s| s.time_slot == slot).unwrap();
+
+        assert_eq!(find(TimeSlot::Morning).available_workers, 3);
+        assert_eq!(find(TimeSlot::Afternoon).available_workers, 1);
+        assert_eq!(find(TimeSlot::FullDay).available_workers, 1);
+        assert!(find(TimeSlot::Evening).bookable);
+
+        assert!(summarize_slots(&[]).iter().all(|s| !s.bookable));
+    }
+}
@@ -1,10 +1,205 @@
 //! 服务业务逻辑层
 //!
-//! 处理服务相关的业务逻辑
+//! 处理服务目录相关的业务逻辑：分类树、服务筛选搜索和服务详情，
+//! 以及管理员对分类、服务和附加项的维护。目录数据只做软删除，每次写操作都记录审计日志
+
+use std::collections::HashMap;
+use std::fmt;
 
 use sqlx::mysql::MySqlPool;
-use crate::repositories::ServiceRepository;
-use crate::models::service::Service;
+use crate::repositories::{CatalogDeleteOutcome, CatalogWriteOutcome, ServiceRepository, ServiceStore};
+use crate::models::money::Money;
+use crate::models::pagination::{Page, PageQuery};
+use crate::models::service::{
+    AddonRequest, CategoryNode, CategoryRequest, Service, ServiceAddon, ServiceCategory, ServiceDetail,
+    ServiceFilter, ServiceQuery, ServiceRequest,
+};
+
+/// 搜索关键词最大长度
+const MAX_KEYWORD_CHARS: usize = 50;
+
+#[derive(Debug)]
+pub enum ServiceServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Conflict(String),
+}
+
+impl fmt::Display for ServiceServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServiceServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            ServiceServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            ServiceServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            ServiceServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ServiceServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => ServiceServiceError::NotFound("服务不存在".to_string()),
+            e => ServiceServiceError::DatabaseError(e),
+        }
+    }
+}
+
+/// 由按 sort_order 排好序的分类列表构造分类树
+/// 父分类不在列表中（例如已停用）的分类连同其子树一起被丢弃
+fn build_category_tree(categories: Vec<ServiceCategory>) -> Vec<CategoryNode> {
+    let mut children: HashMap<Option<i32>, Vec<ServiceCategory>> = HashMap::new();
+    for category in categories {
+        children.entry(category.parent_id).or_default().push(category);
+    }
+
+    fn attach(parent_id: Option<i32>, children: &mut HashMap<Option<i32>, Vec<ServiceCategory>>) -> Vec<CategoryNode> {
+        let mut level = children.remove(&parent_id).unwrap_or_default();
+        level.sort_by_key(|category| (category.sort_order, category.category_id));
+        level
+            .into_iter()
+            .map(|category| {
+                let nested = attach(Some(category.category_id), children);
+                CategoryNode { category, children: nested }
+            })
+            .collect()
+    }
+
+    attach(None, &mut children)
+}
+
+/// 分类自身及其全部子孙分类的ID
+fn descendant_ids(categories: &[ServiceCategory], root_id: i32) -> Vec<i32> {
+    let mut ids = Vec::new();
+    if !categories.iter().any(|category| category.category_id == root_id) {
+        return ids;
+    }
+
+    let mut pending = vec![root_id];
+    while let Some(id) = pending.pop() {
+        ids.push(id);
+        pending.extend(
+            categories
+                .iter()
+                .filter(|category| category.parent_id == Some(id) && !ids.contains(&category.category_id))
+                .map(|category| category.category_id),
+        );
+    }
+    ids
+}
+
+/// 将搜索关键词转为 LIKE 匹配模式，转义其中的通配符
+fn like_pattern(keyword: &str) -> String {
+    let mut pattern = String::with_capacity(keyword.len() + 2);
+    pattern.push('%');
+    for c in keyword.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// 去掉名称首尾空白并校验长度
+fn normalize_name(name: &str, field: &str, max_chars: usize) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("{}不能为空", field));
+    }
+    if name.chars().count() > max_chars {
+        return Err(format!("{}不能超过{}个字符", field, max_chars));
+    }
+    Ok(name.to_string())
+}
+
+/// 目录价格列为 DECIMAL(8, 2)
+const MAX_CATALOG_PRICE: Money = Money::from_fen(99_999_999);
+
+fn check_price(price: Money, field: &str) -> Result<(), String> {
+    if !price.is_positive() {
+        return Err(format!("{}必须大于0", field));
+    }
+    if price > MAX_CATALOG_PRICE {
+        return Err(format!("{}不能超过{}", field, MAX_CATALOG_PRICE));
+    }
+    Ok(())
+}
+
+/// 校验分类参数，空白的图标URL视为未设置
+fn normalize_category(payload: &CategoryRequest) -> Result<CategoryRequest, String> {
+    let category_name = normalize_name(&payload.category_name, "分类名称", 50)?;
+    let icon_url = payload.icon_url.as_deref().map(str::trim).filter(|url| !url.is_empty());
+    if icon_url.is_some_and(|url| url.chars().count() > 500) {
+        return Err("图标URL不能超过500个字符".to_string());
+    }
+    Ok(CategoryRequest {
+        category_name,
+        icon_url: icon_url.map(str::to_string),
+        ..payload.clone()
+    })
+}
+
+/// 校验服务参数：价格为正，1 <= 最小时长 <= 最大时长
+fn normalize_service(payload: &ServiceRequest) -> Result<ServiceRequest, String> {
+    let service_name = normalize_name(&payload.service_name, "服务名称", 100)?;
+    check_price(payload.base_price, "基础价格")?;
+    if payload.min_duration < 1 {
+        return Err("最小时长/数量必须大于0".to_string());
+    }
+    if payload.min_duration > payload.max_duration {
+        return Err("最小时长/数量不能大于最大时长/数量".to_string());
+    }
+    let description = payload.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    Ok(ServiceRequest {
+        service_name,
+        description: description.map(str::to_string),
+        ..payload.clone()
+    })
+}
+
+/// 校验附加项参数，价格必须为正
+fn normalize_addon(payload: &AddonRequest) -> Result<AddonRequest, String> {
+    let addon_name = normalize_name(&payload.addon_name, "附加项名称", 100)?;
+    check_price(payload.addon_price, "附加项价格")?;
+    Ok(AddonRequest { addon_name, ..payload.clone() })
+}
+
+/// 修改接口只能重新启用，停用必须走删除接口以检查订单引用
+fn check_not_deactivating(is_active: Option<bool>) -> Result<(), ServiceServiceError> {
+    if is_active == Some(false) {
+        return Err(ServiceServiceError::ValidationError("停用请使用删除接口".to_string()));
+    }
+    Ok(())
+}
+
+fn write_result<T>(outcome: CatalogWriteOutcome<T>, entity: &str) -> Result<T, ServiceServiceError> {
+    match outcome {
+        CatalogWriteOutcome::Saved(saved) => Ok(saved),
+        CatalogWriteOutcome::NotFound => Err(ServiceServiceError::NotFound(format!("{}不存在", entity))),
+        CatalogWriteOutcome::ParentNotFound => {
+            Err(ServiceServiceError::ValidationError("所属分类不存在".to_string()))
+        }
+        CatalogWriteOutcome::Cycle => {
+            Err(ServiceServiceError::ValidationError("不能把分类移动到自身或其子分类下".to_string()))
+        }
+    }
+}
+
+fn delete_result(outcome: CatalogDeleteOutcome, entity: &str) -> Result<(), ServiceServiceError> {
+    match outcome {
+        CatalogDeleteOutcome::Deleted => Ok(()),
+        CatalogDeleteOutcome::NotFound => Err(ServiceServiceError::NotFound(format!("{}不存在", entity))),
+        CatalogDeleteOutcome::HasChildren => {
+            Err(ServiceServiceError::Conflict("请先停用该分类下的子分类和服务".to_string()))
+        }
+        CatalogDeleteOutcome::InUse => {
+            Err(ServiceServiceError::Conflict(format!("{}仍有未完成的订单，不能停用", entity)))
+        }
+    }
+}
 
 pub struct ServiceService {
     service_repo: ServiceRepository,
@@ -16,14 +211,262 @@ impl ServiceService {
             service_repo: ServiceRepository::new(pool),
         }
     }
-    
-    /// 获取服务列表
-    pub async fn list_services(&self) -> Result<Vec<Service>, sqlx::Error> {
-        self.service_repo.list_services().await
+
+    /// 获取分类树，非管理员始终只能看到启用的分类
+    pub async fn category_tree(&self, active_only: bool, is_admin: bool) -> Result<Vec<CategoryNode>, ServiceServiceError> {
+        let categories = self.service_repo.list_categories(active_only || !is_admin).await?;
+        Ok(build_category_tree(categories))
+    }
+
+    /// 按条件分页查询服务
+    /// 非管理员始终只能看到启用的服务
+    pub async fn list_services(
+        &self,
+        query: &ServiceQuery,
+        page: &PageQuery,
+        is_admin: bool,
+    ) -> Result<Page<Service>, ServiceServiceError> {
+        if let (Some(min), Some(max)) = (query.min_price, query.max_price)
+            && min > max
+        {
+            return Err(ServiceServiceError::ValidationError("最低价格不能高于最高价格".to_string()));
+        }
+
+        let keyword = query.keyword.as_deref().map(str::trim).filter(|k| !k.is_empty());
+        if let Some(keyword) = keyword
+            && keyword.chars().count() > MAX_KEYWORD_CHARS
+        {
+            return Err(ServiceServiceError::ValidationError(
+                format!("搜索关键词不能超过{}个字符", MAX_KEYWORD_CHARS),
+            ));
+        }
+
+        let active_only = query.active_only.unwrap_or(true) || !is_admin;
+        let category_ids = match query.category_id {
+            Some(category_id) => {
+                let categories = self.service_repo.list_categories(active_only).await?;
+                Some(descendant_ids(&categories, category_id))
+            }
+            None => None,
+        };
+
+        let filter = ServiceFilter {
+            category_ids,
+            keyword_pattern: keyword.map(like_pattern),
+            min_price: query.min_price,
+            max_price: query.max_price,
+            unit: query.unit,
+            active_only,
+            sort: query.sort,
+        };
+        let (services, total) = self.service_repo.search_services(&filter, page).await?;
+        Ok(Page::new(services, page, total))
+    }
+
+    /// 获取服务详情及其启用的附加项
+    pub async fn get_service_detail(&self, id: i32) -> Result<ServiceDetail, ServiceServiceError> {
+        let service = self.service_repo.find_by_id(id).await?;
+        let addons = self.service_repo.find_active_addons(id).await?;
+        Ok(ServiceDetail { service, addons })
+    }
+
+    /// 新增分类
+    pub async fn create_category(&self, payload: &CategoryRequest, operator_id: i32) -> Result<ServiceCategory, ServiceServiceError> {
+        let category = normalize_category(payload).map_err(ServiceServiceError::ValidationError)?;
+        let outcome = self.service_repo.create_category(&category, operator_id).await?;
+        write_result(outcome, "分类")
+    }
+
+    /// 修改分类
+    pub async fn update_category(
+        &self,
+        category_id: i32,
+        payload: &CategoryRequest,
+        operator_id: i32,
+    ) -> Result<ServiceCategory, ServiceServiceError> {
+        let category = normalize_category(payload).map_err(ServiceServiceError::ValidationError)?;
+        check_not_deactivating(category.is_active)?;
+        let outcome = self.service_repo.update_category(category_id, &category, operator_id).await?;
+        write_result(outcome, "分类")
+    }
+
+    /// 停用分类
+    pub async fn delete_category(&self, category_id: i32, operator_id: i32) -> Result<(), ServiceServiceError> {
+        let outcome = self.service_repo.delete_category(category_id, operator_id).await?;
+        delete_result(outcome, "分类")
+    }
+
+    /// 新增服务
+    pub async fn create_service(&self, payload: &ServiceRequest, operator_id: i32) -> Result<Service, ServiceServiceError> {
+        let service = normalize_service(payload).map_err(ServiceServiceError::ValidationError)?;
+        let outcome = self.service_repo.create_service(&service, operator_id).await?;
+        write_result(outcome, "服务")
+    }
+
+    /// 修改服务
+    pub async fn update_service(
+        &self,
+        service_id: i32,
+        payload: &ServiceRequest,
+        operator_id: i32,
+    ) -> Result<Service, ServiceServiceError> {
+        let service = normalize_service(payload).map_err(ServiceServiceError::ValidationError)?;
+        check_not_deactivating(service.is_active)?;
+        let outcome = self.service_repo.update_service(service_id, &service, operator_id).await?;
+        write_result(outcome, "服务")
+    }
+
+    /// 下架服务
+    pub async fn delete_service(&self, service_id: i32, operator_id: i32) -> Result<(), ServiceServiceError> {
+        let outcome = self.service_repo.delete_service(service_id, operator_id).await?;
+        delete_result(outcome, "服务")
+    }
+
+    /// 新增附加项
+    pub async fn create_addon(
+        &self,
+        service_id: i32,
+        payload: &AddonRequest,
+        operator_id: i32,
+    ) -> Result<ServiceAddon, ServiceServiceError> {
+        let addon = normalize_addon(payload).map_err(ServiceServiceError::ValidationError)?;
+        self.service_repo.create_addon(service_id, &addon, operator_id).await?
+            .ok_or_else(|| ServiceServiceError::NotFound("服务不存在".to_string()))
+    }
+
+    /// 修改附加项
+    pub async fn update_addon(
+        &self,
+        service_id: i32,
+        addon_id: i32,
+        payload: &AddonRequest,
+        operator_id: i32,
+    ) -> Result<ServiceAddon, ServiceServiceError> {
+        let addon = normalize_addon(payload).map_err(ServiceServiceError::ValidationError)?;
+        check_not_deactivating(addon.is_active)?;
+        self.service_repo.update_addon(service_id, addon_id, &addon, operator_id).await?
+            .ok_or_else(|| ServiceServiceError::NotFound("附加项不存在".to_string()))
+    }
+
+    /// 停用附加项
+    pub async fn delete_addon(&self, service_id: i32, addon_id: i32, operator_id: i32) -> Result<(), ServiceServiceError> {
+        let outcome = self.service_repo.delete_addon(service_id, addon_id, operator_id).await?;
+        delete_result(outcome, "附加项")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i32, parent_id: Option<i32>, sort_order: i32) -> ServiceCategory {
+        ServiceCategory {
+            category_id: id,
+            category_name: format!("分类{}", id),
+            parent_id,
+            icon_url: None,
+            is_active: true,
+            sort_order,
+            created_at: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    fn ids(nodes: &[CategoryNode]) -> Vec<i32> {
+        nodes.iter().map(|node| node.category.category_id).collect()
+    }
+
+    #[test]
+    fn test_build_category_tree_orders_by_sort_order() {
+        let tree = build_category_tree(vec![
+            category(1, None, 2),
+            category(2, None, 1),
+            category(3, Some(1), 5),
+            category(4, Some(1), 3),
+            category(5, Some(4), 0),
+        ]);
+
+        assert_eq!(ids(&tree), vec![2, 1]);
+        assert_eq!(ids(&tree[1].children), vec![4, 3]);
+        assert_eq!(ids(&tree[1].children[0].children), vec![5]);
+        assert!(tree[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_build_category_tree_drops_orphans() {
+        let tree = build_category_tree(vec![category(1, None, 0), category(3, Some(2), 0)]);
+        assert_eq!(ids(&tree), vec![1]);
+        assert!(tree[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_descendant_ids() {
+        let categories = vec![
+            category(1, None, 0),
+            category(2, Some(1), 0),
+            category(3, Some(2), 0),
+            category(4, None, 0),
+        ];
+
+        let mut result = descendant_ids(&categories, 1);
+        result.sort();
+        assert_eq!(result, vec![1, 2, 3]);
+        assert_eq!(descendant_ids(&categories, 4), vec![4]);
+        assert!(descendant_ids(&categories, 9).is_empty());
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("保洁"), "%保洁%");
+        assert_eq!(like_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+
+    fn service_request(base_price: Money, min_duration: i32, max_duration: i32) -> ServiceRequest {
+        ServiceRequest {
+            category_id: 1,
+            service_name: " 日常保洁 ".to_string(),
+            description: Some("  ".to_string()),
+            base_price,
+            unit: crate::models::service::ServiceUnit::Hour,
+            min_duration,
+            max_duration,
+            is_active: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_service() {
+        let service = normalize_service(&service_request(Money::from_yuan(50), 2, 8)).unwrap();
+        assert_eq!(service.service_name, "日常保洁");
+        assert_eq!(service.description, None);
+
+        assert!(normalize_service(&service_request(Money::from_yuan(50), 2, 2)).is_ok());
+        assert!(normalize_service(&service_request(Money::ZERO, 2, 8)).is_err());
+        assert!(normalize_service(&service_request(Money::from_fen(-100), 2, 8)).is_err());
+        assert!(normalize_service(&service_request(Money::from_fen(100_000_000), 2, 8)).is_err());
+        assert!(normalize_service(&service_request(Money::from_yuan(50), 0, 8)).is_err());
+        assert!(normalize_service(&service_request(Money::from_yuan(50), 9, 8)).is_err());
     }
-    
-    /// 根据ID获取服务
-    pub async fn get_service_by_id(&self, id: i32) -> Result<Service, sqlx::Error> {
-        self.service_repo.find_by_id(id).await
+
+    #[test]
+    fn test_normalize_category_and_addon() {
+        let payload = CategoryRequest {
+            category_name: " 家电清洗 ".to_string(),
+            parent_id: Some(1),
+            icon_url: Some(" ".to_string()),
+            sort_order: 3,
+            is_active: None,
+        };
+        let category = normalize_category(&payload).unwrap();
+        assert_eq!(category.category_name, "家电清洗");
+        assert_eq!(category.icon_url, None);
+        assert_eq!((category.parent_id, category.sort_order), (Some(1), 3));
+
+        let blank = CategoryRequest { category_name: "  ".to_string(), ..payload };
+        assert!(normalize_category(&blank).is_err());
+
+        let addon = AddonRequest { addon_name: "自带工具".to_string(), addon_price: Money::from_yuan(20), is_active: None };
+        assert!(normalize_addon(&addon).is_ok());
+        let free = AddonRequest { addon_price: Money::ZERO, ..addon };
+        assert!(normalize_addon(&free).is_err());
     }
-}
\ No newline at end of file
+}
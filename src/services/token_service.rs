@@ -0,0 +1,218 @@
+//! 刷新令牌业务逻辑层
+//!
+//! 负责刷新令牌的签发、轮换和撤销。
+//! 每次刷新都会撤销旧令牌并在同一令牌族中签发新令牌；
+//! 如果已经轮换过的令牌再次被使用，说明令牌可能已泄露，整个令牌族都会被撤销
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::models::refresh_token::{NewRefreshToken, RefreshToken};
+use crate::repositories::RefreshTokenStore;
+use crate::utils::jwt::{generate_refresh_token, hash_refresh_token};
+
+/// 刷新令牌默认有效期 (天)
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 7;
+
+/// 设备信息最大长度，与 refresh_tokens.device_info 列一致
+const DEVICE_INFO_MAX_LEN: usize = 255;
+
+#[derive(Debug)]
+pub enum RefreshTokenError {
+    /// 令牌不存在
+    Invalid,
+    /// 令牌已过期
+    Expired,
+    /// 令牌已被轮换或撤销后再次使用
+    Reused,
+    DatabaseError(sqlx::Error),
+}
+
+impl fmt::Display for RefreshTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RefreshTokenError::Invalid => write!(f, "无效的刷新令牌"),
+            RefreshTokenError::Expired => write!(f, "刷新令牌已过期"),
+            RefreshTokenError::Reused => write!(f, "刷新令牌已失效，请重新登录"),
+            RefreshTokenError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for RefreshTokenError {
+    fn from(error: sqlx::Error) -> Self {
+        RefreshTokenError::DatabaseError(error)
+    }
+}
+
+pub struct RefreshTokenService {
+    store: Arc<dyn RefreshTokenStore>,
+    ttl: Duration,
+}
+
+impl RefreshTokenService {
+    pub fn new(store: Arc<dyn RefreshTokenStore>) -> Self {
+        Self {
+            store,
+            ttl: Duration::days(REFRESH_TOKEN_TTL_DAYS),
+        }
+    }
+
+    /// 指定有效期，有效期来自配置
+    pub fn with_ttl(store: Arc<dyn RefreshTokenStore>, ttl: Duration) -> Self {
+        Self { store, ttl }
+    }
+
+    /// 登录时签发新令牌，开启一个新的令牌族
+    pub async fn issue(&self, user_id: i32, device_info: Option<&str>) -> Result<String, RefreshTokenError> {
+        let family_id = Uuid::new_v4().to_string();
+        let (token, new_token) = self.new_token(user_id, family_id, None, device_info);
+        self.store.insert(new_token).await?;
+        Ok(token)
+    }
+
+    /// 使用刷新令牌换取新令牌
+    /// 返回令牌所属的用户ID和新的刷新令牌
+    pub async fn rotate(&self, token: &str, device_info: Option<&str>) -> Result<(i32, String), RefreshTokenError> {
+        let record = self.find_active(token).await?;
+
+        // 撤销旧令牌和保存新令牌在同一事务中完成，
+        // 并发刷新时只有一个请求能轮换成功，失败的一方视为重放
+        let (token, new_token) =
+            self.new_token(record.user_id, record.family_id.clone(), Some(record.token_id), device_info);
+        if self.store.rotate(record.token_id, new_token).await?.is_none() {
+            self.revoke_family_for_reuse(&record).await?;
+            return Err(RefreshTokenError::Reused);
+        }
+
+        Ok((record.user_id, token))
+    }
+
+    /// 登出时撤销令牌所在的整个令牌族
+    pub async fn revoke(&self, token: &str) -> Result<(), RefreshTokenError> {
+        if let Some(record) = self.store.find_by_hash(&hash_refresh_token(token)).await? {
+            self.store.revoke_family(&record.family_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn find_active(&self, token: &str) -> Result<RefreshToken, RefreshTokenError> {
+        let record = self
+            .store
+            .find_by_hash(&hash_refresh_token(token))
+            .await?
+            .ok_or(RefreshTokenError::Invalid)?;
+
+        if record.revoked {
+            self.revoke_family_for_reuse(&record).await?;
+            return Err(RefreshTokenError::Reused);
+        }
+
+        if record.expires_at <= Utc::now().naive_utc() {
+            return Err(RefreshTokenError::Expired);
+        }
+
+        Ok(record)
+    }
+
+    async fn revoke_family_for_reuse(&self, record: &RefreshToken) -> Result<(), RefreshTokenError> {
+        let revoked = self.store.revoke_family(&record.family_id).await?;
+        tracing::warn!(
+            "检测到刷新令牌重放, 用户ID: {}, 令牌族: {}, 撤销令牌数: {}",
+            record.user_id,
+            record.family_id,
+            revoked
+        );
+        Ok(())
+    }
+
+    /// 生成新令牌，返回 (令牌明文, 待保存的记录)
+    fn new_token(
+        &self,
+        user_id: i32,
+        family_id: String,
+        parent_id: Option<i64>,
+        device_info: Option<&str>,
+    ) -> (String, NewRefreshToken) {
+        let token = generate_refresh_token();
+        let record = NewRefreshToken {
+            token_hash: hash_refresh_token(&token),
+            user_id,
+            family_id,
+            parent_id,
+            device_info: device_info.map(|d| d.chars().take(DEVICE_INFO_MAX_LEN).collect()),
+            expires_at: Utc::now().naive_utc() + self.ttl,
+        };
+        (token, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::InMemoryRefreshTokenStore;
+
+    fn service() -> RefreshTokenService {
+        RefreshTokenService::new(Arc::new(InMemoryRefreshTokenStore::new()))
+    }
+
+    #[tokio::test]
+    async fn test_rotate_issues_new_token() {
+        let service = service();
+        let token = service.issue(1, Some("test-agent")).await.unwrap();
+
+        let (user_id, rotated) = service.rotate(&token, None).await.unwrap();
+        assert_eq!(user_id, 1);
+        assert_ne!(rotated, token);
+
+        let (user_id, _) = service.rotate(&rotated, None).await.unwrap();
+        assert_eq!(user_id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_is_invalid() {
+        let service = service();
+        assert!(matches!(service.rotate("unknown", None).await, Err(RefreshTokenError::Invalid)));
+    }
+
+    #[tokio::test]
+    async fn test_reuse_revokes_family() {
+        let service = service();
+        let token = service.issue(1, None).await.unwrap();
+        let (_, rotated) = service.rotate(&token, None).await.unwrap();
+
+        // 旧令牌被重放
+        assert!(matches!(service.rotate(&token, None).await, Err(RefreshTokenError::Reused)));
+        // 同一令牌族中轮换出的新令牌也随之失效
+        assert!(matches!(service.rotate(&rotated, None).await, Err(RefreshTokenError::Reused)));
+    }
+
+    #[tokio::test]
+    async fn test_reuse_does_not_affect_other_family() {
+        let service = service();
+        let first = service.issue(1, None).await.unwrap();
+        let second = service.issue(1, None).await.unwrap();
+        service.rotate(&first, None).await.unwrap();
+        assert!(service.rotate(&first, None).await.is_err());
+
+        assert!(service.rotate(&second, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_rejected() {
+        let service = RefreshTokenService::with_ttl(Arc::new(InMemoryRefreshTokenStore::new()), Duration::seconds(-1));
+        let token = service.issue(1, None).await.unwrap();
+        assert!(matches!(service.rotate(&token, None).await, Err(RefreshTokenError::Expired)));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_on_logout() {
+        let service = service();
+        let token = service.issue(1, None).await.unwrap();
+        service.revoke(&token).await.unwrap();
+        assert!(service.rotate(&token, None).await.is_err());
+    }
+}
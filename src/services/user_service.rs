@@ -6,15 +6,18 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use sqlx::mysql::MySqlPool;
 use std::fmt;
+use std::sync::Arc;
+
+use chrono::Duration;
 
 use crate::{
+    config::JwtConfig,
     models::{user::User, auth::{LoginRequest, RegisterRequest, RefreshTokenRequest, LogoutRequest}},
-    repositories::UserRepository,
-    utils::jwt::{Claims, store_refresh_token, validate_refresh_token, remove_refresh_token, generate_refresh_token},
+    repositories::{UserStore, RefreshTokenStore},
+    services::token_service::{RefreshTokenService, RefreshTokenError},
+    utils::jwt::Claims,
 };
-use std::env;
 
 #[derive(Debug)]
 pub enum UserServiceError {
@@ -55,17 +58,37 @@ impl From<jsonwebtoken::errors::Error> for UserServiceError {
     }
 }
 
+impl From<RefreshTokenError> for UserServiceError {
+    fn from(error: RefreshTokenError) -> Self {
+        match error {
+            RefreshTokenError::DatabaseError(e) => UserServiceError::DatabaseError(e),
+            e => UserServiceError::TokenError(e.to_string()),
+        }
+    }
+}
+
 pub struct UserService {
-    user_repo: UserRepository,
+    user_repo: Arc<dyn UserStore>,
+    refresh_tokens: RefreshTokenService,
+    jwt: JwtConfig,
 }
 
 impl UserService {
-    pub fn new(pool: MySqlPool) -> Self {
+    /// 用户存储和刷新令牌存储可替换，测试中可使用内存实现
+    pub fn new(user_store: Arc<dyn UserStore>, token_store: Arc<dyn RefreshTokenStore>, jwt: JwtConfig) -> Self {
         Self {
-            user_repo: UserRepository::new(pool),
+            user_repo: user_store,
+            refresh_tokens: RefreshTokenService::with_ttl(token_store, Duration::days(jwt.refresh_token_ttl_days)),
+            jwt,
         }
     }
 
+    /// 签发访问令牌
+    fn access_token(&self, user: &User) -> Result<String, UserServiceError> {
+        let claims = Claims::new(user, "access", self.jwt.access_token_ttl_secs);
+        Ok(claims.generate_token(&self.jwt.secret)?)
+    }
+
     /// 获取用户列表
     pub async fn list_users(&self) -> Result<Vec<User>, UserServiceError> {
         self.user_repo.list_users().await.map_err(UserServiceError::from)
@@ -77,7 +100,8 @@ impl UserService {
     }
 
     /// 用户登录
-    pub async fn login(&self, payload: &LoginRequest) -> Result<(String, String, User), UserServiceError> {
+    /// device_info 为客户端设备信息 (如 User-Agent)，随刷新令牌一同保存
+    pub async fn login(&self, payload: &LoginRequest, device_info: Option<&str>) -> Result<(String, String, User), UserServiceError> {
         // 查询用户信息
         let user = self.user_repo.find_by_identifier(&payload.identifier).await?;
 
@@ -91,19 +115,11 @@ impl UserService {
             return Err(UserServiceError::AuthenticationError("用户名或密码错误".to_string()));
         }
 
-        // 获取JWT密钥
-        let jwt_secret = env::var("JWT_SECRET")
-            .unwrap_or_else(|_| "secret-development-key".to_string());
-
         // 生成访问令牌
-        let access_claims = Claims::new(&user, "access", 3600); // 1小时过期
-        let access_token = access_claims.generate_token(&jwt_secret)?;
+        let access_token = self.access_token(&user)?;
 
-        // 生成刷新令牌
-        let refresh_token = generate_refresh_token();
-        
-        // 存储刷新令牌
-        store_refresh_token(refresh_token.clone(), user.user_id, user.username.clone()).await;
+        // 生成并存储刷新令牌
+        let refresh_token = self.refresh_tokens.issue(user.user_id, device_info).await?;
 
         Ok((access_token, refresh_token, user))
     }
@@ -142,11 +158,12 @@ impl UserService {
     }
 
     /// 刷新访问令牌
-    pub async fn refresh_token(&self, payload: &RefreshTokenRequest) -> Result<(String, String), UserServiceError> {
-        // 验证刷新令牌
-        let (user_id, username) = validate_refresh_token(&payload.refresh_token)
-            .await
-            .ok_or(UserServiceError::TokenError("无效的刷新令牌".to_string()))?;
+    /// 旧的刷新令牌会被撤销并轮换为新令牌，过期或已轮换的令牌将被拒绝
+    pub async fn refresh_token(&self, payload: &RefreshTokenRequest, device_info: Option<&str>) -> Result<(String, String), UserServiceError> {
+        // 验证并轮换刷新令牌
+        let (user_id, new_refresh_token) = self.refresh_tokens
+            .rotate(&payload.refresh_token, device_info)
+            .await?;
 
         // 获取用户信息，显式处理 RowNotFound 返回自定义错误
         let user = match self.user_repo.find_by_id(user_id).await {
@@ -155,28 +172,16 @@ impl UserService {
             Err(e) => return Err(UserServiceError::from(e)),
         };
 
-        // 获取JWT密钥
-        let jwt_secret = env::var("JWT_SECRET")
-            .unwrap_or_else(|_| "secret-development-key".to_string());
-
         // 生成新的访问令牌
-        let access_claims = Claims::new(&user, "access", 3600); // 1小时过期
-        let access_token = access_claims.generate_token(&jwt_secret)?;
-
-        // 生成新的刷新令牌
-        let new_refresh_token = generate_refresh_token();
-        
-        // 删除旧的刷新令牌，存储新的刷新令牌
-        remove_refresh_token(&payload.refresh_token).await;
-        store_refresh_token(new_refresh_token.clone(), user.user_id, user.username.clone()).await;
+        let access_token = self.access_token(&user)?;
 
         Ok((access_token, new_refresh_token))
     }
 
     /// 用户登出
     pub async fn logout(&self, payload: &LogoutRequest) -> Result<(), UserServiceError> {
-        // 移除刷新令牌
-        remove_refresh_token(&payload.refresh_token).await;
+        // 撤销刷新令牌
+        self.refresh_tokens.revoke(&payload.refresh_token).await?;
         Ok(())
     }
 
@@ -0,0 +1,269 @@
+//! 钱包业务逻辑层
+//!
+//! 负责余额查询、充值和管理员调整余额。
+//! 充值记录先以 pending 状态落库，再向支付渠道下单；渠道返回处理中时保留记录，
+//! 查询钱包或充值记录时向渠道同步结果，成功后入账。
+//! 余额支付和退款到余额由支付业务层在订单结算事务中完成
+
+use std::fmt;
+use std::sync::Arc;
+
+use sqlx::mysql::MySqlPool;
+use uuid::Uuid;
+
+use crate::models::money::Money;
+use crate::models::pagination::{Page, PageQuery};
+use crate::models::payment::PaymentMethod;
+use crate::models::wallet::{
+    AdjustBalanceRequest, BalanceTransaction, LedgerEntry, NewTopup, TopUpRequest, TopupStatus, TransactionReference,
+    TransactionType, Wallet, WalletTopup,
+};
+use crate::repositories::WalletRepository;
+use crate::services::payment_provider::{
+    PaymentIntent, PaymentProvider, PaymentProviderError, ProviderPayment, ProviderPaymentStatus,
+};
+
+/// 单笔充值上限
+pub const MAX_TOP_UP_AMOUNT: Money = Money::from_yuan(50_000);
+
+/// balance 列为 DECIMAL(10,2)，单笔变动金额的绝对值必须小于该值
+const AMOUNT_LIMIT: Money = Money::from_yuan(100_000_000);
+
+#[derive(Debug)]
+pub enum WalletServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Conflict(String),
+    ProviderError(PaymentProviderError),
+}
+
+impl fmt::Display for WalletServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalletServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            WalletServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            WalletServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            WalletServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+            WalletServiceError::ProviderError(e) => write!(f, "支付渠道错误: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for WalletServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => WalletServiceError::NotFound("用户或充值记录不存在".to_string()),
+            e => WalletServiceError::DatabaseError(e),
+        }
+    }
+}
+
+impl From<PaymentProviderError> for WalletServiceError {
+    fn from(error: PaymentProviderError) -> Self {
+        WalletServiceError::ProviderError(error)
+    }
+}
+
+/// 校验金额非零且在 DECIMAL(10,2) 可表示的范围内
+fn validate_amount(amount: Money) -> Result<(), String> {
+    if amount.is_zero() {
+        return Err("金额不能为0".to_string());
+    }
+    if amount.abs() >= AMOUNT_LIMIT {
+        return Err("金额超出范围".to_string());
+    }
+    Ok(())
+}
+
+pub struct WalletService {
+    wallet_repo: WalletRepository,
+    provider: Arc<dyn PaymentProvider>,
+}
+
+impl WalletService {
+    /// 使用指定的支付渠道充值
+    pub fn new(pool: MySqlPool, provider: Arc<dyn PaymentProvider>) -> Self {
+        Self {
+            wallet_repo: WalletRepository::new(pool),
+            provider,
+        }
+    }
+
+    /// 当前余额和分页流水，处理中的充值会先向渠道查询最新结果
+    pub async fn get_wallet(&self, user_id: i32, page: &PageQuery) -> Result<Wallet, WalletServiceError> {
+        for topup in self.wallet_repo.list_processing_topups(user_id).await? {
+            self.sync_topup(&topup).await?;
+        }
+        let balance = self.wallet_repo.balance(user_id).await?;
+        let (items, total) = self.wallet_repo.list_transactions(user_id, page).await?;
+        Ok(Wallet {
+            balance,
+            transactions: Page::new(items, page, total),
+        })
+    }
+
+    /// 通过支付渠道充值，渠道确认成功后入账
+    /// 渠道返回处理中时返回 processing 状态的充值记录，之后查询时再同步结果
+    pub async fn top_up(&self, user_id: i32, payload: &TopUpRequest) -> Result<WalletTopup, WalletServiceError> {
+        validate_amount(payload.amount).map_err(WalletServiceError::ValidationError)?;
+        if payload.amount.is_negative() || payload.amount > MAX_TOP_UP_AMOUNT {
+            return Err(WalletServiceError::ValidationError(format!(
+                "充值金额须在 0 到 {} 之间",
+                MAX_TOP_UP_AMOUNT
+            )));
+        }
+        if payload.payment_method == PaymentMethod::Balance {
+            return Err(WalletServiceError::ValidationError("不能使用余额充值".to_string()));
+        }
+
+        let topup = self
+            .wallet_repo
+            .create_topup(&NewTopup {
+                topup_id: generate_topup_id(),
+                user_id,
+                payment_method: payload.payment_method,
+                amount: payload.amount,
+            })
+            .await?;
+
+        let intent = PaymentIntent {
+            payment_id: &topup.topup_id,
+            method: topup.payment_method,
+            amount: topup.amount,
+            description: "余额充值",
+        };
+        let result = match self.provider.create_intent(intent).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("充值 {} 下单失败 ({}): {}", topup.topup_id, self.provider.name(), e);
+                self.wallet_repo.mark_topup_failed(&topup.topup_id, None).await?;
+                return Err(e.into());
+            }
+        };
+
+        self.apply_provider_result(&topup, &result).await?;
+        Ok(self.wallet_repo.find_topup(&topup.topup_id).await?)
+    }
+
+    /// 查询充值记录，处理中的充值会先向渠道查询最新结果
+    pub async fn get_topup(&self, user_id: i32, topup_id: &str) -> Result<WalletTopup, WalletServiceError> {
+        let topup = self.wallet_repo.find_topup(topup_id).await?;
+        if topup.user_id != user_id {
+            return Err(WalletServiceError::NotFound("充值记录不存在".to_string()));
+        }
+        if self.sync_topup(&topup).await? {
+            Ok(self.wallet_repo.find_topup(topup_id).await?)
+        } else {
+            Ok(topup)
+        }
+    }
+
+    /// 管理员调整余额，扣减后余额不能为负
+    pub async fn adjust(
+        &self,
+        user_id: i32,
+        operator_id: i32,
+        payload: &AdjustBalanceRequest,
+    ) -> Result<BalanceTransaction, WalletServiceError> {
+        validate_amount(payload.amount).map_err(WalletServiceError::ValidationError)?;
+        let remark = payload.remark.trim();
+        if remark.is_empty() {
+            return Err(WalletServiceError::ValidationError("请填写调整原因".to_string()));
+        }
+
+        let entry = LedgerEntry {
+            user_id,
+            transaction_type: TransactionType::Adjustment,
+            amount: payload.amount,
+            reference: TransactionReference::Manual,
+            operator_id: Some(operator_id),
+            remark: Some(remark.to_string()),
+        };
+        let transaction = self
+            .wallet_repo
+            .record(&entry)
+            .await?
+            .ok_or_else(|| WalletServiceError::Conflict("余额不足，无法扣减".to_string()))?;
+        tracing::info!("管理员 {} 调整用户 {} 余额 {}: {}", operator_id, user_id, payload.amount, remark);
+        Ok(transaction)
+    }
+
+    /// 向渠道同步处理中的充值，返回是否有状态变化
+    async fn sync_topup(&self, topup: &WalletTopup) -> Result<bool, WalletServiceError> {
+        if topup.topup_status != TopupStatus::Processing {
+            return Ok(false);
+        }
+        let Some(trade_no) = topup.thirdparty_trade_no.as_deref() else {
+            return Ok(false);
+        };
+        match self.provider.query(trade_no).await {
+            Ok(result) => {
+                self.apply_provider_result(topup, &result).await?;
+                Ok(result.status != ProviderPaymentStatus::Processing)
+            }
+            Err(e) => {
+                tracing::warn!("查询充值 {} 失败: {}", topup.topup_id, e);
+                Ok(false)
+            }
+        }
+    }
+
+    /// 根据渠道返回的结果更新充值记录，成功时入账
+    async fn apply_provider_result(
+        &self,
+        topup: &WalletTopup,
+        result: &ProviderPayment,
+    ) -> Result<(), WalletServiceError> {
+        match result.status {
+            ProviderPaymentStatus::Success => {
+                let entry = LedgerEntry {
+                    user_id: topup.user_id,
+                    transaction_type: TransactionType::Topup,
+                    amount: topup.amount,
+                    reference: TransactionReference::Topup(result.trade_no.clone()),
+                    operator_id: None,
+                    remark: Some(format!("{} 充值", topup.payment_method)),
+                };
+                let settled = self.wallet_repo.settle_topup(&topup.topup_id, &result.trade_no, &entry).await?;
+                if settled.is_some() {
+                    tracing::info!("用户 {} 充值 {} 入账: {}", topup.user_id, topup.amount, topup.topup_id);
+                }
+            }
+            ProviderPaymentStatus::Processing => {
+                self.wallet_repo.mark_topup_processing(&topup.topup_id, &result.trade_no).await?;
+            }
+            ProviderPaymentStatus::Failed => {
+                self.wallet_repo.mark_topup_failed(&topup.topup_id, Some(&result.trade_no)).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 生成充值ID: T + 16位随机串
+fn generate_topup_id() -> String {
+    format!("T{}", &Uuid::new_v4().simple().to_string()[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_amount() {
+        assert!(validate_amount(Money::from_yuan(10)).is_ok());
+        assert!(validate_amount(Money::from_fen(-1)).is_ok());
+        assert!(validate_amount(Money::ZERO).is_err());
+        assert!(validate_amount(AMOUNT_LIMIT).is_err());
+        assert!(validate_amount(-AMOUNT_LIMIT).is_err());
+    }
+
+    #[test]
+    fn test_generate_topup_id() {
+        let id = generate_topup_id();
+        assert!(id.starts_with('T'));
+        assert_eq!(id.len(), 17);
+        assert_ne!(id, generate_topup_id());
+    }
+}
@@ -0,0 +1,352 @@
+//! 服务人员业务逻辑层
+//!
+//! 客户提交入驻申请，管理员审核通过后成为服务人员；
+//! 服务人员维护自己的资料并切换是否接单，任何人都可以搜索和查看服务人员的公开资料
+
+use std::fmt;
+
+use sqlx::mysql::MySqlPool;
+
+use crate::models::money::Money;
+use crate::models::pagination::{Page, PageQuery};
+use crate::models::worker::{
+    ApplicationDecisionRequest, ApplicationStatus, UpdateWorkerProfileRequest, WorkerApplication,
+    WorkerApplicationRequest, WorkerProfile, WorkerPublicProfile, WorkerSearchQuery,
+};
+use crate::repositories::{
+    CreateApplicationOutcome, ReviewApplicationOutcome, WorkerApplicationRepository, WorkerRepository,
+};
+use crate::services::notifier::Notifier;
+
+/// 技能标签和服务区域的最大个数
+const MAX_TAGS: usize = 20;
+
+/// 单个技能标签或服务区域的最大字数
+const MAX_TAG_CHARS: usize = 50;
+
+/// 个人简介最大字数
+const MAX_BIO_CHARS: usize = 500;
+
+/// 每日接单上限的取值范围
+const DAILY_ORDER_LIMIT: std::ops::RangeInclusive<i32> = 1..=10;
+
+/// worker_profiles.hourly_rate 为 DECIMAL(8, 2)
+const MAX_HOURLY_RATE: Money = Money::from_fen(99_999_999);
+
+#[derive(Debug)]
+pub enum WorkerServiceError {
+    DatabaseError(sqlx::Error),
+    NotFound(String),
+    ValidationError(String),
+    Conflict(String),
+}
+
+impl fmt::Display for WorkerServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkerServiceError::DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            WorkerServiceError::NotFound(msg) => write!(f, "未找到: {}", msg),
+            WorkerServiceError::ValidationError(msg) => write!(f, "参数错误: {}", msg),
+            WorkerServiceError::Conflict(msg) => write!(f, "状态冲突: {}", msg),
+        }
+    }
+}
+
+impl From<sqlx::Error> for WorkerServiceError {
+    fn from(error: sqlx::Error) -> Self {
+        WorkerServiceError::DatabaseError(error)
+    }
+}
+
+/// 去掉首尾空白、空项和重复项，并校验个数和长度
+fn normalize_tags(name: &str, tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if tag.chars().count() > MAX_TAG_CHARS {
+            return Err(format!("{}每项不能超过{}个字符", name, MAX_TAG_CHARS));
+        }
+        if !normalized.iter().any(|existing| existing == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("{}最多{}项", name, MAX_TAGS));
+    }
+    Ok(normalized)
+}
+
+/// 申请和资料共有的可编辑字段
+struct ProfileFields {
+    bio: Option<String>,
+    skills: Vec<String>,
+    service_area: Vec<String>,
+}
+
+/// 校验时薪、简介、技能和服务区域，服务区域决定能否被派单，不能为空
+fn normalize_profile_fields(
+    hourly_rate: Money,
+    bio: Option<&str>,
+    skills: &[String],
+    service_area: &[String],
+) -> Result<ProfileFields, String> {
+    if !hourly_rate.is_positive() {
+        return Err("时薪必须大于0".to_string());
+    }
+    if hourly_rate > MAX_HOURLY_RATE {
+        return Err(format!("时薪不能超过{}", MAX_HOURLY_RATE));
+    }
+    let bio = bio.map(str::trim).filter(|bio| !bio.is_empty());
+    if bio.is_some_and(|bio| bio.chars().count() > MAX_BIO_CHARS) {
+        return Err(format!("个人简介不能超过{}字", MAX_BIO_CHARS));
+    }
+    let skills = normalize_tags("技能标签", skills)?;
+    let service_area = normalize_tags("服务区域", service_area)?;
+    if service_area.is_empty() {
+        return Err("服务区域不能为空".to_string());
+    }
+    Ok(ProfileFields { bio: bio.map(str::to_string), skills, service_area })
+}
+
+fn normalize_application(payload: &WorkerApplicationRequest) -> Result<WorkerApplicationRequest, String> {
+    let real_name = payload.real_name.trim();
+    if real_name.is_empty() {
+        return Err("真实姓名不能为空".to_string());
+    }
+    if real_name.chars().count() > 50 {
+        return Err("真实姓名不能超过50个字符".to_string());
+    }
+    let ProfileFields { bio, skills, service_area } =
+        normalize_profile_fields(payload.hourly_rate, payload.bio.as_deref(), &payload.skills, &payload.service_area)?;
+    Ok(WorkerApplicationRequest {
+        service_category_id: payload.service_category_id,
+        real_name: real_name.to_string(),
+        hourly_rate: payload.hourly_rate,
+        bio,
+        skills,
+        service_area,
+    })
+}
+
+fn normalize_profile(payload: &UpdateWorkerProfileRequest) -> Result<UpdateWorkerProfileRequest, String> {
+    if !DAILY_ORDER_LIMIT.contains(&payload.max_daily_orders) {
+        return Err(format!(
+            "每日最大接单数须在{}到{}之间",
+            DAILY_ORDER_LIMIT.start(),
+            DAILY_ORDER_LIMIT.end()
+        ));
+    }
+    let ProfileFields { bio, skills, service_area } =
+        normalize_profile_fields(payload.hourly_rate, payload.bio.as_deref(), &payload.skills, &payload.service_area)?;
+    Ok(UpdateWorkerProfileRequest {
+        hourly_rate: payload.hourly_rate,
+        bio,
+        skills,
+        service_area,
+        max_daily_orders: payload.max_daily_orders,
+    })
+}
+
+/// 审核意见，驳回时必须填写
+fn normalize_remark(decision: ApplicationStatus, remark: Option<&str>) -> Result<Option<String>, String> {
+    let remark = remark.map(str::trim).filter(|remark| !remark.is_empty());
+    if decision == ApplicationStatus::Rejected && remark.is_none() {
+        return Err("驳回申请时必须填写审核意见".to_string());
+    }
+    if remark.is_some_and(|remark| remark.chars().count() > 255) {
+        return Err("审核意见不能超过255个字符".to_string());
+    }
+    Ok(remark.map(str::to_string))
+}
+
+pub struct WorkerService {
+    worker_repo: WorkerRepository,
+    application_repo: WorkerApplicationRepository,
+    notifier: Notifier,
+}
+
+impl WorkerService {
+    pub fn new(pool: MySqlPool, notifier: Notifier) -> Self {
+        Self {
+            worker_repo: WorkerRepository::new(pool.clone()),
+            application_repo: WorkerApplicationRepository::new(pool.clone()),
+            notifier,
+        }
+    }
+
+    /// 客户申请成为服务人员
+    pub async fn apply(
+        &self,
+        user_id: i32,
+        payload: &WorkerApplicationRequest,
+    ) -> Result<WorkerApplication, WorkerServiceError> {
+        let application = normalize_application(payload).map_err(WorkerServiceError::ValidationError)?;
+        match self.application_repo.create(user_id, &application).await? {
+            CreateApplicationOutcome::Created(created) => Ok(*created),
+            CreateApplicationOutcome::PendingExists => {
+                Err(WorkerServiceError::Conflict("已有待审核的入驻申请".to_string()))
+            }
+            CreateApplicationOutcome::NotCustomer => {
+                Err(WorkerServiceError::Conflict("只有客户可以申请成为服务人员".to_string()))
+            }
+            CreateApplicationOutcome::CategoryNotFound => {
+                Err(WorkerServiceError::ValidationError("服务分类不存在".to_string()))
+            }
+        }
+    }
+
+    /// 用户自己的入驻申请
+    pub async fn list_my_applications(&self, user_id: i32) -> Result<Vec<WorkerApplication>, WorkerServiceError> {
+        Ok(self.application_repo.list_by_user(user_id).await?)
+    }
+
+    /// 入驻申请列表 (管理员)
+    pub async fn list_applications(
+        &self,
+        status: Option<ApplicationStatus>,
+        page: &PageQuery,
+    ) -> Result<Page<WorkerApplication>, WorkerServiceError> {
+        let (applications, total) = self.application_repo.list(status, page).await?;
+        Ok(Page::new(applications, page, total))
+    }
+
+    /// 审核入驻申请并通知申请人
+    pub async fn review_application(
+        &self,
+        application_id: i32,
+        reviewer_id: i32,
+        decision: ApplicationStatus,
+        payload: &ApplicationDecisionRequest,
+    ) -> Result<WorkerApplication, WorkerServiceError> {
+        let remark = normalize_remark(decision, payload.remark.as_deref()).map_err(WorkerServiceError::ValidationError)?;
+        let outcome = self.application_repo
+            .review(application_id, reviewer_id, decision, remark.as_deref())
+            .await?;
+        let application = match outcome {
+            ReviewApplicationOutcome::Reviewed(application) => *application,
+            ReviewApplicationOutcome::NotFound => {
+                return Err(WorkerServiceError::NotFound("入驻申请不存在".to_string()));
+            }
+            ReviewApplicationOutcome::AlreadyReviewed => {
+                return Err(WorkerServiceError::Conflict("该申请已审核".to_string()));
+            }
+            ReviewApplicationOutcome::NotCustomer => {
+                return Err(WorkerServiceError::Conflict("申请人已不是客户，无法开通服务人员身份".to_string()));
+            }
+        };
+        self.notifier.worker_application_reviewed(&application).await;
+        Ok(application)
+    }
+
+    /// 服务人员自己的资料
+    pub async fn get_my_profile(&self, worker_id: i32) -> Result<WorkerProfile, WorkerServiceError> {
+        self.worker_repo.find_profile(worker_id).await?
+            .ok_or_else(|| WorkerServiceError::NotFound("服务人员资料不存在".to_string()))
+    }
+
+    /// 修改自己的资料
+    pub async fn update_my_profile(
+        &self,
+        worker_id: i32,
+        payload: &UpdateWorkerProfileRequest,
+    ) -> Result<WorkerProfile, WorkerServiceError> {
+        let profile = normalize_profile(payload).map_err(WorkerServiceError::ValidationError)?;
+        self.worker_repo.update_profile(worker_id, &profile).await?
+            .ok_or_else(|| WorkerServiceError::NotFound("服务人员资料不存在".to_string()))
+    }
+
+    /// 切换是否接单，关闭后不再参与自动派单
+    pub async fn set_available(&self, worker_id: i32, is_available: bool) -> Result<WorkerProfile, WorkerServiceError> {
+        self.worker_repo.set_available(worker_id, is_available).await?
+            .ok_or_else(|| WorkerServiceError::NotFound("服务人员资料不存在".to_string()))
+    }
+
+    /// 搜索服务人员
+    pub async fn search_workers(
+        &self,
+        query: &WorkerSearchQuery,
+        page: &PageQuery,
+    ) -> Result<Page<WorkerPublicProfile>, WorkerServiceError> {
+        if query.min_rating.is_some_and(|rating| !(0.0..=5.0).contains(&rating)) {
+            return Err(WorkerServiceError::ValidationError("评分须在0到5之间".to_string()));
+        }
+        let query = WorkerSearchQuery {
+            area: query.area.as_deref().map(str::trim).filter(|area| !area.is_empty()).map(str::to_string),
+            ..query.clone()
+        };
+        let (workers, total) = self.worker_repo.search(&query, page).await?;
+        Ok(Page::new(workers, page, total))
+    }
+
+    /// 服务人员公开资料
+    pub async fn get_worker(&self, worker_id: i32) -> Result<WorkerPublicProfile, WorkerServiceError> {
+        self.worker_repo.find_public_profile(worker_id).await?
+            .ok_or_else(|| WorkerServiceError::NotFound("服务人员不存在".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn application() -> WorkerApplicationRequest {
+        WorkerApplicationRequest {
+            service_category_id: 1,
+            real_name: " 张三 ".to_string(),
+            hourly_rate: Money::from_yuan(50),
+            bio: Some("  ".to_string()),
+            skills: tags(&["擦玻璃", " 擦玻璃 ", ""]),
+            service_area: tags(&["朝阳区", "海淀区"]),
+        }
+    }
+
+    #[test]
+    fn test_normalize_application() {
+        let normalized = normalize_application(&application()).unwrap();
+        assert_eq!(normalized.real_name, "张三");
+        assert_eq!(normalized.bio, None);
+        assert_eq!(normalized.skills, tags(&["擦玻璃"]));
+        assert_eq!(normalized.service_area, tags(&["朝阳区", "海淀区"]));
+
+        let no_area = WorkerApplicationRequest { service_area: tags(&[" "]), ..application() };
+        assert!(normalize_application(&no_area).is_err());
+        let free = WorkerApplicationRequest { hourly_rate: Money::ZERO, ..application() };
+        assert!(normalize_application(&free).is_err());
+        let nameless = WorkerApplicationRequest { real_name: String::new(), ..application() };
+        assert!(normalize_application(&nameless).is_err());
+    }
+
+    #[test]
+    fn test_normalize_profile_daily_limit() {
+        let profile = |max_daily_orders| UpdateWorkerProfileRequest {
+            hourly_rate: Money::from_yuan(60),
+            bio: None,
+            skills: Vec::new(),
+            service_area: tags(&["朝阳区"]),
+            max_daily_orders,
+        };
+        assert!(normalize_profile(&profile(3)).is_ok());
+        assert!(normalize_profile(&profile(0)).is_err());
+        assert!(normalize_profile(&profile(11)).is_err());
+    }
+
+    #[test]
+    fn test_normalize_tags_limits() {
+        let many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("区{}", i)).collect();
+        assert!(normalize_tags("服务区域", &many).is_err());
+        assert!(normalize_tags("服务区域", &["长".repeat(MAX_TAG_CHARS + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_rejection_requires_remark() {
+        assert!(normalize_remark(ApplicationStatus::Rejected, Some(" ")).is_err());
+        assert_eq!(normalize_remark(ApplicationStatus::Approved, Some(" ")), Ok(None));
+        assert_eq!(
+            normalize_remark(ApplicationStatus::Rejected, Some("资料不完整")),
+            Ok(Some("资料不完整".to_string()))
+        );
+    }
+}
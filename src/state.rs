@@ -0,0 +1,214 @@
+//! 应用共享状态
+//!
+//! `AppState` 在启动时构造一次，持有连接池、配置、可替换的基础组件
+//! (数据存储、事件中心、支付渠道) 以及由它们组装好的业务服务。
+//! 处理器通过 `State<T>` 只提取自己需要的部分，`T` 由 `FromRef` 从 `AppState` 中取出
+
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use sqlx::mysql::MySqlPool;
+
+use crate::config::Config;
+use crate::models::order::Order;
+use crate::models::user::UserAddress;
+use crate::repositories::{
+    AddressRepository, AddressStore, ComplaintRepository, CouponRepository, CouponStore, InMemoryAddressStore,
+    InMemoryCouponStore, InMemoryOrderStore, InMemoryPaymentStore, InMemoryRefreshTokenStore, InMemoryServiceStore,
+    InMemoryUserStore, OrderRepository, OrderStore, PaymentRepository, PaymentStore, RefreshTokenRepository, RefreshTokenStore,
+    ServiceRepository, ServiceStore, UserRepository, UserStore,
+};
+use crate::services::{
+    address_service::AddressService,
+    complaint_service::ComplaintService,
+    coupon_service::CouponService,
+    dispatch_service::DispatchService,
+    event_hub::{EventHub, InMemoryEventHub},
+    notification_service::NotificationService,
+    notifier::Notifier,
+    order_service::OrderService,
+    payment_provider::{provider_from_config, MockPaymentProvider, PaymentProvider},
+    payment_service::PaymentService,
+    review_service::ReviewService,
+    schedule_service::ScheduleService,
+    service_service::ServiceService,
+    user_service::UserService,
+    wallet_service::WalletService,
+    worker_service::WorkerService,
+};
+
+/// 可替换的基础组件，测试中可换成内存实现或模拟实现
+#[derive(Clone)]
+pub struct Components {
+    pub user_store: Arc<dyn UserStore>,
+    pub service_store: Arc<dyn ServiceStore>,
+    pub order_store: Arc<dyn OrderStore>,
+    pub payment_store: Arc<dyn PaymentStore>,
+    pub address_store: Arc<dyn AddressStore>,
+    pub coupon_store: Arc<dyn CouponStore>,
+    pub token_store: Arc<dyn RefreshTokenStore>,
+    pub event_hub: Arc<dyn EventHub>,
+    pub payment_provider: Arc<dyn PaymentProvider>,
+}
+
+impl Components {
+    /// 按配置创建默认组件：MySQL 存储、进程内事件中心、配置指定的支付渠道
+    pub fn from_config(pool: &MySqlPool, config: &Config) -> Self {
+        Self {
+            user_store: Arc::new(UserRepository::new(pool.clone())),
+            service_store: Arc::new(ServiceRepository::new(pool.clone())),
+            order_store: Arc::new(OrderRepository::new(pool.clone())),
+            payment_store: Arc::new(PaymentRepository::new(pool.clone())),
+            address_store: Arc::new(AddressRepository::new(pool.clone())),
+            coupon_store: Arc::new(CouponRepository::new(pool.clone())),
+            token_store: Arc::new(RefreshTokenRepository::new(pool.clone())),
+            event_hub: Arc::new(InMemoryEventHub::default()),
+            payment_provider: provider_from_config(&config.payment),
+        }
+    }
+
+    /// 全部使用内存实现，配合延迟连接的连接池可在没有数据库时测试真实的服务和路由
+    pub fn in_memory(config: &Config) -> Self {
+        Self::in_memory_with(config, Vec::new(), Vec::new())
+    }
+
+    /// 全部使用内存实现，订单和地址存储预置指定数据
+    /// 支付存储与订单存储共享同一份订单，支付结算会更新订单的支付状态；
+    /// 订单存储通过同一地址存储校验下单地址
+    pub fn in_memory_with(config: &Config, orders: Vec<Order>, addresses: Vec<UserAddress>) -> Self {
+        let address_store: Arc<dyn AddressStore> = Arc::new(InMemoryAddressStore::with_addresses(addresses));
+        let order_store = Arc::new(InMemoryOrderStore::with_orders(orders).with_address_store(address_store.clone()));
+        Self {
+            user_store: Arc::new(InMemoryUserStore::new()),
+            service_store: Arc::new(InMemoryServiceStore::new()),
+            payment_store: Arc::new(InMemoryPaymentStore::new(order_store.clone())),
+            order_store,
+            address_store,
+            coupon_store: Arc::new(InMemoryCouponStore::new()),
+            token_store: Arc::new(InMemoryRefreshTokenStore::new()),
+            event_hub: Arc::new(InMemoryEventHub::default()),
+            payment_provider: Arc::new(MockPaymentProvider::new(config.payment.mock_outcome)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub config: Arc<Config>,
+    pub token_store: Arc<dyn RefreshTokenStore>,
+    pub event_hub: Arc<dyn EventHub>,
+    pub payment_provider: Arc<dyn PaymentProvider>,
+    pub notifier: Notifier,
+    pub user_service: Arc<UserService>,
+    pub address_service: Arc<AddressService>,
+    pub wallet_service: Arc<WalletService>,
+    pub service_service: Arc<ServiceService>,
+    pub schedule_service: Arc<ScheduleService>,
+    pub order_service: Arc<OrderService>,
+    pub dispatch_service: Arc<DispatchService>,
+    pub payment_service: Arc<PaymentService>,
+    pub review_service: Arc<ReviewService>,
+    pub complaint_service: Arc<ComplaintService>,
+    pub coupon_service: Arc<CouponService>,
+    pub worker_service: Arc<WorkerService>,
+    pub notification_service: Arc<NotificationService>,
+}
+
+impl AppState {
+    /// 使用默认组件构造
+    pub fn new(pool: MySqlPool, config: Config) -> Self {
+        let components = Components::from_config(&pool, &config);
+        Self::with_components(pool, config, components)
+    }
+
+    /// 使用指定组件构造，业务服务共享同一组组件
+    pub fn with_components(pool: MySqlPool, config: Config, components: Components) -> Self {
+        let Components {
+            user_store,
+            service_store,
+            order_store,
+            payment_store,
+            address_store,
+            coupon_store,
+            token_store,
+            event_hub,
+            payment_provider,
+        } = components;
+        let notifier = Notifier::new(pool.clone(), event_hub.clone());
+        let payment_service = Arc::new(PaymentService::new(
+            payment_store,
+            order_store.clone(),
+            payment_provider.clone(),
+            notifier.clone(),
+        ));
+
+        Self {
+            user_service: Arc::new(UserService::new(user_store.clone(), token_store.clone(), config.jwt.clone())),
+            address_service: Arc::new(AddressService::new(address_store.clone())),
+            wallet_service: Arc::new(WalletService::new(pool.clone(), payment_provider.clone())),
+            service_service: Arc::new(ServiceService::new(pool.clone())),
+            schedule_service: Arc::new(ScheduleService::new(pool.clone())),
+            order_service: Arc::new(OrderService::new(
+                order_store.clone(),
+                service_store,
+                coupon_store.clone(),
+                notifier.clone(),
+            )),
+            dispatch_service: Arc::new(DispatchService::new(pool.clone(), order_store.clone(), notifier.clone())),
+            review_service: Arc::new(ReviewService::new(pool.clone(), order_store.clone())),
+            complaint_service: Arc::new(ComplaintService::new(
+                Arc::new(ComplaintRepository::new(pool.clone())),
+                order_store,
+                user_store,
+                payment_service.clone(),
+                notifier.clone(),
+            )),
+            coupon_service: Arc::new(CouponService::new(coupon_store)),
+            worker_service: Arc::new(WorkerService::new(pool.clone(), notifier.clone())),
+            notification_service: Arc::new(NotificationService::new(pool.clone())),
+            payment_service,
+            notifier,
+            pool,
+            config: Arc::new(config),
+            token_store,
+            event_hub,
+            payment_provider,
+        }
+    }
+}
+
+/// 为 `AppState` 的字段实现 `FromRef`，处理器可直接提取 `State<字段类型>`
+macro_rules! impl_from_ref {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            impl FromRef<AppState> for $ty {
+                fn from_ref(state: &AppState) -> Self {
+                    state.$field.clone()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_ref!(
+    pool: MySqlPool,
+    config: Arc<Config>,
+    token_store: Arc<dyn RefreshTokenStore>,
+    event_hub: Arc<dyn EventHub>,
+    payment_provider: Arc<dyn PaymentProvider>,
+    notifier: Notifier,
+    user_service: Arc<UserService>,
+    address_service: Arc<AddressService>,
+    wallet_service: Arc<WalletService>,
+    service_service: Arc<ServiceService>,
+    schedule_service: Arc<ScheduleService>,
+    order_service: Arc<OrderService>,
+    dispatch_service: Arc<DispatchService>,
+    payment_service: Arc<PaymentService>,
+    review_service: Arc<ReviewService>,
+    complaint_service: Arc<ComplaintService>,
+    coupon_service: Arc<CouponService>,
+    worker_service: Arc<WorkerService>,
+    notification_service: Arc<NotificationService>,
+);
@@ -1,12 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 use jsonwebtoken::{encode, decode, Header, Algorithm, Validation, EncodingKey, DecodingKey, errors::{Error, ErrorKind}};
-use crate::models::user::User;
-use std::collections::HashMap;
+use crate::models::user::{User, UserType};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
-use once_cell::sync::Lazy;
-use tokio::sync::RwLock;
-use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize,Clone)]
 pub struct Claims {
@@ -19,7 +16,7 @@ pub struct Claims {
     // 添加用户相关信息
     pub user_id: i32,
     pub username: String,
-    pub user_type: String,
+    pub user_type: UserType,
     // Token类型(access或refresh)
     pub token_type: String,
 }
@@ -33,12 +30,7 @@ impl Claims {
             .as_secs() as usize;
 
         Self {
-            // 根据token类型设置aud字段
-            aud: match token_type {
-                "access" => "access-token-audience".to_string(),
-                "refresh" => "refresh-token-audience".to_string(),
-                _ => "default-audience".to_string(),
-            },
+            aud: audience(token_type).to_string(),
             exp: now + expiration_seconds, // 设置过期时间为当前时间加上指定秒数
             iat: now, // 设置签发时间为当前时间
             iss: "jz-service".to_string(), // 设置签发者
@@ -46,11 +38,28 @@ impl Claims {
             sub: user.user_id.to_string(), // 设置subject为用户ID
             user_id: user.user_id,
             username: user.username.clone(),
-            user_type: user.user_type.clone(),
+            user_type: user.user_type,
             token_type: token_type.to_string(),
         }
     }
 
+    // 基于已验证的令牌为同一用户签发其他类型的令牌，如订阅事件流使用的短期 stream 令牌
+    pub fn reissue(&self, token_type: &str, expiration_seconds: usize) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs() as usize;
+
+        Self {
+            aud: audience(token_type).to_string(),
+            exp: now + expiration_seconds,
+            iat: now,
+            nbf: now,
+            token_type: token_type.to_string(),
+            ..self.clone()
+        }
+    }
+
     // 生成JWT token
     pub fn generate_token(&self, secret: &str) -> Result<String, Error> {
         let encoding_key = EncodingKey::from_secret(secret.as_ref());
@@ -64,7 +73,7 @@ impl Claims {
         expected_type: Option<&str>,
     ) -> Result<Claims, Error> {
         let mut validation = Validation::new(Algorithm::HS256);
-        validation.set_audience(&["access-token-audience", "refresh-token-audience"]); // 设置期望的audience
+        validation.set_audience(&["access-token-audience", "refresh-token-audience", "stream-token-audience"]); // 设置期望的audience
         validation.set_issuer(&["jz-service"]); // 设置期望的issuer
         
         // 如果指定了token类型，则验证
@@ -76,39 +85,24 @@ impl Claims {
         let decoded = decode::<Claims>(token, &decoding_key, &validation)?;
         
         // 验证token类型
-        if let Some(token_type) = expected_type {
-            if decoded.claims.token_type != token_type {
-                return Err(Error::from(ErrorKind::InvalidToken));
-            }
+        if let Some(token_type) = expected_type
+            && decoded.claims.token_type != token_type
+        {
+            return Err(Error::from(ErrorKind::InvalidToken));
         }
         
         Ok(decoded.claims)
     }
 }
 
-// 存储刷新令牌的简单内存存储
-// 在生产环境中，应该使用Redis或其他持久化存储
-type RefreshTokenStore = HashMap<String, (i32, String)>;
-static REFRESH_TOKENS: Lazy<Arc<RwLock<RefreshTokenStore>>> = Lazy::new(|| {
-    Arc::new(RwLock::new(HashMap::<String, (i32, String)>::new()))
-});
-
-// 保存刷新令牌
-pub async fn store_refresh_token(token: String, user_id: i32, username: String) {
-    let mut tokens = REFRESH_TOKENS.write().await;
-    tokens.insert(token, (user_id, username));
-}
-
-// 验证刷新令牌
-pub async fn validate_refresh_token(token: &str) -> Option<(i32, String)> {
-    let tokens = REFRESH_TOKENS.read().await;
-    tokens.get(token).cloned()
-}
-
-// 移除刷新令牌(登出时使用)
-pub async fn remove_refresh_token(token: &str) {
-    let mut tokens = REFRESH_TOKENS.write().await;
-    tokens.remove(token);
+// 根据token类型设置aud字段
+fn audience(token_type: &str) -> &'static str {
+    match token_type {
+        "access" => "access-token-audience",
+        "refresh" => "refresh-token-audience",
+        "stream" => "stream-token-audience",
+        _ => "default-audience",
+    }
 }
 
 // 生成刷新令牌
@@ -116,10 +110,17 @@ pub fn generate_refresh_token() -> String {
     Uuid::new_v4().to_string()
 }
 
+// 计算刷新令牌的 SHA-256 哈希，数据库中只保存哈希值
+pub fn hash_refresh_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sqlx::types::chrono::NaiveDateTime;
+    use crate::models::user::{UserStatus, UserType};
+    use crate::models::Money;
+    use sqlx::types::chrono::DateTime;
 
     #[test]
     fn test_generate_and_validate_token() {
@@ -129,14 +130,14 @@ mod tests {
             email: "test@example.com".to_string(),
             phone: "1234567890".to_string(),
             password_hash: "hash".to_string(),
-            user_type: "customer".to_string(),
+            user_type: UserType::Customer,
             avatar_url: None,
             real_name: None,
             is_verified: false,
-            balance: 0.0,
-            status: "active".to_string(),
-            created_at: NaiveDateTime::from_timestamp_opt(1609459200, 0).unwrap(),
-            updated_at: NaiveDateTime::from_timestamp_opt(1609459200, 0).unwrap(),
+            balance: Money::ZERO,
+            status: UserStatus::Active,
+            created_at: DateTime::from_timestamp(1609459200, 0).unwrap().naive_utc(),
+            updated_at: DateTime::from_timestamp(1609459200, 0).unwrap().naive_utc(),
         };
         
         let claims = Claims::new(&user, "access", 3600);
@@ -150,7 +151,7 @@ mod tests {
         let validated_claims = Claims::validate_token(&token, secret, Some("access")).expect("Failed to validate token");
         assert_eq!(validated_claims.user_id, 1);
         assert_eq!(validated_claims.username, "testuser");
-        assert_eq!(validated_claims.user_type, "customer");
+        assert_eq!(validated_claims.user_type, UserType::Customer);
         assert_eq!(validated_claims.token_type, "access");
     }
 }
\ No newline at end of file
@@ -1,81 +1,384 @@
-//! 集成测试示例
-//! 
-//! 这些测试需要运行中的数据库实例
+//! 集成测试
+//!
+//! 使用内存存储组装真实的服务和路由，通过 `oneshot` 发送请求，无需数据库。
+//! 连接池延迟连接到不可用的地址，通知等尽力而为的写入失败时只记录日志
+
+use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     body::Body,
     http::{self, Request, StatusCode},
-    Router,
+    middleware, Router,
+};
+use chrono::NaiveDate;
+use jz::{
+    auth_interceptor,
+    config::Config,
+    models::{
+        service::ServiceUnit, Money, Order, OrderPaymentStatus, OrderStatus, Service, ServiceAddon, TimeSlot, User,
+        UserAddress, UserStatus, UserType,
+    },
+    order_routes,
+    repositories::{InMemoryServiceStore, InMemoryUserStore},
+    state::{AppState, Components},
+    user_routes,
+    utils::jwt::Claims,
 };
-use serde_json::json;
+use serde_json::{json, Value};
+use sqlx::mysql::MySqlPoolOptions;
 use tower::ServiceExt; // for `call`, `oneshot`, and `ready`
 
-// 重新定义需要的路由函数和数据库初始化函数
-mod test_utils {
-    use axum::{routing::post, Router, http::StatusCode};
-    
-    // 模拟handler模块中的user_routes函数
-    pub fn user_routes() -> Router {
-        Router::new()
-            .route("/register", post(|| async { (StatusCode::CREATED, "Created") }))
-            .route("/login", post(|| async { (StatusCode::OK, "OK") }))
+const TEST_SECRET: &str = "integration_test_secret";
+
+const CUSTOMER_ID: i32 = 1;
+const OTHER_CUSTOMER_ID: i32 = 2;
+const WORKER_ID: i32 = 3;
+const ADMIN_ID: i32 = 4;
+
+fn test_config() -> Config {
+    let mut config = Config::default();
+    config.jwt.secret = TEST_SECRET.to_string();
+    config
+}
+
+fn test_user(user_id: i32, user_type: UserType) -> User {
+    let now = chrono::Utc::now().naive_utc();
+    User {
+        user_id,
+        username: format!("{}{}", user_type, user_id),
+        password_hash: String::new(),
+        email: format!("{}{}@example.com", user_type, user_id),
+        phone: format!("1380000000{}", user_id),
+        user_type,
+        avatar_url: None,
+        real_name: None,
+        is_verified: true,
+        balance: Money::ZERO,
+        status: UserStatus::Active,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn test_users() -> Vec<User> {
+    vec![
+        test_user(CUSTOMER_ID, UserType::Customer),
+        test_user(OTHER_CUSTOMER_ID, UserType::Customer),
+        test_user(WORKER_ID, UserType::Worker),
+        test_user(ADMIN_ID, UserType::Admin),
+    ]
+}
+
+fn test_order(order_id: &str, customer_id: i32, worker_id: Option<i32>, status: OrderStatus) -> Order {
+    let now = chrono::Local::now().naive_local();
+    Order {
+        order_id: order_id.to_string(),
+        customer_id,
+        worker_id,
+        address_id: 1,
+        service_id: 1,
+        coupon_id: None,
+        service_date: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+        time_slot: TimeSlot::Morning,
+        duration: 2.0,
+        unit_price: Money::from_yuan(50),
+        subtotal: Money::from_yuan(100),
+        discount_amount: Money::ZERO,
+        total_amount: Money::from_yuan(100),
+        payment_status: OrderPaymentStatus::Pending,
+        order_status: status,
+        special_instructions: None,
+        cancellation_reason: None,
+        scheduled_start_time: None,
+        actual_start_time: None,
+        actual_end_time: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// 按小时计价的服务，50 元/小时，可选 2 到 8 小时，带一个 20 元的附加项
+fn test_catalog() -> InMemoryServiceStore {
+    let now = chrono::Utc::now().naive_utc();
+    let service = Service {
+        service_id: 1,
+        category_id: 1,
+        service_name: "日常保洁".to_string(),
+        description: None,
+        base_price: Money::from_yuan(50),
+        unit: ServiceUnit::Hour,
+        min_duration: 2,
+        max_duration: 8,
+        is_active: true,
+        created_at: now,
+    };
+    let addon = ServiceAddon {
+        addon_id: 1,
+        service_id: 1,
+        addon_name: "冰箱清洁".to_string(),
+        addon_price: Money::from_yuan(20),
+        is_active: true,
+        created_at: now,
+    };
+    InMemoryServiceStore::with_catalog(vec![service], vec![addon])
+}
+
+fn test_address(address_id: i32, user_id: i32) -> UserAddress {
+    UserAddress {
+        address_id,
+        user_id,
+        contact_name: "张三".to_string(),
+        contact_phone: "13800000000".to_string(),
+        province: "广东省".to_string(),
+        city: "深圳市".to_string(),
+        district: "南山区".to_string(),
+        street_address: "科技园1号".to_string(),
+        is_default: true,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+/// 按 main.rs 的方式组装用户和订单路由
+fn test_app(components: Components) -> Router {
+    let pool = MySqlPoolOptions::new()
+        .acquire_timeout(Duration::from_secs(1))
+        .connect_lazy("mysql://jz:jz@127.0.0.1:1/jz_test")
+        .unwrap();
+    let state = AppState::with_components(pool, test_config(), components);
+
+    let api_routes = Router::new()
+        .nest("/users", user_routes())
+        .nest("/orders", order_routes())
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth_interceptor))
+        .with_state(state);
+
+    Router::new().nest("/api", api_routes)
+}
+
+fn app_with_orders(orders: Vec<Order>) -> Router {
+    let config = test_config();
+    test_app(Components {
+        user_store: Arc::new(InMemoryUserStore::with_users(test_users())),
+        service_store: Arc::new(test_catalog()),
+        ..Components::in_memory_with(
+            &config,
+            orders,
+            vec![test_address(1, CUSTOMER_ID), test_address(2, OTHER_CUSTOMER_ID)],
+        )
+    })
+}
+
+fn token_for(user_id: i32) -> String {
+    let user = test_users().into_iter().find(|u| u.user_id == user_id).unwrap();
+    Claims::new(&user, "access", 3600).generate_token(TEST_SECRET).unwrap()
+}
+
+async fn send(app: &Router, method: http::Method, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        builder = builder.header(http::header::AUTHORIZATION, format!("Bearer {}", token));
     }
+    let body = match body {
+        Some(body) => {
+            builder = builder.header(http::header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = app.clone().oneshot(builder.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
 }
 
+// 注册、登录、刷新令牌和查看本人信息
 #[tokio::test]
 async fn test_user_registration_integration() {
-    // 构建应用
-    let app = Router::new()
-        .nest("/users", test_utils::user_routes());
-
-    // 构造注册请求
-    let response = app
-        .oneshot(
-            Request::builder()
-                .method(http::Method::POST)
-                .uri("/users/register")
-                .header(http::header::CONTENT_TYPE, "application/json")
-                .body(Body::from(
-                    serde_json::to_vec(&json!({
-                        "username": "testuser",
-                        "email": "test@example.com",
-                        "phone": "1234567890",
-                        "password": "password123"
-                    }))
-                    .unwrap(),
-                ))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let app = test_app(Components::in_memory(&test_config()));
+    let register = json!({
+        "username": "testuser",
+        "email": "test@example.com",
+        "phone": "1234567890",
+        "password": "password123"
+    });
+
+    let (status, body) = send(&app, http::Method::POST, "/api/users/register", None, Some(register.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["user"]["username"], "testuser");
+    assert!(body["user"].get("password_hash").is_none());
 
-    assert_eq!(response.status(), StatusCode::CREATED);
-} 
+    let (status, body) = send(&app, http::Method::POST, "/api/users/register", None, Some(register)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "ALREADY_EXISTS");
+}
 
 #[tokio::test]
 async fn test_user_login_integration() {
-    // 构建应用
-    let app = Router::new()
-        .nest("/users", test_utils::user_routes());
-
-    // 构造登录请求
-    let response = app
-        .oneshot(
-            Request::builder()
-                .method(http::Method::POST)
-                .uri("/users/login")
-                .header(http::header::CONTENT_TYPE, "application/json")
-                .body(Body::from(
-                    serde_json::to_vec(&json!({
-                        "identifier": "testuser",
-                        "password": "password123"
-                    }))
-                    .unwrap(),
-                ))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let app = test_app(Components::in_memory(&test_config()));
+    let register = json!({
+        "username": "testuser",
+        "email": "test@example.com",
+        "phone": "1234567890",
+        "password": "password123"
+    });
+    let (status, body) = send(&app, http::Method::POST, "/api/users/register", None, Some(register)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let user_id = body["user"]["user_id"].as_i64().unwrap();
+
+    let login = json!({ "identifier": "testuser", "password": "wrongpass" });
+    let (status, body) = send(&app, http::Method::POST, "/api/users/login", None, Some(login)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "INVALID_CREDENTIALS");
+
+    let login = json!({ "identifier": "test@example.com", "password": "password123" });
+    let (status, body) = send(&app, http::Method::POST, "/api/users/login", None, Some(login)).await;
+    assert_eq!(status, StatusCode::OK);
+    let token = body["token"].as_str().unwrap().to_string();
+    let refresh_token = body["refresh_token"].as_str().unwrap().to_string();
+
+    // 访问令牌可以访问需要认证的接口
+    let (status, body) = send(&app, http::Method::GET, &format!("/api/users/{}", user_id), Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["email"], "test@example.com");
+
+    // 刷新令牌轮换后旧令牌失效
+    let refresh = json!({ "refresh_token": refresh_token });
+    let (status, body) = send(&app, http::Method::POST, "/api/users/refresh", None, Some(refresh.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(body["refresh_token"].as_str().unwrap(), refresh_token);
+
+    let (status, body) = send(&app, http::Method::POST, "/api/users/refresh", None, Some(refresh)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "TOKEN_INVALID");
+}
+
+// 客户只能看到自己的订单，服务人员看到指派给自己的订单，管理员看到全部订单
+#[tokio::test]
+async fn test_order_visibility_integration() {
+    let app = app_with_orders(vec![
+        test_order("20300101000001", CUSTOMER_ID, Some(WORKER_ID), OrderStatus::Assigned),
+        test_order("20300101000002", OTHER_CUSTOMER_ID, None, OrderStatus::Pending),
+    ]);
+
+    let (status, body) = send(&app, http::Method::GET, "/api/orders", Some(&token_for(CUSTOMER_ID)), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["order_id"], "20300101000001");
+
+    let (_, body) = send(&app, http::Method::GET, "/api/orders", Some(&token_for(WORKER_ID)), None).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (_, body) = send(&app, http::Method::GET, "/api/orders", Some(&token_for(ADMIN_ID)), None).await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    let (status, body) =
+        send(&app, http::Method::GET, "/api/orders/20300101000002", Some(&token_for(CUSTOMER_ID)), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "FORBIDDEN");
+
+    let (status, body) =
+        send(&app, http::Method::GET, "/api/orders/20300101999999", Some(&token_for(ADMIN_ID)), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+}
+
+// 管理员确认订单，客户取消订单，状态历史按顺序记录
+#[tokio::test]
+async fn test_order_status_flow_integration() {
+    let order_id = "20300101000001";
+    let app = app_with_orders(vec![test_order(order_id, CUSTOMER_ID, None, OrderStatus::Pending)]);
+    let customer = token_for(CUSTOMER_ID);
 
-    assert_eq!(response.status(), StatusCode::OK);
-}
\ No newline at end of file
+    // 客户不能确认订单
+    let uri = format!("/api/orders/{}/confirm", order_id);
+    let (status, _) = send(&app, http::Method::POST, &uri, Some(&customer), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(&app, http::Method::POST, &uri, Some(&token_for(ADMIN_ID)), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["order_status"], "confirmed");
+
+    // 未被指派的服务人员不能开始服务
+    let uri = format!("/api/orders/{}/start", order_id);
+    let (status, _) = send(&app, http::Method::POST, &uri, Some(&token_for(WORKER_ID)), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let uri = format!("/api/orders/{}/cancel", order_id);
+    let cancel = json!({ "cancellation_reason": "行程有变" });
+    let (status, body) = send(&app, http::Method::POST, &uri, Some(&customer), Some(cancel.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["order_status"], "cancelled");
+    assert_eq!(body["cancellation_reason"], "行程有变");
+
+    // 已取消的订单不能再次取消
+    let (status, body) = send(&app, http::Method::POST, &uri, Some(&customer), Some(cancel)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "CONFLICT");
+
+    let uri = format!("/api/orders/{}/history", order_id);
+    let (status, body) = send(&app, http::Method::GET, &uri, Some(&customer), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let transitions: Vec<(Value, Value)> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|h| (h["from_status"].clone(), h["to_status"].clone()))
+        .collect();
+    assert_eq!(
+        transitions,
+        vec![(json!("pending"), json!("confirmed")), (json!("confirmed"), json!("cancelled"))]
+    );
+}
+
+// 客户下单、支付后订单变为已支付，金额由服务端按服务目录计算
+#[tokio::test]
+async fn test_order_create_and_pay_integration() {
+    let app = app_with_orders(Vec::new());
+    let customer = token_for(CUSTOMER_ID);
+    let mut create = json!({
+        "service_id": 1,
+        "address_id": 2,
+        "service_date": "2030-01-01",
+        "time_slot": "morning",
+        "duration": 3,
+        "addons": [{ "addon_id": 1 }]
+    });
+
+    // 不能使用别人的地址下单
+    let (status, body) = send(&app, http::Method::POST, "/api/orders", Some(&customer), Some(create.clone())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+
+    create["address_id"] = json!(1);
+    let (status, body) = send(&app, http::Method::POST, "/api/orders", Some(&customer), Some(create)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["total_amount"], "170.00");
+    assert_eq!(body["payment_status"], "pending");
+    assert_eq!(body["order_status"], "pending");
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+
+    let uri = format!("/api/orders/{}/pay", order_id);
+    let pay = json!({ "payment_method": "wechat" });
+    let (status, _) = send(&app, http::Method::POST, &uri, Some(&token_for(OTHER_CUSTOMER_ID)), Some(pay.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(&app, http::Method::POST, &uri, Some(&customer), Some(pay.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["payment_status"], "success");
+    assert_eq!(body["payment_amount"], "170.00");
+
+    // 已支付的订单不能重复支付
+    let (status, body) = send(&app, http::Method::POST, &uri, Some(&customer), Some(pay)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "CONFLICT");
+
+    let (status, body) = send(&app, http::Method::GET, &format!("/api/orders/{}", order_id), Some(&customer), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["payment_status"], "paid");
+
+    let uri = format!("/api/orders/{}/payments", order_id);
+    let (status, body) = send(&app, http::Method::GET, &uri, Some(&customer), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+}
@@ -1,15 +1,217 @@
-// 服务相关测试
+//! 服务相关测试
+//!
+//! 下单和优惠预览按服务目录计价：服务必须上架、时长在允许范围内、附加项属于该服务。
+//! 使用内存的服务目录、优惠券和地址存储组装真实的服务和路由
 
-// 列出服务测试
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{self, Request, StatusCode},
+    middleware, Router,
+};
+use chrono::NaiveDate;
+use jz::{
+    auth_interceptor,
+    config::Config,
+    coupon_routes,
+    models::{service::ServiceUnit, Coupon, DiscountType, Money, Service, ServiceAddon, User, UserAddress, UserStatus, UserType},
+    order_routes,
+    repositories::{InMemoryCouponStore, InMemoryServiceStore},
+    state::{AppState, Components},
+    utils::jwt::Claims,
+};
+use serde_json::{json, Value};
+use sqlx::mysql::MySqlPoolOptions;
+use tower::ServiceExt;
+
+const TEST_SECRET: &str = "service_test_secret";
+
+const CUSTOMER_ID: i32 = 1;
+
+fn customer() -> User {
+    let now = chrono::Utc::now().naive_utc();
+    User {
+        user_id: CUSTOMER_ID,
+        username: "customer".to_string(),
+        password_hash: String::new(),
+        email: "customer@example.com".to_string(),
+        phone: "13800000001".to_string(),
+        user_type: UserType::Customer,
+        avatar_url: None,
+        real_name: None,
+        is_verified: true,
+        balance: Money::ZERO,
+        status: UserStatus::Active,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn service(service_id: i32, base_price: i64, is_active: bool) -> Service {
+    Service {
+        service_id,
+        category_id: 1,
+        service_name: format!("服务{}", service_id),
+        description: None,
+        base_price: Money::from_yuan(base_price),
+        unit: ServiceUnit::Hour,
+        min_duration: 2,
+        max_duration: 8,
+        is_active,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+fn addon(addon_id: i32, service_id: i32, price: i64) -> ServiceAddon {
+    ServiceAddon {
+        addon_id,
+        service_id,
+        addon_name: format!("附加项{}", addon_id),
+        addon_price: Money::from_yuan(price),
+        is_active: true,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+/// 满 100 减 30，仅适用于服务 1
+fn coupon() -> Coupon {
+    let today = chrono::Local::now().date_naive();
+    Coupon {
+        coupon_id: 1,
+        coupon_code: "SAVE30".to_string(),
+        coupon_name: "满100减30".to_string(),
+        discount_type: DiscountType::Fixed,
+        discount_value: Money::from_yuan(30),
+        min_order_amount: Money::from_yuan(100),
+        applicable_services: Some(vec![1]),
+        valid_from: today,
+        valid_until: today + chrono::Duration::days(30),
+        usage_limit: 100,
+        used_count: 0,
+        is_active: true,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+fn address() -> UserAddress {
+    UserAddress {
+        address_id: 1,
+        user_id: CUSTOMER_ID,
+        contact_name: "张三".to_string(),
+        contact_phone: "13800000000".to_string(),
+        province: "广东省".to_string(),
+        city: "深圳市".to_string(),
+        district: "南山区".to_string(),
+        street_address: "科技园1号".to_string(),
+        is_default: true,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+/// 服务 1 (50 元/小时) 带附加项 1，服务 2 已下架，附加项 2 属于服务 2
+fn test_app() -> Router {
+    let mut config = Config::default();
+    config.jwt.secret = TEST_SECRET.to_string();
+    let pool = MySqlPoolOptions::new()
+        .acquire_timeout(Duration::from_secs(1))
+        .connect_lazy("mysql://jz:jz@127.0.0.1:1/jz_test")
+        .unwrap();
+    let components = Components {
+        service_store: Arc::new(InMemoryServiceStore::with_catalog(
+            vec![service(1, 50, true), service(2, 80, false)],
+            vec![addon(1, 1, 20), addon(2, 2, 20)],
+        )),
+        coupon_store: Arc::new(InMemoryCouponStore::with_coupons(vec![coupon()])),
+        ..Components::in_memory_with(&config, Vec::new(), vec![address()])
+    };
+    let state = AppState::with_components(pool, config, components);
+
+    let api_routes = Router::new()
+        .nest("/orders", order_routes())
+        .nest("/coupons", coupon_routes())
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth_interceptor))
+        .with_state(state);
+
+    Router::new().nest("/api", api_routes)
+}
+
+fn token() -> String {
+    Claims::new(&customer(), "access", 3600).generate_token(TEST_SECRET).unwrap()
+}
+
+async fn send(app: &Router, method: http::Method, uri: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(http::header::AUTHORIZATION, format!("Bearer {}", token()))
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+fn order_request(service_id: i32, duration: f64, addons: Value) -> Value {
+    json!({
+        "service_id": service_id,
+        "address_id": 1,
+        "service_date": NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+        "time_slot": "afternoon",
+        "duration": duration,
+        "addons": addons
+    })
+}
+
+// 优惠预览按服务单价、时长和附加项计价，领取优惠券后才能使用
 #[tokio::test]
-async fn test_list_services() {
-    // TODO: 实际测试需要数据库支持，这里只是演示测试结构
-    assert_eq!(2 + 2, 4);
+async fn test_price_preview_from_catalog() {
+    let app = test_app();
+    let preview = json!({
+        "service_id": 1,
+        "duration": 2,
+        "addons": [{ "addon_id": 1, "quantity": 2 }],
+        "coupon_code": "SAVE30"
+    });
+
+    let (status, body) = send(&app, http::Method::POST, "/api/coupons/preview", preview.clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+
+    let (status, _) = send(&app, http::Method::POST, "/api/coupons/1/claim", Value::Null).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = send(&app, http::Method::POST, "/api/coupons/preview", preview).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["service_amount"], "100.00");
+    assert_eq!(body["addon_amount"], "40.00");
+    assert_eq!(body["discount_amount"], "30.00");
+    assert_eq!(body["total_amount"], "110.00");
 }
 
-// 获取单个服务测试
+// 下架的服务、超出范围的时长和不属于该服务的附加项都不能下单
 #[tokio::test]
-async fn test_get_service() {
-    // TODO: 实际测试需要数据库支持，这里只是演示测试结构
-    assert_eq!(2 + 2, 4);
-}
\ No newline at end of file
+async fn test_order_validates_catalog() {
+    let app = test_app();
+
+    let (status, body) = send(&app, http::Method::POST, "/api/orders", order_request(2, 2.0, json!([]))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    let (status, body) = send(&app, http::Method::POST, "/api/orders", order_request(1, 9.0, json!([]))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+
+    let addons = json!([{ "addon_id": 2 }]);
+    let (status, body) = send(&app, http::Method::POST, "/api/orders", order_request(1, 2.0, addons)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+
+    let (status, body) = send(&app, http::Method::POST, "/api/orders", order_request(1, 2.5, json!([]))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["unit_price"], "50.00");
+    assert_eq!(body["total_amount"], "125.00");
+}
@@ -1,31 +1,207 @@
+//! 用户相关测试
+//!
+//! 使用内存存储组装真实的服务和路由，覆盖注册、登录、用户查询权限和地址管理
+
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
     body::Body,
     http::{self, Request, StatusCode},
-    routing::post,
-    Router,
+    middleware, Router,
+};
+use jz::{
+    auth_interceptor,
+    config::Config,
+    models::{Money, User, UserStatus, UserType},
+    repositories::InMemoryUserStore,
+    state::{AppState, Components},
+    user_routes,
+    utils::jwt::Claims,
 };
-use serde_json::json;
-use std::net::SocketAddr;
-use tokio::net::TcpListener;
-use tower::ServiceExt; // for `call`, `oneshot`, and `ready`
+use serde_json::{json, Value};
+use sqlx::mysql::MySqlPoolOptions;
+use tower::ServiceExt;
+
+const TEST_SECRET: &str = "user_test_secret";
+
+const ADMIN_ID: i32 = 1;
+
+fn admin() -> User {
+    let now = chrono::Utc::now().naive_utc();
+    User {
+        user_id: ADMIN_ID,
+        username: "admin".to_string(),
+        password_hash: String::new(),
+        email: "admin@example.com".to_string(),
+        phone: "13800000001".to_string(),
+        user_type: UserType::Admin,
+        avatar_url: None,
+        real_name: None,
+        is_verified: true,
+        balance: Money::ZERO,
+        status: UserStatus::Active,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn test_app() -> Router {
+    let mut config = Config::default();
+    config.jwt.secret = TEST_SECRET.to_string();
+    let pool = MySqlPoolOptions::new()
+        .acquire_timeout(Duration::from_secs(1))
+        .connect_lazy("mysql://jz:jz@127.0.0.1:1/jz_test")
+        .unwrap();
+    let components = Components {
+        user_store: Arc::new(InMemoryUserStore::with_users(vec![admin()])),
+        ..Components::in_memory(&config)
+    };
+    let state = AppState::with_components(pool, config, components);
+
+    let api_routes = Router::new()
+        .nest("/users", user_routes())
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth_interceptor))
+        .with_state(state);
 
-// 用户注册测试
+    Router::new().nest("/api", api_routes)
+}
+
+async fn send(app: &Router, method: http::Method, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        builder = builder.header(http::header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let body = match body {
+        Some(body) => {
+            builder = builder.header(http::header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = app.clone().oneshot(builder.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// 注册并登录，返回用户ID和访问令牌
+async fn register_and_login(app: &Router, username: &str) -> (i64, String) {
+    let register = json!({
+        "username": username,
+        "email": format!("{}@example.com", username),
+        "phone": "13912345678",
+        "password": "password123"
+    });
+    let (status, body) = send(app, http::Method::POST, "/api/users/register", None, Some(register)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let user_id = body["user"]["user_id"].as_i64().unwrap();
+
+    let login = json!({ "identifier": username, "password": "password123" });
+    let (status, body) = send(app, http::Method::POST, "/api/users/login", None, Some(login)).await;
+    assert_eq!(status, StatusCode::OK);
+    (user_id, body["token"].as_str().unwrap().to_string())
+}
+
+fn address(contact_name: &str) -> Value {
+    json!({
+        "contact_name": contact_name,
+        "contact_phone": "13800000000",
+        "province": "广东省",
+        "city": "深圳市",
+        "district": "南山区",
+        "street_address": "科技园1号"
+    })
+}
+
+// 用户名、邮箱或手机号已被使用时拒绝注册
 #[tokio::test]
 async fn test_user_registration() {
-    // TODO: 实际测试需要数据库支持，这里只是演示测试结构
-    assert_eq!(2 + 2, 4);
+    let app = test_app();
+    let register = json!({
+        "username": "newuser",
+        "email": "admin@example.com",
+        "phone": "13912345678",
+        "password": "password123"
+    });
+    let (status, body) = send(&app, http::Method::POST, "/api/users/register", None, Some(register)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "ALREADY_EXISTS");
+
+    let (user_id, _) = register_and_login(&app, "newuser").await;
+    assert!(user_id > i64::from(ADMIN_ID));
 }
 
-// 用户登录测试
+// 未知用户和错误密码返回相同的错误码
 #[tokio::test]
 async fn test_user_login() {
-    // TODO: 实际测试需要数据库支持，这里只是演示测试结构
-    assert_eq!(2 + 2, 4);
+    let app = test_app();
+    register_and_login(&app, "newuser").await;
+
+    for identifier in ["nobody", "newuser"] {
+        let login = json!({ "identifier": identifier, "password": "wrongpass" });
+        let (status, body) = send(&app, http::Method::POST, "/api/users/login", None, Some(login)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], "INVALID_CREDENTIALS");
+    }
 }
 
-// 用户列表测试
+// 用户列表仅管理员可见，客户只能查看本人信息
 #[tokio::test]
 async fn test_list_users() {
-    // TODO: 实际测试需要数据库支持，这里只是演示测试结构
-    assert_eq!(2 + 2, 4);
-}
\ No newline at end of file
+    let app = test_app();
+    let (user_id, token) = register_and_login(&app, "newuser").await;
+
+    let (status, body) = send(&app, http::Method::GET, "/api/users", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "UNAUTHORIZED");
+
+    let (status, _) = send(&app, http::Method::GET, "/api/users", Some(&token), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let uri = format!("/api/users/{}", ADMIN_ID);
+    let (status, _) = send(&app, http::Method::GET, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let admin_token = Claims::new(&admin(), "access", 3600).generate_token(TEST_SECRET).unwrap();
+    let (status, body) = send(&app, http::Method::GET, "/api/users", Some(&admin_token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let users = body.as_array().unwrap();
+    assert_eq!(users.len(), 2);
+    assert!(users.iter().any(|u| u["user_id"] == user_id));
+    assert!(users.iter().all(|u| u.get("password_hash").is_none()));
+}
+
+// 第一个地址成为默认地址，删除默认地址后最近添加的地址成为默认
+#[tokio::test]
+async fn test_user_addresses() {
+    let app = test_app();
+    let (_, token) = register_and_login(&app, "newuser").await;
+    let uri = "/api/users/me/addresses";
+
+    let (status, first) = send(&app, http::Method::POST, uri, Some(&token), Some(address("张三"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(first["is_default"], true);
+
+    let (status, second) = send(&app, http::Method::POST, uri, Some(&token), Some(address("李四"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(second["is_default"], false);
+
+    // 不能直接取消默认地址
+    let mut payload = address("张三");
+    payload["is_default"] = json!(false);
+    let first_uri = format!("{}/{}", uri, first["address_id"]);
+    let (status, body) = send(&app, http::Method::PUT, &first_uri, Some(&token), Some(payload)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+
+    let (status, _) = send(&app, http::Method::DELETE, &first_uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, body) = send(&app, http::Method::GET, uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let addresses = body.as_array().unwrap();
+    assert_eq!(addresses.len(), 1);
+    assert_eq!(addresses[0]["address_id"], second["address_id"]);
+    assert_eq!(addresses[0]["is_default"], true);
+}